@@ -0,0 +1,15 @@
+#!/bin/sh
+# Trunk post_build hook: list the files of this build in the service worker's precache,
+# and version it by their contents so browsers notice every new deployment
+set -e
+cd "$TRUNK_STAGING_DIR"
+files=$(find . -type f ! -name sw.js | sed 's|^\.||' | LC_ALL=C sort)
+version=$(for file in $files; do cat ".$file"; done | cksum | cut -d ' ' -f 1)
+list='"/"'
+for file in $files; do
+    list="$list, \"$file\""
+done
+sed -e "s|^const VERSION = .*|const VERSION = \"$version\";|" \
+    -e "s|^const PRECACHE = .*|const PRECACHE = [$list];|" \
+    sw.js > sw.js.tmp
+mv sw.js.tmp sw.js
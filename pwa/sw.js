@@ -0,0 +1,42 @@
+// Keeps the app shell cached so relf starts without a network. Both constants are filled in
+// by pwa/precache.sh when Trunk builds; a new build gets a new cache and waits until the
+// page agrees to reload into it.
+const VERSION = "dev";
+const PRECACHE = ["/", "/index.html"];
+const CACHE = `relf-${VERSION}`;
+
+self.addEventListener("install", (event) => {
+  event.waitUntil(caches.open(CACHE).then((cache) => cache.addAll(PRECACHE)));
+});
+
+// Drop the caches of earlier builds
+self.addEventListener("activate", (event) => {
+  event.waitUntil(
+    caches
+      .keys()
+      .then((keys) => Promise.all(keys.filter((key) => key.startsWith("relf-") && key !== CACHE).map((key) => caches.delete(key))))
+      .then(() => self.clients.claim()),
+  );
+});
+
+// Sent by the update prompt
+self.addEventListener("message", (event) => {
+  if (event.data === "skip-waiting") {
+    self.skipWaiting();
+  }
+});
+
+self.addEventListener("fetch", (event) => {
+  const request = event.request;
+  const url = new URL(request.url);
+  // The server's API and share pages always need the network
+  if (request.method !== "GET" || url.origin !== self.location.origin || url.pathname.startsWith("/api/") || url.pathname.startsWith("/share/")) {
+    return;
+  }
+  // Every route of the app is index.html
+  if (request.mode === "navigate") {
+    event.respondWith(caches.match("/index.html").then((cached) => cached || fetch(request)));
+    return;
+  }
+  event.respondWith(caches.match(request).then((cached) => cached || fetch(request)));
+});
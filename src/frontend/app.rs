@@ -1,11 +1,22 @@
 use yew::prelude::*;
 use yew_router::prelude::*;
 use crate::frontend::services::router::Route;
+use crate::frontend::services::{crypto, snapshot};
+use crate::frontend::services::store::StoreProvider;
+use crate::frontend::components::command_palette::CommandPalette;
+use crate::frontend::components::update_prompt::UpdatePrompt;
 use crate::frontend::pages::{
     home::Home,
     inside::InsidePage,
     outside::OutsidePage,
     data::Data,
+    unlock::Unlock,
+    not_found::NotFound,
+    outside_detail::OutsideDetail,
+    inside_detail::InsideDetail,
+    stats::Stats,
+    shared_view::SharedView,
+    capture::CapturePage,
 };
 
 fn switch(routes: Route) -> Html {
@@ -13,17 +24,56 @@ fn switch(routes: Route) -> Html {
         Route::Home => html! { <Home /> },
         Route::Relf => html! { <Home /> },
         Route::Outside => html! { <OutsidePage /> },
+        Route::OutsideDetail { uuid } => html! { <OutsideDetail uuid={uuid} /> },
+        Route::OutsideEdit { uuid } => html! { <OutsidePage edit={uuid} /> },
         Route::Inside => html! { <InsidePage /> },
+        Route::InsideDetail { uuid } => html! { <InsideDetail uuid={uuid} /> },
+        Route::InsideEdit { uuid } => html! { <InsidePage edit={uuid} /> },
+        Route::Stats => html! { <Stats /> },
         Route::Data => html! { <Data /> },
-        Route::NotFound => html! { <h1>{"Page Not Found"}</h1> },
+        Route::View => html! { <SharedView /> },
+        Route::Capture => html! { <CapturePage /> },
+        Route::NotFound => html! { <NotFound /> },
     }
 }
 
 #[function_component(App)]
 pub fn app() -> Html {
+    let locked = use_state(crypto::is_locked);
+
+    let on_unlock = {
+        let locked = locked.clone();
+        Callback::from(move |_| locked.set(false))
+    };
+
+    // Keep a rotating daily snapshot of the whole document
+    use_effect_with(*locked, |locked| {
+        if !*locked {
+            wasm_bindgen_futures::spawn_local(async {
+                if let Err(e) = snapshot::take_daily_snapshot().await {
+                    web_sys::console::log_1(&format!("Failed to take snapshot: {}", e).into());
+                }
+            });
+        }
+        || ()
+    });
+
+    if *locked {
+        return html! {
+            <>
+                <Unlock on_unlock={on_unlock} />
+                <UpdatePrompt />
+            </>
+        };
+    }
+
     html! {
-        <BrowserRouter>
-            <Switch<Route> render={switch} />
-        </BrowserRouter>
+        <StoreProvider>
+            <BrowserRouter>
+                <Switch<Route> render={switch} />
+                <CommandPalette />
+            </BrowserRouter>
+            <UpdatePrompt />
+        </StoreProvider>
     }
 }
\ No newline at end of file
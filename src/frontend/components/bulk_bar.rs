@@ -0,0 +1,161 @@
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use crate::frontend::services::storage::Transfer;
+use crate::frontend::services::store::use_store;
+use crate::frontend::services::tags::normalize_tag;
+use crate::frontend::services::workspace;
+
+#[derive(Properties, PartialEq)]
+pub struct BulkBarProps {
+    pub count: usize,
+    // Cards shown under the current search and filters
+    pub matching: usize,
+    pub on_select_all: Callback<()>,
+    pub on_clear: Callback<()>,
+    pub on_tag: Callback<String>,
+    // Only Outside resources have a percentage
+    #[prop_or_default]
+    pub on_percentage: Option<Callback<Option<i32>>>,
+    pub on_export: Callback<()>,
+    // Copy a /view link that carries the selection
+    pub on_share: Callback<()>,
+    // Copy or move the selection into the workspace with the given id
+    pub on_transfer: Callback<(String, Transfer)>,
+    pub on_delete: Callback<()>,
+}
+
+fn prompt(message: &str) -> Option<String> {
+    web_sys::window().and_then(|w| w.prompt_with_message(message).ok().flatten())
+}
+
+fn alert(message: &str) {
+    if let Some(window) = web_sys::window() {
+        let _ = window.alert_with_message(message);
+    }
+}
+
+// Actions on the selected cards, shown while any are selected
+#[function_component(BulkBar)]
+pub fn bulk_bar(props: &BulkBarProps) -> Html {
+    let store = use_store();
+    if props.count == 0 {
+        return html! {};
+    }
+
+    let tag = {
+        let on_tag = props.on_tag.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(input) = prompt("Tag to add to the selected entries:") else {
+                return;
+            };
+            match normalize_tag(&input) {
+                Some(tag) => on_tag.emit(tag),
+                None => alert("Please enter a tag made of letters, digits, '-', '_' or '/'."),
+            }
+        })
+    };
+
+    let percentage = props.on_percentage.clone().map(|on_percentage| {
+        Callback::from(move |_: MouseEvent| {
+            let Some(input) = prompt("Percentage for the selected resources (0–100, empty to clear):") else {
+                return;
+            };
+            let input = input.trim();
+            if input.is_empty() {
+                on_percentage.emit(None);
+            } else {
+                match input.trim_end_matches('%').parse::<i32>() {
+                    Ok(p) if (0..=100).contains(&p) => on_percentage.emit(Some(p)),
+                    _ => alert("Please enter a whole number from 0 to 100."),
+                }
+            }
+        })
+    });
+
+    let targets: Vec<_> = workspace::list().into_iter().filter(|w| w.id != store.workspace).collect();
+    let transfer = {
+        let on_transfer = props.on_transfer.clone();
+        let count = props.count;
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let value = select.value();
+            select.set_value("");
+            let Some((action, id)) = value.split_once(':') else {
+                return;
+            };
+            let Some(target) = workspace::find(id) else {
+                return;
+            };
+            if action == "copy" {
+                on_transfer.emit((target.id, Transfer::Copy));
+                return;
+            }
+            let message = format!(
+                "Move {} selected {} to \"{}\"?",
+                count,
+                if count == 1 { "entry" } else { "entries" },
+                target.name
+            );
+            let confirmed = web_sys::window()
+                .and_then(|w| w.confirm_with_message(&message).ok())
+                .unwrap_or(false);
+            if confirmed {
+                on_transfer.emit((target.id, Transfer::Move));
+            }
+        })
+    };
+
+    let delete = {
+        let on_delete = props.on_delete.clone();
+        let count = props.count;
+        Callback::from(move |_: MouseEvent| {
+            let message = format!("Delete {} selected {}?", count, if count == 1 { "entry" } else { "entries" });
+            let confirmed = web_sys::window()
+                .and_then(|w| w.confirm_with_message(&message).ok())
+                .unwrap_or(false);
+            if confirmed {
+                on_delete.emit(());
+            }
+        })
+    };
+
+    html! {
+        <div class="bulk-bar">
+            <span class="bulk-count">{format!("{} selected", props.count)}</span>
+            if props.count < props.matching {
+                <button class="bulk-link" onclick={props.on_select_all.reform(|_| ())}>
+                    {format!("Select all {}", props.matching)}
+                </button>
+            }
+            <button class="bulk-link" onclick={props.on_clear.reform(|_| ())}>{"Clear"}</button>
+            <div class="bulk-actions">
+                <button class="view-button" onclick={tag} title="Add a tag">{"🏷️"}</button>
+                if let Some(percentage) = percentage {
+                    <button class="view-button" onclick={percentage} title="Set percentage">{"%"}</button>
+                }
+                <button class="view-button" onclick={props.on_export.reform(|_| ())} title="Export selection">{"💾"}</button>
+                <button class="view-button" onclick={props.on_share.reform(|_| ())} title="Copy a share link">{"🔗"}</button>
+                if !targets.is_empty() {
+                    <select class="bulk-transfer" title="Copy or move to another workspace" onchange={transfer}>
+                        <option value="" selected=true>{"📂"}</option>
+                        <optgroup label="Copy to">
+                            {
+                                targets.iter().map(|w| html! {
+                                    <option value={format!("copy:{}", w.id)}>{&w.name}</option>
+                                }).collect::<Html>()
+                            }
+                        </optgroup>
+                        <optgroup label="Move to">
+                            {
+                                targets.iter().map(|w| html! {
+                                    <option value={format!("move:{}", w.id)}>{&w.name}</option>
+                                }).collect::<Html>()
+                            }
+                        </optgroup>
+                    </select>
+                }
+                <button class="view-button" onclick={delete} title="Delete selection">{"🗑️"}</button>
+            </div>
+        </div>
+    }
+}
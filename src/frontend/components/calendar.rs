@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use chrono::{Datelike, Days, Local, Months, NaiveDate};
+use yew::prelude::*;
+use crate::frontend::services::related::excerpt;
+use crate::frontend::services::stats::week_start;
+use crate::frontend::services::store::use_store;
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const PREVIEWS_PER_DAY: usize = 2;
+
+// First day of the month containing `day`
+pub fn month_of(day: NaiveDate) -> NaiveDate {
+    day.with_day(1).unwrap_or(day)
+}
+
+#[derive(Properties, PartialEq)]
+pub struct CalendarProps {
+    // Any day of the month to show
+    pub month: NaiveDate,
+    pub selected: Option<NaiveDate>,
+    pub on_select: Callback<NaiveDate>,
+    pub on_month: Callback<NaiveDate>,
+}
+
+// Month grid of Inside entries, weeks starting on Monday
+#[function_component(Calendar)]
+pub fn calendar(props: &CalendarProps) -> Html {
+    let store = use_store();
+    let by_day = use_memo(store.revision, |_| {
+        let mut by_day: HashMap<NaiveDate, Vec<String>> = HashMap::new();
+        let mut insides: Vec<_> = store.inside.iter().collect();
+        insides.sort_by_key(|i| i.date);
+        for inside in insides {
+            by_day
+                .entry(inside.date.day())
+                .or_default()
+                .push(excerpt(&inside.context, 40));
+        }
+        by_day
+    });
+
+    let first = month_of(props.month);
+    let today = Local::now().date_naive();
+    let start = week_start(first);
+    let next_month = first + Months::new(1);
+    let weeks = ((next_month - start).num_days() as u64).div_ceil(7);
+
+    let shift = |months: i32| {
+        let on_month = props.on_month.clone();
+        Callback::from(move |_: MouseEvent| {
+            let month = if months < 0 {
+                first - Months::new(months.unsigned_abs())
+            } else {
+                first + Months::new(months as u32)
+            };
+            on_month.emit(month);
+        })
+    };
+    let go_today = {
+        let on_month = props.on_month.clone();
+        Callback::from(move |_: MouseEvent| on_month.emit(month_of(today)))
+    };
+
+    html! {
+        <div class="calendar">
+            <div class="calendar-header">
+                <button class="view-button" onclick={shift(-1)} title="Previous month">{"‹"}</button>
+                <h2 class="calendar-title">{first.format("%B %Y").to_string()}</h2>
+                <button class="view-button" onclick={shift(1)} title="Next month">{"›"}</button>
+                <button class="calendar-today" onclick={go_today}>{"Today"}</button>
+            </div>
+            <div class="calendar-grid">
+                {
+                    WEEKDAYS.iter().map(|name| html! {
+                        <div class="calendar-weekday">{*name}</div>
+                    }).collect::<Html>()
+                }
+                {
+                    (0..weeks * 7).map(|offset| {
+                        let day = start + Days::new(offset);
+                        let entries = by_day.get(&day).map(Vec::as_slice).unwrap_or_default();
+                        let onclick = {
+                            let on_select = props.on_select.clone();
+                            Callback::from(move |_: MouseEvent| on_select.emit(day))
+                        };
+                        let class = classes!(
+                            "calendar-day",
+                            (day.month() != first.month()).then_some("outside-month"),
+                            (day == today).then_some("today"),
+                            (Some(day) == props.selected).then_some("selected"),
+                            (!entries.is_empty()).then_some("has-entries"),
+                        );
+                        html! {
+                            <div class={class} onclick={onclick}>
+                                <div class="calendar-day-header">
+                                    <span class="calendar-day-number">{day.day()}</span>
+                                    if !entries.is_empty() {
+                                        <span class="calendar-count">{entries.len()}</span>
+                                    }
+                                </div>
+                                {
+                                    entries.iter().take(PREVIEWS_PER_DAY).map(|text| html! {
+                                        <div class="calendar-preview">{text}</div>
+                                    }).collect::<Html>()
+                                }
+                                if entries.len() > PREVIEWS_PER_DAY {
+                                    <div class="calendar-more">{format!("+{} more", entries.len() - PREVIEWS_PER_DAY)}</div>
+                                }
+                            </div>
+                        }
+                    }).collect::<Html>()
+                }
+            </div>
+        </div>
+    }
+}
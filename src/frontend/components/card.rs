@@ -4,13 +4,15 @@ use yew::prelude::*;
 pub struct CardProps {
     pub children: Children,
     #[prop_or_default]
-    pub class: String,
+    pub class: Classes,
+    #[prop_or_default]
+    pub id: Option<AttrValue>,
 }
 
 #[function_component(Card)]
 pub fn card(props: &CardProps) -> Html {
     html! {
-        <div class={props.class.clone()}>
+        <div class={props.class.clone()} id={props.id.clone()}>
             {props.children.clone()}
         </div>
     }
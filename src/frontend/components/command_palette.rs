@@ -0,0 +1,299 @@
+use gloo::events::{EventListener, EventListenerOptions};
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, KeyboardEvent};
+use yew::prelude::*;
+use yew_router::prelude::*;
+use crate::frontend::services::related::excerpt;
+use crate::frontend::services::router::Route;
+use crate::frontend::services::shortcuts::{self, PageCommand, Shortcut, GO_TO};
+use crate::frontend::services::storage;
+use crate::frontend::services::store::use_store;
+
+// How long a `g` waits for the key naming the page
+const G_TIMEOUT_MS: f64 = 1500.0;
+const MAX_RECORDS: usize = 8;
+
+#[derive(Clone, PartialEq)]
+enum Action {
+    Page(PageCommand),
+    Go(Route),
+    Export,
+    Import,
+}
+
+#[derive(Clone, PartialEq)]
+struct Item {
+    label: String,
+    hint: String,
+    action: Action,
+}
+
+fn matches(label: &str, query: &str) -> bool {
+    let label = label.to_lowercase();
+    query.split_whitespace().all(|term| label.contains(&term.to_lowercase()))
+}
+
+// Global keyboard shortcuts and the Ctrl+K command palette
+#[function_component(CommandPalette)]
+pub fn command_palette() -> Html {
+    let store = use_store();
+    let navigator = use_navigator();
+    let open = use_state(|| false);
+    let query = use_state(String::new);
+    let selected = use_state(|| 0usize);
+    let pending_g = use_mut_ref(|| None::<f64>);
+    let file_input_ref = use_node_ref();
+
+    let show = {
+        let open = open.clone();
+        let query = query.clone();
+        let selected = selected.clone();
+        Callback::from(move |_: ()| {
+            query.set(String::new());
+            selected.set(0);
+            open.set(true);
+        })
+    };
+
+    {
+        let show = show.clone();
+        let navigator = navigator.clone();
+        use_effect_with((), move |_| {
+            let document = web_sys::window().and_then(|w| w.document());
+            let listener = document.map(|document| {
+                EventListener::new_with_options(
+                    &document,
+                    "keydown",
+                    EventListenerOptions::enable_prevent_default(),
+                    move |event| {
+                        let Some(event) = event.dyn_ref::<KeyboardEvent>() else {
+                            return;
+                        };
+                        if shortcuts::is_palette_key(event) {
+                            event.prevent_default();
+                            show.emit(());
+                            return;
+                        }
+                        if event.ctrl_key() || event.meta_key() || event.alt_key() || shortcuts::is_typing(event) {
+                            return;
+                        }
+                        let key = event.key();
+                        let now = js_sys::Date::now();
+                        let after_g = pending_g.borrow_mut().take().is_some_and(|at| now - at < G_TIMEOUT_MS);
+                        if key == "g" && !after_g {
+                            *pending_g.borrow_mut() = Some(now);
+                            return;
+                        }
+                        match shortcuts::shortcut_for(&key, after_g) {
+                            Some(Shortcut::Page(command)) if shortcuts::run(command) => event.prevent_default(),
+                            Some(Shortcut::Go(route)) => {
+                                if let Some(navigator) = &navigator {
+                                    navigator.push(&route);
+                                }
+                            }
+                            Some(Shortcut::Palette) => show.emit(()),
+                            _ => {}
+                        }
+                    },
+                )
+            });
+            move || drop(listener)
+        });
+    }
+
+    // Focus the search field whenever the palette opens
+    use_effect_with(*open, |open| {
+        if *open {
+            shortcuts::focus("palette-input");
+        }
+        || ()
+    });
+
+    let mut items: Vec<Item> = shortcuts::page_commands()
+        .iter()
+        .map(|command| Item {
+            label: command.label().to_string(),
+            hint: command.key().to_string(),
+            action: Action::Page(*command),
+        })
+        .collect();
+    items.extend(GO_TO.iter().map(|(key, name, route)| Item {
+        label: format!("Go to {}", name),
+        hint: format!("g {}", key),
+        action: Action::Go(route.clone()),
+    }));
+    items.push(Item {
+        label: "Export data as JSON".to_string(),
+        hint: String::new(),
+        action: Action::Export,
+    });
+    items.push(Item {
+        label: "Import data from a JSON file".to_string(),
+        hint: String::new(),
+        action: Action::Import,
+    });
+    items.retain(|item| matches(&item.label, &query));
+    if !query.trim().is_empty() {
+        let outsides = store
+            .outside
+            .iter()
+            .filter(|o| matches(&o.name, &query))
+            .map(|o| Item {
+                label: o.name.clone(),
+                hint: "Outside".to_string(),
+                action: Action::Go(Route::OutsideDetail { uuid: o.uuid.clone() }),
+            });
+        let insides = store
+            .inside
+            .iter()
+            .filter(|i| matches(&i.context, &query) || matches(&i.date.to_string(), &query))
+            .map(|i| Item {
+                label: excerpt(&i.context, 60),
+                hint: i.date.to_string(),
+                action: Action::Go(Route::InsideDetail { uuid: i.uuid.clone() }),
+            });
+        items.extend(outsides.chain(insides).take(MAX_RECORDS));
+    }
+    let selected_index = (*selected).min(items.len().saturating_sub(1));
+
+    let close = {
+        let open = open.clone();
+        Callback::from(move |_: MouseEvent| open.set(false))
+    };
+
+    let run = {
+        let open = open.clone();
+        let navigator = navigator.clone();
+        let file_input_ref = file_input_ref.clone();
+        Callback::from(move |action: Action| {
+            open.set(false);
+            match action {
+                Action::Page(command) => {
+                    shortcuts::run(command);
+                }
+                Action::Go(route) => {
+                    if let Some(navigator) = &navigator {
+                        navigator.push(&route);
+                    }
+                }
+                Action::Export => storage::download_json(),
+                Action::Import => {
+                    if let Some(input) = file_input_ref.cast::<HtmlInputElement>() {
+                        input.click();
+                    }
+                }
+            }
+        })
+    };
+
+    let on_input = {
+        let query = query.clone();
+        let selected = selected.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            query.set(input.value());
+            selected.set(0);
+        })
+    };
+
+    let on_keydown = {
+        let open = open.clone();
+        let selected = selected.clone();
+        let run = run.clone();
+        let items = items.clone();
+        Callback::from(move |e: KeyboardEvent| match e.key().as_str() {
+            "ArrowDown" => {
+                e.prevent_default();
+                selected.set((selected_index + 1).min(items.len().saturating_sub(1)));
+            }
+            "ArrowUp" => {
+                e.prevent_default();
+                selected.set(selected_index.saturating_sub(1));
+            }
+            "Enter" => {
+                e.prevent_default();
+                if let Some(item) = items.get(selected_index) {
+                    run.emit(item.action.clone());
+                }
+            }
+            "Escape" => open.set(false),
+            _ => {}
+        })
+    };
+
+    let on_file_change = {
+        let store = store.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Some(file) = input.files().and_then(|files| files.get(0))
+                && shortcuts::confirm("Replace all data with the contents of this file?")
+            {
+                let store = store.clone();
+                storage::read_text_file(&file, move |text| match storage::import_from_json(&text) {
+                    Ok(_) => {
+                        store.reload();
+                        web_sys::console::log_1(&"Data imported successfully from file!".into());
+                    }
+                    Err(e) => {
+                        web_sys::console::log_1(&format!("Import failed: {}", e).into());
+                        if let Some(window) = web_sys::window() {
+                            let _ = window.alert_with_message(&format!("Import failed: {}", e));
+                        }
+                    }
+                });
+            }
+            input.set_value("");
+        })
+    };
+
+    html! {
+        <>
+            <input type="file" accept=".json" class="palette-file" ref={file_input_ref} onchange={on_file_change} />
+            if *open {
+                <div class="palette-overlay" onclick={close}>
+                    <div class="palette" onclick={Callback::from(|e: MouseEvent| e.stop_propagation())}>
+                        <input
+                            type="text"
+                            id="palette-input"
+                            class="palette-input"
+                            placeholder="Type a command or search entries…"
+                            value={(*query).clone()}
+                            oninput={on_input}
+                            onkeydown={on_keydown}
+                        />
+                        <ul class="palette-items">
+                            {
+                                items.iter().enumerate().map(|(index, item)| {
+                                    let onclick = {
+                                        let run = run.clone();
+                                        let action = item.action.clone();
+                                        Callback::from(move |_: MouseEvent| run.emit(action.clone()))
+                                    };
+                                    let onmouseenter = {
+                                        let selected = selected.clone();
+                                        Callback::from(move |_: MouseEvent| selected.set(index))
+                                    };
+                                    html! {
+                                        <li
+                                            class={classes!("palette-item", (index == selected_index).then_some("selected"))}
+                                            onclick={onclick}
+                                            onmouseenter={onmouseenter}
+                                        >
+                                            <span class="palette-label">{&item.label}</span>
+                                            if !item.hint.is_empty() {
+                                                <kbd class="palette-hint">{&item.hint}</kbd>
+                                            }
+                                        </li>
+                                    }
+                                }).collect::<Html>()
+                            }
+                            if items.is_empty() {
+                                <li class="palette-empty">{"Nothing matches."}</li>
+                            }
+                        </ul>
+                    </div>
+                </div>
+            }
+        </>
+    }
+}
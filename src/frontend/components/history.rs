@@ -0,0 +1,51 @@
+use yew::prelude::*;
+
+// One field that an edit changed
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistoryEntry {
+    pub replaced_at: String,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct HistoryProps {
+    pub entries: Vec<HistoryEntry>,
+}
+
+#[function_component(History)]
+pub fn history(props: &HistoryProps) -> Html {
+    if props.entries.is_empty() {
+        return html! { <p class="detail-empty">{"No edits yet."}</p> };
+    }
+
+    html! {
+        <ul class="history-list">
+            {
+                props.entries.iter().map(|entry| html! {
+                    <li class="history-item">
+                        <span class="history-date">{format!("Edited {}", entry.replaced_at)}</span>
+                        if entry.changes.is_empty() {
+                            <span class="history-unchanged">{"Saved without changes"}</span>
+                        }
+                        {
+                            entry.changes.iter().map(|change| html! {
+                                <div class="history-change">
+                                    <span class="history-field">{change.field}</span>
+                                    <div class="history-before">{&change.before}</div>
+                                    <div class="history-after">{&change.after}</div>
+                                </div>
+                            }).collect::<Html>()
+                        }
+                    </li>
+                }).collect::<Html>()
+            }
+        </ul>
+    }
+}
@@ -0,0 +1,99 @@
+use wasm_bindgen::JsCast;
+use yew::prelude::*;
+use yew_router::prelude::*;
+use crate::frontend::services::router::Route;
+use crate::frontend::services::store::use_store;
+use crate::frontend::services::{markdown, wiki};
+
+#[derive(Properties, PartialEq)]
+pub struct MarkdownProps {
+    pub source: String,
+    #[prop_or_default]
+    pub class: Classes,
+}
+
+// Context rendered from Markdown; the HTML is sanitized by `markdown::render`
+#[function_component(Markdown)]
+pub fn markdown_view(props: &MarkdownProps) -> Html {
+    let store = use_store();
+    let navigator = use_navigator();
+    let rendered = {
+        let store = store.clone();
+        use_memo((props.source.clone(), store.revision), move |(source, _)| {
+            markdown::render(source, &|target| {
+                wiki::resolve(target, &store.outside, &store.inside).map(|route| route.to_path())
+            })
+        })
+    };
+
+    // Follow wiki links inside the app instead of reloading the page
+    let onclick = Callback::from(move |e: MouseEvent| {
+        let Some(link) = e
+            .target()
+            .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+            .and_then(|el| el.closest("a.wiki-link").ok().flatten())
+        else {
+            return;
+        };
+        let route = link.get_attribute("href").and_then(|href| Route::recognize(&href));
+        if let (Some(route), Some(navigator)) = (route, &navigator) {
+            e.prevent_default();
+            navigator.push(&route);
+        }
+    });
+
+    html! {
+        <div class={classes!("markdown", props.class.clone())} onclick={onclick}>
+            {Html::from_html_unchecked(AttrValue::from((*rendered).clone()))}
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct MarkdownEditorProps {
+    pub id: AttrValue,
+    pub value: String,
+    pub oninput: Callback<InputEvent>,
+    #[prop_or_default]
+    pub rows: Option<AttrValue>,
+}
+
+// Textarea with a toggle to preview the rendered Markdown
+#[function_component(MarkdownEditor)]
+pub fn markdown_editor(props: &MarkdownEditorProps) -> Html {
+    let preview = use_state(|| false);
+
+    let show_edit = {
+        let preview = preview.clone();
+        Callback::from(move |_: MouseEvent| preview.set(false))
+    };
+
+    let show_preview = {
+        let preview = preview.clone();
+        Callback::from(move |_: MouseEvent| preview.set(true))
+    };
+
+    html! {
+        <div class="markdown-editor">
+            <div class="markdown-tabs">
+                <button type="button" class={classes!("markdown-tab", (!*preview).then_some("active"))} onclick={show_edit}>
+                    {"Edit"}
+                </button>
+                <button type="button" class={classes!("markdown-tab", preview.then_some("active"))} onclick={show_preview}>
+                    {"Preview"}
+                </button>
+            </div>
+            if *preview {
+                <Markdown source={props.value.clone()} class="markdown-preview" />
+            } else {
+                <textarea
+                    id={props.id.clone()}
+                    required=true
+                    value={props.value.clone()}
+                    oninput={props.oninput.clone()}
+                    rows={props.rows.clone()}
+                />
+            }
+        </div>
+    }
+}
@@ -1,3 +1,15 @@
 pub mod navigation;
 pub mod card;
-pub mod modal;
\ No newline at end of file
+pub mod modal;
+pub mod view_menu;
+pub mod history;
+pub mod markdown;
+pub mod outside_picker;
+pub mod calendar;
+pub mod timeline;
+pub mod command_palette;
+pub mod bulk_bar;
+pub mod workspace_switcher;
+pub mod server_sync;
+pub mod quick_capture;
+pub mod update_prompt;
@@ -1,5 +1,6 @@
 use yew::prelude::*;
 use yew_router::prelude::*;
+use crate::frontend::components::workspace_switcher::WorkspaceSwitcher;
 use crate::frontend::services::router::Route;
 
 #[derive(Properties, PartialEq)]
@@ -40,12 +41,18 @@ pub fn navigation(_props: &NavigationProps) -> Html {
         <>
             <div class={classes!("mobile-nav", (*mobile_nav_open).then_some("active"))}>
                 <ul>
+                    <li class="mobile-workspace">
+                        <WorkspaceSwitcher />
+                    </li>
                     <li onclick={close_mobile_nav.clone()}>
                         <Link<Route> to={Route::Outside}>{"OUTSIDE"}</Link<Route>>
                     </li>
                     <li onclick={close_mobile_nav.clone()}>
                         <Link<Route> to={Route::Inside}>{"INSIDE"}</Link<Route>>
                     </li>
+                    <li onclick={close_mobile_nav.clone()}>
+                        <Link<Route> to={Route::Stats}>{"STATS"}</Link<Route>>
+                    </li>
                     <li onclick={close_mobile_nav.clone()}>
                         <Link<Route> to={Route::Data}>{"DATA"}</Link<Route>>
                     </li>
@@ -60,14 +67,20 @@ pub fn navigation(_props: &NavigationProps) -> Html {
                     <span></span>
                     <span></span>
                 </div>
+                <div class="nav-workspace">
+                    <WorkspaceSwitcher />
+                </div>
                 <div class="nav-title logo-clickable" onclick={go_home}>{"Relf"}</div>
                 <ul>
-                    <li class={if matches!(route, Route::Outside) { "active" } else { "" }}>
+                    <li class={if route.is_outside() { "active" } else { "" }}>
                         <Link<Route> to={Route::Outside}>{"OUTSIDE"}</Link<Route>>
                     </li>
-                    <li class={if matches!(route, Route::Inside) { "active" } else { "" }}>
+                    <li class={if route.is_inside() { "active" } else { "" }}>
                         <Link<Route> to={Route::Inside}>{"INSIDE"}</Link<Route>>
                     </li>
+                    <li class={if matches!(route, Route::Stats) { "active" } else { "" }}>
+                        <Link<Route> to={Route::Stats}>{"STATS"}</Link<Route>>
+                    </li>
                     <li class={if matches!(route, Route::Data) { "active" } else { "" }}>
                         <Link<Route> to={Route::Data}>{"DATA"}</Link<Route>>
                     </li>
@@ -0,0 +1,121 @@
+use yew::prelude::*;
+use yew_router::prelude::*;
+use crate::frontend::services::router::Route;
+use crate::frontend::services::store::use_store;
+
+const MAX_RESULTS: usize = 8;
+
+#[derive(Properties, PartialEq)]
+pub struct LinkedOutsidesProps {
+    pub uuids: Vec<String>,
+}
+
+// Outside resources an Inside entry is about, as links to their pages
+#[function_component(LinkedOutsides)]
+pub fn linked_outsides(props: &LinkedOutsidesProps) -> Html {
+    let store = use_store();
+    let outsides: Vec<_> = props.uuids.iter().filter_map(|uuid| store.find_outside(uuid)).collect();
+    if outsides.is_empty() {
+        return html! {};
+    }
+
+    html! {
+        <div class="linked-outsides">
+            {
+                outsides.iter().map(|o| html! {
+                    <Link<Route> to={Route::OutsideDetail { uuid: o.uuid.clone() }} classes="outside-chip">
+                        {&o.name}
+                    </Link<Route>>
+                }).collect::<Html>()
+            }
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct OutsidePickerProps {
+    pub selected: Vec<String>,
+    pub on_change: Callback<Vec<String>>,
+}
+
+// Search box for linking Outside resources to an Inside entry
+#[function_component(OutsidePicker)]
+pub fn outside_picker(props: &OutsidePickerProps) -> Html {
+    let store = use_store();
+    let query = use_state(String::new);
+
+    let on_query_change = {
+        let query = query.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            query.set(input.value());
+        })
+    };
+
+    let needle = query.trim().to_lowercase();
+    let results: Vec<_> = if needle.is_empty() {
+        Vec::new()
+    } else {
+        store
+            .outside
+            .iter()
+            .filter(|o| !props.selected.contains(&o.uuid) && o.name.to_lowercase().contains(&needle))
+            .take(MAX_RESULTS)
+            .collect()
+    };
+
+    html! {
+        <div class="outside-picker">
+            <div class="linked-outsides">
+                {
+                    props.selected.iter().filter_map(|uuid| store.find_outside(uuid)).map(|o| {
+                        let remove = {
+                            let selected = props.selected.clone();
+                            let on_change = props.on_change.clone();
+                            let uuid = o.uuid.clone();
+                            Callback::from(move |_: MouseEvent| {
+                                on_change.emit(selected.iter().filter(|u| **u != uuid).cloned().collect());
+                            })
+                        };
+                        html! {
+                            <span class="outside-chip" key={o.uuid.clone()}>
+                                {&o.name}
+                                <button type="button" class="outside-chip-remove" onclick={remove}>{"×"}</button>
+                            </span>
+                        }
+                    }).collect::<Html>()
+                }
+            </div>
+            <input
+                type="text"
+                class="outside-picker-search"
+                placeholder="Search resources to link…"
+                value={(*query).clone()}
+                oninput={on_query_change}
+            />
+            if !results.is_empty() {
+                <ul class="outside-picker-results">
+                    {
+                        results.iter().map(|o| {
+                            let add = {
+                                let selected = props.selected.clone();
+                                let on_change = props.on_change.clone();
+                                let query = query.clone();
+                                let uuid = o.uuid.clone();
+                                Callback::from(move |_: MouseEvent| {
+                                    let mut selected = selected.clone();
+                                    selected.push(uuid.clone());
+                                    on_change.emit(selected);
+                                    query.set(String::new());
+                                })
+                            };
+                            html! {
+                                <li key={o.uuid.clone()} onclick={add}>{&o.name}</li>
+                            }
+                        }).collect::<Html>()
+                    }
+                </ul>
+            }
+        </div>
+    }
+}
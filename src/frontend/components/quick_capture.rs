@@ -0,0 +1,28 @@
+use web_sys::window;
+use yew::prelude::*;
+use crate::frontend::services::capture;
+
+// Data page section: bookmarklets that add the page they are clicked on through `/capture`
+#[function_component(QuickCapture)]
+pub fn quick_capture() -> Html {
+    // Clicked here they would only capture the Data page
+    let explain = Callback::from(|e: MouseEvent| {
+        e.prevent_default();
+        if let Some(window) = window() {
+            let _ = window.alert_with_message("Drag this button to your bookmarks bar, then click the bookmark on any page to add it to Relf.");
+        }
+    });
+
+    html! {
+        <div class="data-content quick-capture">
+            <div class="snapshots-header">{"Quick Capture"}</div>
+            <div class="file-sync-body">
+                <span class="file-sync-status">
+                    {"Drag a button to your bookmarks bar and click it on any page to add that page, with any text selected on it. Once Relf is installed on a phone, sharing to Relf does the same."}
+                </span>
+                <a class="modern-button bookmarklet" href={capture::bookmarklet(false)} onclick={explain.clone()}>{"Relf Outside"}</a>
+                <a class="modern-button bookmarklet" href={capture::bookmarklet(true)} onclick={explain}>{"Relf Inside"}</a>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,236 @@
+use web_sys::{window, HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use crate::frontend::services::live::{self, LiveStatus};
+use crate::frontend::services::server_api::{self, RemoteSpace};
+use crate::frontend::services::store::use_store;
+use crate::frontend::services::workspace;
+
+fn alert(message: &str) {
+    if let Some(window) = window() {
+        let _ = window.alert_with_message(message);
+    }
+}
+
+fn status_label(status: &LiveStatus) -> String {
+    match status {
+        LiveStatus::Unlinked => "Not linked".to_string(),
+        LiveStatus::Connecting => "connecting…".to_string(),
+        LiveStatus::Connected => "live".to_string(),
+        LiveStatus::Offline(reason) => format!("offline: {}", reason),
+    }
+}
+
+// Data page section: sign in to the relf server and keep a workspace live with it
+#[function_component(ServerSync)]
+pub fn server_sync() -> Html {
+    let store = use_store();
+    let session = use_state(server_api::session);
+    let username_input = use_state(String::new);
+    let password_input = use_state(String::new);
+    let spaces = use_state(Vec::<RemoteSpace>::new);
+    let selected = use_state(|| 0usize);
+    let merge = use_state(|| false);
+    let linked = use_state(live::link);
+    let (status, _) = live::use_live_status();
+
+    // Workspaces to link to, once signed in
+    {
+        let spaces = spaces.clone();
+        use_effect_with((*session).clone(), move |session| {
+            if session.is_some() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    match server_api::spaces().await {
+                        Ok(list) => spaces.set(list),
+                        Err(e) => web_sys::console::log_1(&format!("Failed to list server workspaces: {}", e).into()),
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let on_username_change = {
+        let username_input = username_input.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            username_input.set(input.value());
+        })
+    };
+
+    let on_password_change = {
+        let password_input = password_input.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            password_input.set(input.value());
+        })
+    };
+
+    let sign_in = {
+        let session = session.clone();
+        let username_input = username_input.clone();
+        let password_input = password_input.clone();
+        move |register: bool| {
+            let session = session.clone();
+            let username_input = username_input.clone();
+            let password_input = password_input.clone();
+            Callback::from(move |_: MouseEvent| {
+                let session = session.clone();
+                let username = (*username_input).clone();
+                let password = (*password_input).clone();
+                let password_input = password_input.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    match server_api::sign_in(&username, &password, register).await {
+                        Ok(signed_in) => {
+                            password_input.set(String::new());
+                            session.set(Some(signed_in));
+                        }
+                        Err(e) => alert(&format!("Sign in failed: {}", e)),
+                    }
+                });
+            })
+        }
+    };
+
+    let sign_out = {
+        let session = session.clone();
+        let linked = linked.clone();
+        Callback::from(move |_: MouseEvent| {
+            live::unlink();
+            linked.set(None);
+            let session = session.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                server_api::sign_out().await;
+                session.set(None);
+            });
+        })
+    };
+
+    let on_space_change = {
+        let selected = selected.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            selected.set(select.value().parse().unwrap_or(0));
+        })
+    };
+
+    let on_merge_change = {
+        let merge = merge.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            merge.set(input.checked());
+        })
+    };
+
+    let link_workspace = {
+        let store = store.clone();
+        let spaces = spaces.clone();
+        let selected = selected.clone();
+        let merge = merge.clone();
+        let linked = linked.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(space) = spaces.get(*selected).cloned() else {
+                return;
+            };
+            let local = workspace::find(&store.workspace).map(|w| w.name).unwrap_or_default();
+            let message = format!(
+                "Keep \"{}\" live with \"{}\" on the server? If the server already has records there, they replace the ones in \"{}\".",
+                local, space.name, local
+            );
+            let confirmed = window()
+                .and_then(|w| w.confirm_with_message(&message).ok())
+                .unwrap_or(false);
+            if !confirmed {
+                return;
+            }
+            let linked = linked.clone();
+            let merge = *merge;
+            wasm_bindgen_futures::spawn_local(async move {
+                match live::link_active(space, merge).await {
+                    Ok(()) => linked.set(live::link()),
+                    Err(e) => alert(&format!("Link failed: {}", e)),
+                }
+            });
+        })
+    };
+
+    let unlink = {
+        let linked = linked.clone();
+        Callback::from(move |_: MouseEvent| {
+            live::unlink();
+            linked.set(None);
+        })
+    };
+
+    html! {
+        <div class="data-content server-sync">
+            <div class="snapshots-header">{"Server"}</div>
+            <div class="file-sync-body">
+                {
+                    match (&*session, &*linked) {
+                        (None, _) => html! {
+                            <>
+                                <input
+                                    type="text"
+                                    class="passphrase-input"
+                                    placeholder="Username"
+                                    autocomplete="username"
+                                    value={(*username_input).clone()}
+                                    oninput={on_username_change}
+                                />
+                                <input
+                                    type="password"
+                                    class="passphrase-input"
+                                    placeholder="Password"
+                                    autocomplete="current-password"
+                                    value={(*password_input).clone()}
+                                    oninput={on_password_change}
+                                />
+                                <button class="modern-button" onclick={sign_in(false)}>{"Sign in"}</button>
+                                <button class="modern-button" onclick={sign_in(true)}>{"Register"}</button>
+                            </>
+                        },
+                        (Some(session), Some(link)) => {
+                            let local = workspace::find(&link.workspace).map(|w| w.name).unwrap_or_default();
+                            html! {
+                                <>
+                                    <span class="file-sync-status">
+                                        {format!(
+                                            "{} is live with {} as {}{} ({})",
+                                            local,
+                                            link.space.name,
+                                            session.username,
+                                            if link.merge { ", merging edits" } else { "" },
+                                            status_label(&status)
+                                        )}
+                                    </span>
+                                    <button class="modern-button" onclick={unlink}>{"Unlink"}</button>
+                                    <button class="modern-button" onclick={sign_out}>{"Sign out"}</button>
+                                </>
+                            }
+                        }
+                        (Some(session), None) => html! {
+                            <>
+                                <span class="file-sync-status">{format!("Signed in as {}", session.username)}</span>
+                                <select class="workspace-select" title="Server workspace" onchange={on_space_change}>
+                                    {
+                                        spaces.iter().enumerate().map(|(i, space)| html! {
+                                            <option value={i.to_string()} selected={i == *selected}>
+                                                {format!("{} ({})", space.name, space.role)}
+                                            </option>
+                                        }).collect::<Html>()
+                                    }
+                                </select>
+                                <label class="file-sync-status" title="Combine edits made at the same time, even offline, instead of keeping the last save">
+                                    <input type="checkbox" checked={*merge} onchange={on_merge_change} />
+                                    {" Merge concurrent edits"}
+                                </label>
+                                <button class="modern-button" onclick={link_workspace} disabled={spaces.is_empty()}>{"Link workspace"}</button>
+                                <button class="modern-button" onclick={sign_out}>{"Sign out"}</button>
+                            </>
+                        },
+                    }
+                }
+            </div>
+        </div>
+    }
+}
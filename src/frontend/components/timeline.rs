@@ -0,0 +1,138 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use chrono::{Datelike, Days, NaiveDate};
+use yew::prelude::*;
+use yew_router::prelude::*;
+use crate::models::Inside;
+use crate::frontend::services::related::excerpt;
+use crate::frontend::services::router::Route;
+use crate::frontend::services::stats::week_start;
+
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+fn month_id(day: NaiveDate) -> String {
+    format!("timeline-{}", day.format("%Y-%m"))
+}
+
+fn week_id(day: NaiveDate) -> String {
+    format!("{}-w{:02}", month_id(day), day.iso_week().week())
+}
+
+// Anchor of the week holding the entry closest to `day`
+pub fn anchor_for(insides: &[Inside], day: NaiveDate) -> Option<String> {
+    insides
+        .iter()
+        .map(|i| i.date.day())
+        .min_by_key(|d| (*d - day).num_days().abs())
+        .map(week_id)
+}
+
+pub fn scroll_to(id: &str) {
+    if let Some(element) = web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.get_element_by_id(id))
+    {
+        element.scroll_into_view_with_bool(true);
+    }
+}
+
+type Weeks<'a> = BTreeMap<NaiveDate, Vec<&'a Inside>>;
+
+#[derive(Properties, PartialEq)]
+pub struct TimelineProps {
+    pub insides: Rc<Vec<Inside>>,
+}
+
+// Inside entries newest first, grouped by year, month and week
+#[function_component(Timeline)]
+pub fn timeline(props: &TimelineProps) -> Html {
+    let mut years: BTreeMap<i32, BTreeMap<u32, Weeks>> = BTreeMap::new();
+    for inside in props.insides.iter() {
+        let day = inside.date.day();
+        years
+            .entry(day.year())
+            .or_default()
+            .entry(day.month())
+            .or_default()
+            .entry(week_start(day))
+            .or_default()
+            .push(inside);
+    }
+    if years.is_empty() {
+        return html! { <p class="detail-empty">{"No entries yet."}</p> };
+    }
+
+    let jump = |id: String| Callback::from(move |_: MouseEvent| scroll_to(&id));
+
+    html! {
+        <div class="timeline-view">
+            <nav class="timeline-index">
+                {
+                    years.iter().rev().map(|(year, months)| html! {
+                        <div class="timeline-index-year">
+                            <button class="timeline-index-link year" onclick={jump(format!("timeline-{}", year))}>{year}</button>
+                            {
+                                months.keys().rev().map(|month| {
+                                    let first = NaiveDate::from_ymd_opt(*year, *month, 1).unwrap_or_default();
+                                    html! {
+                                        <button class="timeline-index-link" onclick={jump(month_id(first))}>
+                                            {MONTHS[*month as usize - 1]}
+                                        </button>
+                                    }
+                                }).collect::<Html>()
+                            }
+                        </div>
+                    }).collect::<Html>()
+                }
+            </nav>
+            {
+                years.iter().rev().map(|(year, months)| html! {
+                    <section class="timeline-year" id={format!("timeline-{}", year)}>
+                        <h2>{year}</h2>
+                        {
+                            months.iter().rev().map(|(month, weeks)| {
+                                let first = NaiveDate::from_ymd_opt(*year, *month, 1).unwrap_or_default();
+                                html! {
+                                    <section class="timeline-month" id={month_id(first)}>
+                                        <h3>{first.format("%B %Y").to_string()}</h3>
+                                        {
+                                            weeks.iter().rev().map(|(week_start, entries)| {
+                                                let week_end = *week_start + Days::new(6);
+                                                let mut entries = entries.clone();
+                                                entries.sort_by_key(|i| std::cmp::Reverse(i.date));
+                                                html! {
+                                                    <section class="timeline-week" id={week_id(entries[0].date.day())}>
+                                                        <h4>
+                                                            {format!(
+                                                                "Week {} · {} – {}",
+                                                                week_start.iso_week().week(),
+                                                                week_start.format("%b %-d"),
+                                                                week_end.format("%b %-d"),
+                                                            )}
+                                                        </h4>
+                                                        <ul class="timeline">
+                                                            {
+                                                                entries.iter().map(|i| html! {
+                                                                    <li key={i.uuid.clone()} class="timeline-item">
+                                                                        <Link<Route> to={Route::InsideDetail { uuid: i.uuid.clone() }} classes="timeline-date">
+                                                                            {i.date.to_string()}
+                                                                        </Link<Route>>
+                                                                        <span class="timeline-excerpt">{excerpt(&i.context, 160)}</span>
+                                                                    </li>
+                                                                }).collect::<Html>()
+                                                            }
+                                                        </ul>
+                                                    </section>
+                                                }
+                                            }).collect::<Html>()
+                                        }
+                                    </section>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </section>
+                }).collect::<Html>()
+            }
+        </div>
+    }
+}
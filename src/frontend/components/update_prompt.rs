@@ -0,0 +1,35 @@
+use web_sys::ServiceWorker;
+use yew::prelude::*;
+use crate::frontend::services::pwa;
+
+// Bar offering to reload into a newly deployed build
+#[function_component(UpdatePrompt)]
+pub fn update_prompt() -> Html {
+    let waiting = use_state(|| None::<ServiceWorker>);
+
+    {
+        let waiting = waiting.clone();
+        use_effect_with((), move |_| {
+            pwa::register(Callback::from(move |worker| waiting.set(Some(worker))));
+            || ()
+        });
+    }
+
+    let Some(worker) = (*waiting).clone() else {
+        return html! {};
+    };
+
+    let reload = Callback::from(move |_: MouseEvent| pwa::activate(&worker));
+    let later = {
+        let waiting = waiting.clone();
+        Callback::from(move |_: MouseEvent| waiting.set(None))
+    };
+
+    html! {
+        <div class="update-prompt" role="status">
+            <span>{"A new version of Relf is available."}</span>
+            <button class="update-reload" onclick={reload}>{"Reload"}</button>
+            <button class="update-later" onclick={later}>{"Later"}</button>
+        </div>
+    }
+}
@@ -0,0 +1,112 @@
+use yew::prelude::*;
+use web_sys::HtmlSelectElement;
+use crate::frontend::services::view::{Density, Layout, SortKey, SortOrder, ViewOptions};
+
+#[derive(Properties, PartialEq)]
+pub struct ViewMenuProps {
+    pub options: ViewOptions,
+    pub keys: &'static [SortKey],
+    pub on_change: Callback<ViewOptions>,
+    // Layouts to switch between; none shows no switch
+    #[prop_or_default]
+    pub layouts: &'static [Layout],
+    // Extra controls shown before the sort menu
+    #[prop_or_default]
+    pub children: Children,
+}
+
+fn new_seed() -> u32 {
+    (js_sys::Math::random() * f64::from(u32::MAX)) as u32
+}
+
+#[function_component(ViewMenu)]
+pub fn view_menu(props: &ViewMenuProps) -> Html {
+    let options = props.options;
+
+    let on_sort_change = {
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(sort) = SortKey::parse(&select.value()) {
+                let seed = if sort == SortKey::Random { new_seed() } else { options.seed };
+                on_change.emit(ViewOptions { sort, seed, ..options });
+            }
+        })
+    };
+
+    let toggle_order = {
+        let on_change = props.on_change.clone();
+        Callback::from(move |_: MouseEvent| {
+            on_change.emit(ViewOptions { order: options.order.toggled(), ..options });
+        })
+    };
+
+    let reshuffle = {
+        let on_change = props.on_change.clone();
+        Callback::from(move |_: MouseEvent| {
+            on_change.emit(ViewOptions { seed: new_seed(), ..options });
+        })
+    };
+
+    html! {
+        <div class="view-menu">
+            {props.children.clone()}
+            if !props.layouts.is_empty() {
+                <div class="view-density">
+                    {
+                        props.layouts.iter().map(|layout| {
+                            let layout = *layout;
+                            let on_change = props.on_change.clone();
+                            let onclick = Callback::from(move |_: MouseEvent| {
+                                on_change.emit(ViewOptions { layout, ..options });
+                            });
+                            html! {
+                                <button
+                                    class={classes!("view-button", (layout == options.layout).then_some("active"))}
+                                    onclick={onclick}
+                                    title={layout.label()}
+                                >
+                                    {layout.icon()}
+                                </button>
+                            }
+                        }).collect::<Html>()
+                    }
+                </div>
+            }
+            <select class="view-sort" onchange={on_sort_change} title="Sort by">
+                {
+                    props.keys.iter().map(|key| html! {
+                        <option value={key.as_str()} selected={*key == options.sort}>{key.label()}</option>
+                    }).collect::<Html>()
+                }
+            </select>
+            if options.sort == SortKey::Random {
+                <button class="view-button" onclick={reshuffle} title="Shuffle again">{"🔀"}</button>
+            } else {
+                <button class="view-button" onclick={toggle_order} title="Toggle ascending/descending">
+                    {if options.order == SortOrder::Asc { "↑" } else { "↓" }}
+                </button>
+            }
+            <div class="view-density">
+                {
+                    Density::ALL.iter().map(|density| {
+                        let density = *density;
+                        let on_change = props.on_change.clone();
+                        let onclick = Callback::from(move |_: MouseEvent| {
+                            on_change.emit(ViewOptions { density, ..options });
+                        });
+                        html! {
+                            <button
+                                class={classes!("view-button", (density == options.density).then_some("active"))}
+                                onclick={onclick}
+                                title={density.label()}
+                            >
+                                {density.icon()}
+                            </button>
+                        }
+                    }).collect::<Html>()
+                }
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,70 @@
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use yew_router::prelude::*;
+use crate::frontend::services::router::Route;
+use crate::frontend::services::store::{use_store, StoreAction};
+use crate::frontend::services::workspace;
+
+// Option value that asks for the name of a new workspace
+const NEW_WORKSPACE: &str = "__new";
+
+// Select of the workspace shown in this tab, with an entry to create one
+#[function_component(WorkspaceSwitcher)]
+pub fn workspace_switcher() -> Html {
+    let store = use_store();
+    let navigator = use_navigator();
+    let route = use_route::<Route>();
+    let workspaces = workspace::list();
+
+    let onchange = {
+        let store = store.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let mut id = select.value();
+            if id == NEW_WORKSPACE {
+                let name = web_sys::window().and_then(|w| w.prompt_with_message("Name of the new workspace:").ok().flatten());
+                let created = name.filter(|n| !n.trim().is_empty()).map(|n| workspace::create(&n));
+                match created {
+                    Some(Ok(created)) => id = created.id,
+                    Some(Err(e)) => {
+                        if let Some(window) = web_sys::window() {
+                            let _ = window.alert_with_message(&e);
+                        }
+                        select.set_value(&store.workspace);
+                        return;
+                    }
+                    None => {
+                        select.set_value(&store.workspace);
+                        return;
+                    }
+                }
+            }
+            if let Err(e) = store.run(StoreAction::SwitchWorkspace(id)) {
+                if let Some(window) = web_sys::window() {
+                    let _ = window.alert_with_message(&e);
+                }
+                select.set_value(&store.workspace);
+                return;
+            }
+            // Records of the previous workspace are no longer there
+            if let Some(navigator) = &navigator {
+                match &route {
+                    Some(Route::OutsideDetail { .. } | Route::OutsideEdit { .. }) => navigator.push(&Route::Outside),
+                    Some(Route::InsideDetail { .. } | Route::InsideEdit { .. }) => navigator.push(&Route::Inside),
+                    _ => {}
+                }
+            }
+        })
+    };
+
+    html! {
+        <select class="workspace-select" title="Workspace" onchange={onchange}>
+            {
+                workspaces.iter().map(|w| html! {
+                    <option key={w.id.clone()} value={w.id.clone()} selected={w.id == store.workspace}>{&w.name}</option>
+                }).collect::<Html>()
+            }
+            <option value={NEW_WORKSPACE}>{"+ New workspace…"}</option>
+        </select>
+    }
+}
@@ -0,0 +1,21 @@
+use yew::prelude::*;
+use yew_router::prelude::*;
+use crate::frontend::pages::inside::InsidePage;
+use crate::frontend::pages::outside::OutsidePage;
+use crate::frontend::services::capture::Capture;
+
+// `/capture`: the Outside list, or Inside with `to=inside`, with the add form filled in
+#[function_component(CapturePage)]
+pub fn capture_page() -> Html {
+    let location = use_location();
+    let capture = location
+        .as_ref()
+        .and_then(|l| l.query::<Capture>().ok())
+        .unwrap_or_default()
+        .normalized();
+    if capture.wants_inside() {
+        html! { <InsidePage capture={capture} /> }
+    } else {
+        html! { <OutsidePage capture={capture} /> }
+    }
+}
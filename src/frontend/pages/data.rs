@@ -1,51 +1,71 @@
 use yew::prelude::*;
-use wasm_bindgen::prelude::*;
 use web_sys::{window, HtmlInputElement, HtmlTextAreaElement};
 use js_sys;
 use crate::frontend::components::navigation::Navigation;
+use crate::frontend::components::server_sync::ServerSync;
+use crate::frontend::components::quick_capture::QuickCapture;
+use crate::frontend::services::crypto;
+use crate::frontend::services::file_sync::{self, SyncStatus};
+use crate::frontend::services::snapshot::{self, Snapshot};
 use crate::frontend::services::storage;
+use crate::frontend::services::store::{use_store, StoreAction};
+use crate::frontend::services::workspace::{self, Workspace};
 
 #[function_component(Data)]
 pub fn data() -> Html {
-    let outsides = use_state(|| {
-        let mut data = storage::get_outsides();
-        data.sort_by(|a, b| b.percentage.cmp(&a.percentage));
-        data
-    });
-    let insides = use_state(|| {
-        let mut data = storage::get_insides();
-        data.sort_by(|a, b| b.date.cmp(&a.date));
-        data
-    });
-    let json_content = use_state(|| storage::export_to_json());
+    let store = use_store();
+    let json_content = use_memo(store.revision, |_| storage::export_to_json());
     let show_import_modal = use_state(|| false);
     let show_import_outside_modal = use_state(|| false);
     let show_import_inside_modal = use_state(|| false);
-    let import_json = use_state(|| String::new());
-    let import_outside_json = use_state(|| String::new());
-    let import_inside_json = use_state(|| String::new());
+    let import_json = use_state(String::new);
+    let import_outside_json = use_state(String::new);
+    let import_inside_json = use_state(String::new);
     let file_input_ref = use_node_ref();
+    let everything_input_ref = use_node_ref();
     let textarea_ref = use_node_ref();
     let outside_textarea_ref = use_node_ref();
     let inside_textarea_ref = use_node_ref();
+    let snapshots = use_state(Vec::<Snapshot>::new);
+    let sync_status = use_state(file_sync::status);
+    let encryption_enabled = use_state(crypto::is_enabled);
+    let passphrase_input = use_state(String::new);
+    let passphrase_confirm_input = use_state(String::new);
+    let workspaces = use_memo(store.revision, |_| {
+        workspace::list()
+            .into_iter()
+            .map(|w| {
+                let outside_count = storage::get_outsides_in(&w.id).len();
+                let inside_count = storage::get_insides_in(&w.id).len();
+                (w, outside_count, inside_count)
+            })
+            .collect::<Vec<_>>()
+    });
+
+    // Load stored snapshots
+    {
+        let snapshots = snapshots.clone();
+        let sync_status = sync_status.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match snapshot::list_snapshots().await {
+                    Ok(list) => snapshots.set(list),
+                    Err(e) => web_sys::console::log_1(&format!("Failed to load snapshots: {}", e).into()),
+                }
+                // The linked file is restored asynchronously on startup
+                sync_status.set(file_sync::status());
+            });
+            || ()
+        });
+    }
 
     // Refresh/Reset all data to defaults
     let refresh_data = {
-        let outsides = outsides.clone();
-        let insides = insides.clone();
-        let json_content = json_content.clone();
+        let store = store.clone();
         Callback::from(move |_: MouseEvent| {
             match storage::reset_to_defaults() {
                 Ok(_) => {
-                    let mut outside_data = storage::get_outsides();
-                    outside_data.sort_by(|a, b| b.percentage.cmp(&a.percentage));
-                    outsides.set(outside_data);
-                    
-                    let mut inside_data = storage::get_insides();
-                    inside_data.sort_by(|a, b| b.date.cmp(&a.date));
-                    insides.set(inside_data);
-                    
-                    json_content.set(storage::export_to_json());
+                    store.reload();
                     web_sys::console::log_1(&"All data reset to defaults!".into());
                 }
                 Err(e) => {
@@ -115,6 +135,335 @@ pub fn data() -> Html {
         storage::download_json();
     });
 
+    // Take a snapshot of the current data now
+    let take_snapshot = {
+        let snapshots = snapshots.clone();
+        Callback::from(move |_| {
+            let snapshots = snapshots.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = match snapshot::take_snapshot().await {
+                    Ok(_) => snapshot::list_snapshots().await,
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(list) => {
+                        snapshots.set(list);
+                        web_sys::console::log_1(&"Snapshot taken successfully!".into());
+                    }
+                    Err(e) => web_sys::console::log_1(&format!("Snapshot failed: {}", e).into()),
+                }
+            });
+        })
+    };
+
+    // Restore all data from a snapshot
+    let restore_snapshot = {
+        let store = store.clone();
+        Callback::from(move |item: Snapshot| {
+            let confirmed = window()
+                .and_then(|w| w.confirm_with_message(&format!("Restore the snapshot from {}? Current data will be replaced.", item.taken_at)).ok())
+                .unwrap_or(false);
+            if !confirmed {
+                return;
+            }
+            match snapshot::restore_snapshot(&item) {
+                Ok(_) => {
+                    store.reload();
+                    web_sys::console::log_1(&"Snapshot restored successfully!".into());
+                }
+                Err(e) => {
+                    web_sys::console::log_1(&format!("Restore failed: {}", e).into());
+                    if let Some(window) = window() {
+                        let _ = window.alert_with_message(&format!("Restore failed: {}", e));
+                    }
+                }
+            }
+        })
+    };
+
+    // Download a snapshot as a file
+    let download_snapshot = Callback::from(|item: Snapshot| {
+        snapshot::download_snapshot(&item);
+    });
+
+    // Link a new file and keep it in sync, or download where unsupported
+    let link_new_file = {
+        let sync_status = sync_status.clone();
+        Callback::from(move |_| {
+            if !file_sync::is_supported() {
+                storage::download_json();
+                return;
+            }
+            let sync_status = sync_status.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match file_sync::link_new_file().await {
+                    Ok(name) => web_sys::console::log_1(&format!("Linked to {}", name).into()),
+                    Err(e) => web_sys::console::log_1(&format!("Link failed: {}", e).into()),
+                }
+                sync_status.set(file_sync::status());
+            });
+        })
+    };
+
+    // Link an existing file, loading its data first
+    let link_existing_file = {
+        let store = store.clone();
+        let sync_status = sync_status.clone();
+        Callback::from(move |_| {
+            let store = store.clone();
+            let sync_status = sync_status.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match file_sync::link_existing_file().await {
+                    Ok(name) => {
+                        store.reload();
+                        web_sys::console::log_1(&format!("Linked to {}", name).into());
+                    }
+                    Err(e) => {
+                        web_sys::console::log_1(&format!("Link failed: {}", e).into());
+                        if let Some(window) = window() {
+                            let _ = window.alert_with_message(&format!("Link failed: {}", e));
+                        }
+                    }
+                }
+                sync_status.set(file_sync::status());
+            });
+        })
+    };
+
+    // Ask again for access to the file linked in a previous session
+    let reconnect_file = {
+        let sync_status = sync_status.clone();
+        Callback::from(move |_| {
+            let sync_status = sync_status.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Err(e) = file_sync::reconnect().await {
+                    web_sys::console::log_1(&format!("Reconnect failed: {}", e).into());
+                }
+                sync_status.set(file_sync::status());
+            });
+        })
+    };
+
+    // Stop syncing with the linked file
+    let unlink_file = {
+        let sync_status = sync_status.clone();
+        Callback::from(move |_| {
+            let sync_status = sync_status.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Err(e) = file_sync::unlink().await {
+                    web_sys::console::log_1(&format!("Unlink failed: {}", e).into());
+                }
+                sync_status.set(file_sync::status());
+            });
+        })
+    };
+
+    // Show another workspace
+    let switch_workspace = {
+        let store = store.clone();
+        Callback::from(move |item: Workspace| {
+            if let Err(e) = store.run(StoreAction::SwitchWorkspace(item.id))
+                && let Some(window) = window()
+            {
+                let _ = window.alert_with_message(&e);
+            }
+        })
+    };
+
+    let create_workspace = {
+        let store = store.clone();
+        Callback::from(move |_| {
+            let Some(name) = window().and_then(|w| w.prompt_with_message("Name of the new workspace:").ok().flatten()) else {
+                return;
+            };
+            match workspace::create(&name) {
+                Ok(created) => {
+                    if let Err(e) = store.run(StoreAction::SwitchWorkspace(created.id))
+                        && let Some(window) = window()
+                    {
+                        let _ = window.alert_with_message(&e);
+                    }
+                }
+                Err(e) => {
+                    if let Some(window) = window() {
+                        let _ = window.alert_with_message(&e);
+                    }
+                }
+            }
+        })
+    };
+
+    let rename_workspace = {
+        let store = store.clone();
+        Callback::from(move |item: Workspace| {
+            let Some(name) = window().and_then(|w| w.prompt_with_message_and_default("New name of the workspace:", &item.name).ok().flatten()) else {
+                return;
+            };
+            match workspace::rename(&item.id, &name) {
+                Ok(_) => {
+                    store.reload();
+                }
+                Err(e) => {
+                    if let Some(window) = window() {
+                        let _ = window.alert_with_message(&e);
+                    }
+                }
+            }
+        })
+    };
+
+    let download_workspace = Callback::from(|item: Workspace| {
+        storage::download_workspace(&item.id);
+    });
+
+    // Delete a workspace with its records
+    let delete_workspace = {
+        let store = store.clone();
+        Callback::from(move |item: Workspace| {
+            let confirmed = window()
+                .and_then(|w| w.confirm_with_message(&format!("Delete the workspace \"{}\" and all of its records?", item.name)).ok())
+                .unwrap_or(false);
+            if !confirmed {
+                return;
+            }
+            match workspace::delete(&item.id) {
+                Ok(_) => {
+                    store.reload();
+                }
+                Err(e) => {
+                    if let Some(window) = window() {
+                        let _ = window.alert_with_message(&format!("Delete failed: {}", e));
+                    }
+                }
+            }
+        })
+    };
+
+    let download_everything = Callback::from(|_| {
+        storage::download_everything();
+    });
+
+    let trigger_everything_import = {
+        let everything_input_ref = everything_input_ref.clone();
+        Callback::from(move |_| {
+            if let Some(input) = everything_input_ref.cast::<HtmlInputElement>() {
+                input.click();
+            }
+        })
+    };
+
+    // Restore every workspace in a file, replacing those with the same name
+    let on_everything_change = {
+        let store = store.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let confirm = |message: &str| window().and_then(|w| w.confirm_with_message(message).ok()).unwrap_or(false);
+            if let Some(file) = input.files().and_then(|files| files.get(0))
+                && confirm("Replace workspaces with the ones in this file? Workspaces of the same name will be overwritten.")
+            {
+                let store = store.clone();
+                storage::read_text_file(&file, move |text_str| {
+                    match storage::import_everything_from_json(&text_str) {
+                        Ok(_) => {
+                            store.reload();
+                            web_sys::console::log_1(&"Workspaces imported successfully from file!".into());
+                        }
+                        Err(e) => {
+                            web_sys::console::log_1(&format!("Import failed: {}", e).into());
+                            if let Some(window) = window() {
+                                let _ = window.alert_with_message(&format!("Import failed: {}", e));
+                            }
+                        }
+                    }
+                });
+            }
+            input.set_value("");
+        })
+    };
+
+    let on_passphrase_change = {
+        let passphrase_input = passphrase_input.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            passphrase_input.set(input.value());
+        })
+    };
+
+    let on_passphrase_confirm_change = {
+        let passphrase_confirm_input = passphrase_confirm_input.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            passphrase_confirm_input.set(input.value());
+        })
+    };
+
+    // Encrypt stored data and exports with a passphrase
+    let enable_encryption = {
+        let encryption_enabled = encryption_enabled.clone();
+        let passphrase_input = passphrase_input.clone();
+        let passphrase_confirm_input = passphrase_confirm_input.clone();
+        Callback::from(move |_| {
+            let passphrase = (*passphrase_input).clone();
+            let error = if passphrase.is_empty() {
+                Some("Passphrase must not be empty".to_string())
+            } else if passphrase != *passphrase_confirm_input {
+                Some("Passphrases do not match".to_string())
+            } else {
+                storage::enable_encryption(&passphrase).err()
+            };
+            match error {
+                None => {
+                    passphrase_input.set(String::new());
+                    passphrase_confirm_input.set(String::new());
+                    encryption_enabled.set(true);
+                    wasm_bindgen_futures::spawn_local(async {
+                        if let Err(e) = snapshot::encrypt_existing().await {
+                            web_sys::console::log_1(&format!("Failed to encrypt snapshots: {}", e).into());
+                        }
+                    });
+                    web_sys::console::log_1(&"Encryption enabled!".into());
+                }
+                Some(e) => {
+                    if let Some(window) = window() {
+                        let _ = window.alert_with_message(&format!("Enable encryption failed: {}", e));
+                    }
+                }
+            }
+        })
+    };
+
+    // Forget the key until the passphrase is entered again
+    let lock_data = Callback::from(|_| {
+        crypto::lock();
+        if let Some(window) = window() {
+            let _ = window.location().reload();
+        }
+    });
+
+    // Store data in plaintext again
+    let disable_encryption = {
+        let encryption_enabled = encryption_enabled.clone();
+        Callback::from(move |_| {
+            let confirmed = window()
+                .and_then(|w| w.confirm_with_message("Disable encryption? Data will be stored in plaintext.").ok())
+                .unwrap_or(false);
+            if !confirmed {
+                return;
+            }
+            match storage::disable_encryption() {
+                Ok(_) => {
+                    encryption_enabled.set(false);
+                    web_sys::console::log_1(&"Encryption disabled!".into());
+                }
+                Err(e) => {
+                    if let Some(window) = window() {
+                        let _ = window.alert_with_message(&format!("Disable encryption failed: {}", e));
+                    }
+                }
+            }
+        })
+    };
+
     // Show import modal
     let show_modal = {
         let show_import_modal = show_import_modal.clone();
@@ -199,24 +548,14 @@ pub fn data() -> Html {
     // Append JSON data
     let append_data = {
         let import_json = import_json.clone();
-        let outsides = outsides.clone();
-        let insides = insides.clone();
-        let json_content = json_content.clone();
+        let store = store.clone();
         let show_import_modal = show_import_modal.clone();
         Callback::from(move |_| {
             let json_str = (*import_json).clone();
             if !json_str.is_empty() {
                 match storage::append_from_json(&json_str) {
                     Ok(_) => {
-                        let mut outside_data = storage::get_outsides();
-                        outside_data.sort_by(|a, b| b.percentage.cmp(&a.percentage));
-                        outsides.set(outside_data);
-                        
-                        let mut inside_data = storage::get_insides();
-                        inside_data.sort_by(|a, b| b.date.cmp(&a.date));
-                        insides.set(inside_data);
-                        
-                        json_content.set(storage::export_to_json());
+                        store.reload();
                         show_import_modal.set(false);
                         web_sys::console::log_1(&"Data appended successfully!".into());
                     }
@@ -235,24 +574,14 @@ pub fn data() -> Html {
     // Import JSON data
     let import_data = {
         let import_json = import_json.clone();
-        let outsides = outsides.clone();
-        let insides = insides.clone();
-        let json_content = json_content.clone();
+        let store = store.clone();
         let show_import_modal = show_import_modal.clone();
         Callback::from(move |_| {
             let json_str = (*import_json).clone();
             if !json_str.is_empty() {
                 match storage::import_from_json(&json_str) {
                     Ok(_) => {
-                        let mut outside_data = storage::get_outsides();
-                        outside_data.sort_by(|a, b| b.percentage.cmp(&a.percentage));
-                        outsides.set(outside_data);
-                        
-                        let mut inside_data = storage::get_insides();
-                        inside_data.sort_by(|a, b| b.date.cmp(&a.date));
-                        insides.set(inside_data);
-                        
-                        json_content.set(storage::export_to_json());
+                        store.reload();
                         show_import_modal.set(false);
                         web_sys::console::log_1(&"Data imported successfully!".into());
                     }
@@ -271,19 +600,14 @@ pub fn data() -> Html {
     // Append outside JSON data
     let append_outside_data = {
         let import_outside_json = import_outside_json.clone();
-        let outsides = outsides.clone();
-        let json_content = json_content.clone();
+        let store = store.clone();
         let show_import_outside_modal = show_import_outside_modal.clone();
         Callback::from(move |_| {
             let json_str = (*import_outside_json).clone();
             if !json_str.is_empty() {
                 match storage::append_outside_from_json(&json_str) {
                     Ok(_) => {
-                        let mut outside_data = storage::get_outsides();
-                        outside_data.sort_by(|a, b| b.percentage.cmp(&a.percentage));
-                        outsides.set(outside_data);
-                        
-                        json_content.set(storage::export_to_json());
+                        store.reload();
                         show_import_outside_modal.set(false);
                         web_sys::console::log_1(&"Outside data appended successfully!".into());
                     }
@@ -302,19 +626,14 @@ pub fn data() -> Html {
     // Import outside JSON data
     let import_outside_data = {
         let import_outside_json = import_outside_json.clone();
-        let outsides = outsides.clone();
-        let json_content = json_content.clone();
+        let store = store.clone();
         let show_import_outside_modal = show_import_outside_modal.clone();
         Callback::from(move |_| {
             let json_str = (*import_outside_json).clone();
             if !json_str.is_empty() {
                 match storage::import_outside_from_json(&json_str) {
                     Ok(_) => {
-                        let mut outside_data = storage::get_outsides();
-                        outside_data.sort_by(|a, b| b.percentage.cmp(&a.percentage));
-                        outsides.set(outside_data);
-                        
-                        json_content.set(storage::export_to_json());
+                        store.reload();
                         show_import_outside_modal.set(false);
                         web_sys::console::log_1(&"Outside data imported successfully!".into());
                     }
@@ -333,19 +652,14 @@ pub fn data() -> Html {
     // Append inside JSON data
     let append_inside_data = {
         let import_inside_json = import_inside_json.clone();
-        let insides = insides.clone();
-        let json_content = json_content.clone();
+        let store = store.clone();
         let show_import_inside_modal = show_import_inside_modal.clone();
         Callback::from(move |_| {
             let json_str = (*import_inside_json).clone();
             if !json_str.is_empty() {
                 match storage::append_inside_from_json(&json_str) {
                     Ok(_) => {
-                        let mut inside_data = storage::get_insides();
-                        inside_data.sort_by(|a, b| b.date.cmp(&a.date));
-                        insides.set(inside_data);
-                        
-                        json_content.set(storage::export_to_json());
+                        store.reload();
                         show_import_inside_modal.set(false);
                         web_sys::console::log_1(&"Inside data appended successfully!".into());
                     }
@@ -364,19 +678,14 @@ pub fn data() -> Html {
     // Import inside JSON data
     let import_inside_data = {
         let import_inside_json = import_inside_json.clone();
-        let insides = insides.clone();
-        let json_content = json_content.clone();
+        let store = store.clone();
         let show_import_inside_modal = show_import_inside_modal.clone();
         Callback::from(move |_| {
             let json_str = (*import_inside_json).clone();
             if !json_str.is_empty() {
                 match storage::import_inside_from_json(&json_str) {
                     Ok(_) => {
-                        let mut inside_data = storage::get_insides();
-                        inside_data.sort_by(|a, b| b.date.cmp(&a.date));
-                        insides.set(inside_data);
-                        
-                        json_content.set(storage::export_to_json());
+                        store.reload();
                         show_import_inside_modal.set(false);
                         web_sys::console::log_1(&"Inside data imported successfully!".into());
                     }
@@ -404,61 +713,39 @@ pub fn data() -> Html {
 
     // Handle file selection
     let on_file_change = {
-        let outsides = outsides.clone();
-        let insides = insides.clone();
-        let json_content = json_content.clone();
+        let store = store.clone();
         let show_import_modal = show_import_modal.clone();
         Callback::from(move |e: Event| {
             let input: HtmlInputElement = e.target_unchecked_into();
-            if let Some(files) = input.files() {
-                if let Some(file) = files.get(0) {
-                    let outsides = outsides.clone();
-                    let insides = insides.clone();
-                    let json_content = json_content.clone();
-                    let show_import_modal = show_import_modal.clone();
-                    
-                    let reader = web_sys::FileReader::new().unwrap();
-                    let reader_clone = reader.clone();
-                    
-                    let _ = reader.read_as_text(&file);
-                    
-                    let closure = Closure::once(move || {
-                        if let Ok(result) = reader_clone.result() {
-                            if let Some(text_str) = result.as_string() {
-                                match storage::import_from_json(&text_str) {
-                                    Ok(_) => {
-                                        let mut outside_data = storage::get_outsides();
-                                        outside_data.sort_by(|a, b| b.percentage.cmp(&a.percentage));
-                                        outsides.set(outside_data);
-                                        
-                                        let mut inside_data = storage::get_insides();
-                                        inside_data.sort_by(|a, b| b.date.cmp(&a.date));
-                                        insides.set(inside_data);
-                                        
-                                        json_content.set(storage::export_to_json());
-                                        show_import_modal.set(false);
-                                        web_sys::console::log_1(&"Data imported successfully from file!".into());
-                                    }
-                                    Err(e) => {
-                                        web_sys::console::log_1(&format!("Import failed: {}", e).into());
-                                        if let Some(window) = window() {
-                                            let _ = window.alert_with_message(&format!("Import failed: {}", e));
-                                        }
-                                    }
-                                }
+            if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                let store = store.clone();
+                let show_import_modal = show_import_modal.clone();
+                
+                storage::read_text_file(&file, move |text_str| {
+                    match storage::import_from_json(&text_str) {
+                        Ok(_) => {
+                            store.reload();
+                            show_import_modal.set(false);
+                            web_sys::console::log_1(&"Data imported successfully from file!".into());
+                        }
+                        Err(e) => {
+                            web_sys::console::log_1(&format!("Import failed: {}", e).into());
+                            if let Some(window) = window() {
+                                let _ = window.alert_with_message(&format!("Import failed: {}", e));
                             }
                         }
-                    });
-                    
-                    reader.set_onloadend(Some(closure.as_ref().unchecked_ref()));
-                    closure.forget();
-                }
+                    }
+                });
             }
             // Reset the file input value to allow re-selecting the same file
             input.set_value("");
         })
     };
 
+    let linked_workspace = workspace::find(&file_sync::linked_workspace())
+        .map(|w| w.name)
+        .unwrap_or_default();
+
     html! {
         <>
             <Navigation title="Relf" />
@@ -517,6 +804,20 @@ pub fn data() -> Html {
                                 >
                                     <span class="button-icon">{"📁"}</span>
                                 </button>
+                                <button 
+                                    class="snapshot-button modern-button icon-only" 
+                                    onclick={take_snapshot}
+                                    title="Take snapshot now"
+                                >
+                                    <span class="button-icon">{"📸"}</span>
+                                </button>
+                                <button 
+                                    class="link-button modern-button icon-only" 
+                                    onclick={link_new_file.clone()}
+                                    title="Sync data with a file"
+                                >
+                                    <span class="button-icon">{"🔗"}</span>
+                                </button>
                             </div>
                         </div>
                         <div class="markdown-content-wrapper">
@@ -524,6 +825,166 @@ pub fn data() -> Html {
                         </div>
                     </div>
                 </div>
+                
+                <div class="data-content workspaces">
+                    <div class="snapshots-header">{"Workspaces"}</div>
+                    <ul class="snapshot-list">
+                        {
+                            workspaces.iter().map(|(item, outside_count, inside_count)| {
+                                let active = item.id == store.workspace;
+                                let with_item = |callback: &Callback<Workspace>| callback.reform({
+                                    let item = item.clone();
+                                    move |_: MouseEvent| item.clone()
+                                });
+                                html! {
+                                    <li key={item.id.clone()} class={classes!("snapshot-item", active.then_some("active"))}>
+                                        <div class="snapshot-meta">
+                                            <span class="snapshot-date">{&item.name}</span>
+                                            <span class="snapshot-counts">
+                                                {format!("{} outside · {} inside", outside_count, inside_count)}
+                                            </span>
+                                        </div>
+                                        <div class="snapshot-actions">
+                                            if !active {
+                                                <button class="modern-button icon-only" onclick={with_item(&switch_workspace)} title="Switch to workspace">
+                                                    <span class="button-icon">{"↪️"}</span>
+                                                </button>
+                                            }
+                                            <button class="modern-button icon-only" onclick={with_item(&rename_workspace)} title="Rename workspace">
+                                                <span class="button-icon">{"✏️"}</span>
+                                            </button>
+                                            <button class="modern-button icon-only" onclick={with_item(&download_workspace)} title="Export workspace">
+                                                <span class="button-icon">{"💾"}</span>
+                                            </button>
+                                            if item.id != workspace::DEFAULT_ID {
+                                                <button class="modern-button icon-only" onclick={with_item(&delete_workspace)} title="Delete workspace">
+                                                    <span class="button-icon">{"🗑️"}</span>
+                                                </button>
+                                            }
+                                        </div>
+                                    </li>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </ul>
+                    <div class="file-sync-body">
+                        <button class="modern-button" onclick={create_workspace}>{"New workspace"}</button>
+                        <button class="modern-button" onclick={download_everything}>{"Export all"}</button>
+                        <button class="modern-button" onclick={trigger_everything_import}>{"Import all"}</button>
+                    </div>
+                </div>
+                
+                <div class="data-content file-sync">
+                    <div class="snapshots-header">{"File Sync"}</div>
+                    <div class="file-sync-body">
+                        {
+                            match &*sync_status {
+                                SyncStatus::Linked(name) => html! {
+                                    <>
+                                        <span class="file-sync-status">{format!("Synced with {} ({})", name, linked_workspace)}</span>
+                                        <button class="modern-button" onclick={unlink_file}>{"Unlink"}</button>
+                                    </>
+                                },
+                                SyncStatus::NeedsPermission(name) => html! {
+                                    <>
+                                        <span class="file-sync-status">{format!("{} needs permission to sync", name)}</span>
+                                        <button class="modern-button" onclick={reconnect_file}>{"Reconnect"}</button>
+                                        <button class="modern-button" onclick={unlink_file}>{"Unlink"}</button>
+                                    </>
+                                },
+                                SyncStatus::Unlinked => html! {
+                                    <>
+                                        <span class="file-sync-status">{"Not linked to a file"}</span>
+                                        <button class="modern-button" onclick={link_new_file}>{"New file"}</button>
+                                        <button class="modern-button" onclick={link_existing_file}>{"Open file"}</button>
+                                    </>
+                                },
+                                SyncStatus::Unsupported => html! {
+                                    <>
+                                        <span class="file-sync-status">{"File sync is not supported in this browser"}</span>
+                                        <button class="modern-button" onclick={link_new_file}>{"Download"}</button>
+                                    </>
+                                },
+                            }
+                        }
+                    </div>
+                </div>
+                
+                <ServerSync />
+                
+                <QuickCapture />
+                
+                <div class="data-content encryption">
+                    <div class="snapshots-header">{"Encryption"}</div>
+                    if *encryption_enabled {
+                        <div class="file-sync-body">
+                            <span class="file-sync-status">{"Stored data and exported files are encrypted"}</span>
+                            <button class="modern-button" onclick={lock_data}>{"Lock"}</button>
+                            <button class="modern-button" onclick={disable_encryption}>{"Disable"}</button>
+                        </div>
+                    } else {
+                        <div class="file-sync-body">
+                            <input
+                                type="password"
+                                class="passphrase-input"
+                                placeholder="Passphrase"
+                                autocomplete="new-password"
+                                value={(*passphrase_input).clone()}
+                                oninput={on_passphrase_change}
+                            />
+                            <input
+                                type="password"
+                                class="passphrase-input"
+                                placeholder="Confirm passphrase"
+                                autocomplete="new-password"
+                                value={(*passphrase_confirm_input).clone()}
+                                oninput={on_passphrase_confirm_change}
+                            />
+                            <button class="modern-button" onclick={enable_encryption}>{"Enable"}</button>
+                        </div>
+                    }
+                </div>
+                
+                <div class="data-content snapshots">
+                    <div class="snapshots-header">{"Snapshots"}</div>
+                    if snapshots.is_empty() {
+                        <div class="snapshot-empty">{"No snapshots yet"}</div>
+                    } else {
+                        <ul class="snapshot-list">
+                            {
+                                snapshots.iter().map(|item| {
+                                    let restore_callback = restore_snapshot.reform({
+                                        let item = item.clone();
+                                        move |_: MouseEvent| item.clone()
+                                    });
+                                    let download_callback = download_snapshot.reform({
+                                        let item = item.clone();
+                                        move |_: MouseEvent| item.clone()
+                                    });
+                                    
+                                    html! {
+                                        <li key={item.id.clone()} class="snapshot-item">
+                                            <div class="snapshot-meta">
+                                                <span class="snapshot-date">{&item.taken_at}</span>
+                                                <span class="snapshot-counts">
+                                                    {format!("{} outside · {} inside", item.outside_count, item.inside_count)}
+                                                </span>
+                                            </div>
+                                            <div class="snapshot-actions">
+                                                <button class="modern-button icon-only" onclick={restore_callback} title="Restore snapshot">
+                                                    <span class="button-icon">{"↩️"}</span>
+                                                </button>
+                                                <button class="modern-button icon-only" onclick={download_callback} title="Download snapshot">
+                                                    <span class="button-icon">{"💾"}</span>
+                                                </button>
+                                            </div>
+                                        </li>
+                                    }
+                                }).collect::<Html>()
+                            }
+                        </ul>
+                    }
+                </div>
             </div>
             
             // Import Modal
@@ -605,6 +1066,13 @@ pub fn data() -> Html {
                 style="display: none;"
                 onchange={on_file_change}
             />
+            <input 
+                type="file" 
+                ref={everything_input_ref}
+                accept=".json"
+                style="display: none;"
+                onchange={on_everything_change}
+            />
         </>
     }
 }
\ No newline at end of file
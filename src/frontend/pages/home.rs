@@ -6,26 +6,18 @@ use web_sys::window;
 #[function_component(Home)]
 pub fn home() -> Html {
     use_effect_with((), |_| {
-        if let Some(window) = window() {
-            if let Some(document) = window.document() {
-                if let Some(body) = document.body() {
-                    let current_class = body.class_name();
-                    if !current_class.contains("homepage") {
-                        body.set_class_name(&format!("{} homepage", current_class));
-                    }
-                }
+        if let Some(body) = window().and_then(|w| w.document()).and_then(|d| d.body()) {
+            let current_class = body.class_name();
+            if !current_class.contains("homepage") {
+                body.set_class_name(&format!("{} homepage", current_class));
             }
         }
         
         || {
-            if let Some(window) = window() {
-                if let Some(document) = window.document() {
-                    if let Some(body) = document.body() {
-                        let current_class = body.class_name();
-                        let new_class = current_class.replace("homepage", "").trim().to_string();
-                        body.set_class_name(&new_class);
-                    }
-                }
+            if let Some(body) = window().and_then(|w| w.document()).and_then(|d| d.body()) {
+                let current_class = body.class_name();
+                let new_class = current_class.replace("homepage", "").trim().to_string();
+                body.set_class_name(&new_class);
             }
         }
     });
@@ -1,37 +1,212 @@
+use std::rc::Rc;
+use chrono::{Local, NaiveDate};
 use yew::prelude::*;
-use crate::models::Inside;
+use yew_router::prelude::*;
+use crate::models::{Inside, Timestamp};
 use crate::frontend::components::navigation::Navigation;
 use crate::frontend::components::card::{Card, CardContent, CardFooter};
 use crate::frontend::components::modal::Modal;
-use crate::frontend::services::storage;
+use crate::frontend::components::markdown::{Markdown, MarkdownEditor};
+use crate::frontend::components::outside_picker::{LinkedOutsides, OutsidePicker};
+use crate::frontend::components::view_menu::ViewMenu;
+use crate::frontend::components::bulk_bar::BulkBar;
+use crate::frontend::components::calendar::{month_of, Calendar};
+use crate::frontend::components::timeline::{anchor_for, scroll_to, Timeline};
+use crate::frontend::pages::not_found::NotFound;
+use crate::frontend::services::capture::Capture;
+use crate::frontend::services::router::Route;
+use crate::frontend::services::{live, selection, storage, tags, url_share};
+use crate::frontend::services::storage::Transfer;
+use crate::frontend::services::shortcuts::{self, PageCommand, LIST_COMMANDS};
+use crate::frontend::services::store::{use_store, StoreAction};
+use crate::frontend::services::tab_sync::Collection;
+use crate::frontend::services::view::{self, Layout, SortKey, ViewOptions, ViewQuery};
+
+fn alert(message: &str) {
+    if let Some(window) = web_sys::window() {
+        let _ = window.alert_with_message(message);
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct InsidePageProps {
+    // Record to open in the edit modal, from `/inside/:uuid/edit`
+    #[prop_or_default]
+    pub edit: Option<String>,
+    // Entry to add with the form filled in, from `/capture`
+    #[prop_or_default]
+    pub capture: Option<Capture>,
+}
 
 #[function_component(InsidePage)]
-pub fn inside_page() -> Html {
-    let insides = use_state(|| {
-        let mut data = storage::get_insides();
-        data.sort_by(|a, b| b.date.cmp(&a.date));
-        data
-    });
+pub fn inside_page(props: &InsidePageProps) -> Html {
+    let store = use_store();
     let show_modal = use_state(|| false);
     let edit_uuid = use_state(|| None::<String>);
-    let context_input = use_state(|| String::new());
-
-    let _refresh_data = {
+    let context_input = use_state(String::new);
+    let date_input = use_state(String::new);
+    let outside_uuids_input = use_state(Vec::<String>::new);
+    let conflict = use_state(|| false);
+    let location = use_location();
+    let navigator = use_navigator();
+    let query = location
+        .as_ref()
+        .and_then(|l| l.query::<ViewQuery>().ok())
+        .unwrap_or_default();
+    let view_options = use_state(|| view::load_inside_view().with_query(&query, &SortKey::INSIDE));
+    let search = use_state(|| query.q.clone().unwrap_or_default());
+    // Card under the keyboard cursor
+    let cursor = use_state(|| None::<usize>);
+    // Checked cards, and the last one clicked as the start of a shift-click range
+    let selected = use_state(Vec::<String>::new);
+    let anchor = use_state(|| None::<usize>);
+    let insides = use_memo((store.revision, *view_options, (*search).clone()), |(_, options, search)| {
+        let mut list: Vec<Inside> = store
+            .inside
+            .iter()
+            .filter(|i| view::inside_matches(i, search))
+            .cloned()
+            .collect();
+        view::sort_insides(&mut list, options);
+        list
+    });
+    // Entries shown as cards: all of them, or the selected day's
+    let cards = {
         let insides = insides.clone();
+        use_memo((store.revision, *view_options, (*search).clone()), move |(_, options, _)| {
+            let shown = match options.layout {
+                Layout::Cards => true,
+                Layout::Calendar => options.day.is_some(),
+                Layout::Timeline => false,
+            };
+            insides
+                .iter()
+                .filter(|i| shown && options.day.is_none_or(|day| i.date.day() == day))
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+    };
+    // Month shown by the calendar
+    let month = use_state(|| month_of(view_options.day.unwrap_or_else(|| Local::now().date_naive())));
+
+    // Mirror the view options in the query string so the view can be linked
+    {
+        let navigator = navigator.clone();
+        let routed = props.edit.is_some() || props.capture.is_some();
+        use_effect_with((*view_options, (*search).clone()), move |(options, search)| {
+            if let (Some(navigator), false) = (navigator, routed) {
+                let _ = navigator.replace_with_query(&Route::Inside, &options.to_query().with_search(search));
+            }
+            || ()
+        });
+    }
+
+    {
+        let cards = cards.clone();
+        use_effect_with(*cursor, move |cursor| {
+            if let Some(inside) = cursor.and_then(|i| cards.get(i)) {
+                shortcuts::reveal(&format!("card-{}", inside.uuid));
+            }
+            || ()
+        });
+    }
+
+    let on_search = {
+        let search = search.clone();
+        let cursor = cursor.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            search.set(input.value());
+            cursor.set(None);
+        })
+    };
+
+    let on_view_change = {
+        let view_options = view_options.clone();
+        Callback::from(move |options: ViewOptions| {
+            view::save_inside_view(&options);
+            view_options.set(options);
+        })
+    };
+
+    // Clicking the selected day again clears the filter
+    let on_select_day = {
+        let view_options = view_options.clone();
+        Callback::from(move |day: NaiveDate| {
+            let day = (view_options.day != Some(day)).then_some(day);
+            view_options.set(ViewOptions { day, ..*view_options });
+        })
+    };
+
+    let clear_day = {
+        let view_options = view_options.clone();
         Callback::from(move |_: MouseEvent| {
-            let mut insides_data = storage::get_insides();
-            insides_data.sort_by(|a, b| b.date.cmp(&a.date));
-            insides.set(insides_data);
+            view_options.set(ViewOptions { day: None, ..*view_options });
+        })
+    };
+
+    let on_month = {
+        let month = month.clone();
+        Callback::from(move |first: NaiveDate| month.set(first))
+    };
+
+    // The timeline scrolls to the nearest week; other layouts show that day
+    let on_jump = {
+        let view_options = view_options.clone();
+        let month = month.clone();
+        let insides = insides.clone();
+        Callback::from(move |e: Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            let Ok(day) = NaiveDate::parse_from_str(&input.value(), "%Y-%m-%d") else {
+                return;
+            };
+            if view_options.layout == Layout::Timeline {
+                if let Some(id) = anchor_for(&insides, day) {
+                    scroll_to(&id);
+                }
+            } else {
+                month.set(month_of(day));
+                view_options.set(ViewOptions { day: Some(day), ..*view_options });
+            }
         })
     };
 
+    // Flag edits another tab or a teammate made to the record open in the modal
+    {
+        let conflict = conflict.clone();
+        let editing = (*edit_uuid).clone();
+        let remote_change = store.remote_change.clone();
+        use_effect_with(store.revision, move |_| {
+            let touched = editing
+                .zip(remote_change)
+                .is_some_and(|(uuid, change)| change.collection == Collection::Inside && change.touches(&uuid));
+            if touched {
+                conflict.set(true);
+            }
+            || ()
+        });
+    }
+
+    // Let teammates see which record is open for editing
+    let co_editors = {
+        let editing = (*show_modal).then(|| (*edit_uuid).clone()).flatten();
+        let active = editing.is_some();
+        live::use_presence(editing, active)
+    };
+
     let show_add_modal = {
         let show_modal = show_modal.clone();
         let edit_uuid = edit_uuid.clone();
         let context_input = context_input.clone();
-        Callback::from(move |_: MouseEvent| {
+        let date_input = date_input.clone();
+        let outside_uuids_input = outside_uuids_input.clone();
+        let conflict = conflict.clone();
+        Callback::from(move |_: ()| {
+            conflict.set(false);
             edit_uuid.set(None);
             context_input.set(String::new());
+            date_input.set(Timestamp::now().to_input_value());
+            outside_uuids_input.set(Vec::new());
             show_modal.set(true);
         })
     };
@@ -40,20 +215,78 @@ pub fn inside_page() -> Html {
         let show_modal = show_modal.clone();
         let edit_uuid = edit_uuid.clone();
         let context_input = context_input.clone();
-        let insides = insides.clone();
+        let date_input = date_input.clone();
+        let outside_uuids_input = outside_uuids_input.clone();
+        let store = store.clone();
+        let conflict = conflict.clone();
         Callback::from(move |uuid: String| {
-            if let Some(inside) = insides.iter().find(|i| i.uuid == uuid) {
+            if let Some(inside) = store.find_inside(&uuid) {
+                conflict.set(false);
                 context_input.set(inside.context.clone());
+                date_input.set(inside.date.to_input_value());
+                outside_uuids_input.set(inside.outside_uuids.clone());
                 edit_uuid.set(Some(uuid));
                 show_modal.set(true);
             }
         })
     };
 
+    // Open the edit modal when the URL names a record, close it when the URL moves on
+    {
+        let show_modal = show_modal.clone();
+        let show_edit_modal = show_edit_modal.clone();
+        use_effect_with(props.edit.clone(), move |edit| {
+            match edit {
+                Some(uuid) => show_edit_modal.emit(uuid.clone()),
+                None => show_modal.set(false),
+            }
+            || ()
+        });
+    }
+
+    // Open the add modal filled in from `/capture`, linked to the Outside entry of the same URL
+    {
+        let show_add_modal = show_add_modal.clone();
+        let context_input = context_input.clone();
+        let outside_uuids_input = outside_uuids_input.clone();
+        let store = store.clone();
+        use_effect_with(props.capture.clone(), move |capture| {
+            if let Some(capture) = capture {
+                show_add_modal.emit(());
+                context_input.set(capture.inside_context());
+                outside_uuids_input.set(
+                    store
+                        .outside
+                        .iter()
+                        .filter(|o| !capture.url.is_empty() && o.url == capture.url)
+                        .map(|o| o.uuid.clone())
+                        .collect(),
+                );
+            }
+            || ()
+        });
+    }
+
+    let open_edit = {
+        let navigator = navigator.clone();
+        Callback::from(move |uuid: String| {
+            if let Some(navigator) = &navigator {
+                navigator.push(&Route::InsideEdit { uuid });
+            }
+        })
+    };
+
     let close_modal = {
         let show_modal = show_modal.clone();
+        let navigator = navigator.clone();
+        let routed = props.edit.is_some() || props.capture.is_some();
+        let view_options = view_options.clone();
+        let search = search.clone();
         Callback::from(move |_| {
             show_modal.set(false);
+            if let (Some(navigator), true) = (&navigator, routed) {
+                let _ = navigator.push_with_query(&Route::Inside, &view_options.to_query().with_search(&search));
+            }
         })
     };
 
@@ -65,104 +298,304 @@ pub fn inside_page() -> Html {
         })
     };
 
+    let on_date_change = {
+        let date_input = date_input.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            date_input.set(input.value());
+        })
+    };
+
+    let on_outsides_change = {
+        let outside_uuids_input = outside_uuids_input.clone();
+        Callback::from(move |uuids: Vec<String>| {
+            outside_uuids_input.set(uuids);
+        })
+    };
+
     let submit_inside = {
         let context_input = context_input.clone();
+        let date_input = date_input.clone();
+        let outside_uuids_input = outside_uuids_input.clone();
         let edit_uuid = edit_uuid.clone();
-        let insides = insides.clone();
-        let show_modal = show_modal.clone();
+        let store = store.clone();
+        let close_modal = close_modal.clone();
+        let conflict = conflict.clone();
         Callback::from(move |_| {
+            if *conflict {
+                let confirmed = web_sys::window()
+                    .and_then(|w| w.confirm_with_message("This entry was changed elsewhere. Overwrite those changes?").ok())
+                    .unwrap_or(false);
+                if !confirmed {
+                    return;
+                }
+            }
+            let Some(date) = Timestamp::parse(&date_input) else {
+                if let Some(window) = web_sys::window() {
+                    let _ = window.alert_with_message("Please enter a valid date and time.");
+                }
+                return;
+            };
             let context = (*context_input).clone();
+            let outside_uuids = (*outside_uuids_input).clone();
             let uuid = (*edit_uuid).clone();
             
-            let result = if let Some(ref uuid) = uuid {
-                // Update: preserve existing date (handled in storage.rs, but explicitly use existing date)
-                let existing_item = insides.iter().find(|i| i.uuid == *uuid);
-                let date = existing_item
-                    .map(|i| i.date.clone())
-                    .unwrap_or_else(|| String::new());
-                
-                let inside = Inside {
-                    uuid: uuid.clone(),
+            let action = if let Some(uuid) = uuid {
+                StoreAction::UpdateInside(Inside {
+                    uuid,
                     context,
                     date,
-                };
-                storage::update_inside(uuid, inside)
+                    outside_uuids,
+                    ..Default::default()
+                })
             } else {
-                // Create new: set current timestamp
-                let inside = Inside {
+                StoreAction::AddInside(Inside {
                     uuid: uuid::Uuid::new_v4().to_string(),
                     context,
-                    date: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-                };
-                storage::add_inside(inside)
+                    date,
+                    outside_uuids,
+                    ..Default::default()
+                })
             };
-
-            match result {
-                Ok(_) => {
-                    let mut insides_data = storage::get_insides();
-                    insides_data.sort_by(|a, b| b.date.cmp(&a.date));
-                    insides.set(insides_data);
-                    web_sys::console::log_1(&"Inside saved successfully!".into());
-                }
-                Err(e) => web_sys::console::log_1(&format!("Failed to save inside: {}", e).into()),
+            // Keep the form open so nothing typed is lost
+            match store.run(action) {
+                Ok(()) => close_modal.emit(()),
+                Err(e) => alert(&format!("Failed to save: {}", e)),
             }
-            
-            show_modal.set(false);
         })
     };
 
     let delete_inside_callback = {
-        let insides = insides.clone();
+        let store = store.clone();
         Callback::from(move |uuid: String| {
-            match storage::delete_inside(&uuid) {
-                Ok(_) => {
-                    let mut insides_data = storage::get_insides();
-                    insides_data.sort_by(|a, b| b.date.cmp(&a.date));
-                    insides.set(insides_data);
-                    web_sys::console::log_1(&"Inside deleted successfully!".into());
+            store.run(StoreAction::DeleteInside(uuid)).unwrap_or_else(|e| alert(&format!("Failed to save: {}", e)));
+        })
+    };
+
+    let on_command = {
+        let show_add_modal = show_add_modal.clone();
+        let open_edit = open_edit.clone();
+        let delete_inside_callback = delete_inside_callback.clone();
+        let cursor = cursor.clone();
+        let cards = cards.clone();
+        Callback::from(move |command: PageCommand| {
+            let current = cursor.and_then(|i| cards.get(i));
+            match command {
+                PageCommand::New => show_add_modal.emit(()),
+                PageCommand::Search => shortcuts::focus("search"),
+                PageCommand::Next | PageCommand::Previous => {
+                    cursor.set(shortcuts::step(*cursor, cards.len(), command));
+                }
+                PageCommand::Edit => {
+                    if let Some(inside) = current {
+                        open_edit.emit(inside.uuid.clone());
+                    }
+                }
+                PageCommand::Delete => {
+                    if let Some(inside) = current.filter(|i| shortcuts::confirm(&format!("Delete the entry from {}?", i.date))) {
+                        delete_inside_callback.emit(inside.uuid.clone());
+                    }
                 }
-                Err(e) => web_sys::console::log_1(&format!("Failed to delete inside: {}", e).into()),
             }
         })
     };
+    shortcuts::use_page_commands(&LIST_COMMANDS, on_command);
+
+    let visible: Rc<Vec<String>> = Rc::new(cards.iter().map(|r| r.uuid.clone()).collect());
+    let selected_uuids: Vec<String> = selected
+        .iter()
+        .filter(|uuid| store.find_inside(uuid).is_some())
+        .cloned()
+        .collect();
+
+    let select_all = {
+        let selected = selected.clone();
+        let visible = visible.clone();
+        Callback::from(move |_: ()| selected.set((*visible).clone()))
+    };
+
+    let clear_selection = {
+        let selected = selected.clone();
+        Callback::from(move |_: ()| selected.set(Vec::new()))
+    };
+
+    let tag_selection = {
+        let store = store.clone();
+        let selected_uuids = selected_uuids.clone();
+        Callback::from(move |tag: String| {
+            let updated = selected_uuids
+                .iter()
+                .filter_map(|uuid| store.find_inside(uuid))
+                .map(|r| Inside { context: tags::add_tag(&r.context, &tag), ..r.clone() })
+                .collect();
+            store.run(StoreAction::UpdateInsides(updated)).unwrap_or_else(|e| alert(&format!("Failed to save: {}", e)));
+        })
+    };
+
+    let export_selection = {
+        let selected_uuids = selected_uuids.clone();
+        Callback::from(move |_: ()| storage::download_selection(&[], &selected_uuids))
+    };
+
+    let share_selection = {
+        let selected_uuids = selected_uuids.clone();
+        Callback::from(move |_: ()| match url_share::link_for(&storage::selection_data(&[], &selected_uuids)) {
+            Ok(link) => url_share::copy_link(link),
+            Err(e) => web_sys::console::log_1(&format!("Failed to create share link: {}", e).into()),
+        })
+    };
+
+    let transfer_selection = {
+        let store = store.clone();
+        let selected = selected.clone();
+        let selected_uuids = selected_uuids.clone();
+        Callback::from(move |(workspace, transfer): (String, Transfer)| {
+            let result = store.run(StoreAction::TransferRecords {
+                workspace,
+                outsides: Vec::new(),
+                insides: selected_uuids.clone(),
+                transfer,
+            });
+            match result {
+                Ok(()) if transfer == Transfer::Move => selected.set(Vec::new()),
+                Ok(()) => {}
+                Err(e) => alert(&format!("Failed to save: {}", e)),
+            }
+        })
+    };
+
+    let delete_selection = {
+        let store = store.clone();
+        let selected = selected.clone();
+        let selected_uuids = selected_uuids.clone();
+        Callback::from(move |_: ()| {
+            store.run(StoreAction::DeleteInsides(selected_uuids.clone())).unwrap_or_else(|e| alert(&format!("Failed to save: {}", e)));
+            selected.set(Vec::new());
+        })
+    };
+
+    if props.edit.as_deref().is_some_and(|uuid| store.find_inside(uuid).is_none()) {
+        return html! { <NotFound message="This Inside entry does not exist or was deleted." /> };
+    }
+
+    let render_card = |(index, inside): (usize, &Inside)| {
+        let edit_callback = open_edit.reform({
+            let uuid = inside.uuid.clone();
+            move |_| uuid.clone()
+        });
+        let delete_callback = delete_inside_callback.reform({
+            let uuid = inside.uuid.clone();
+            move |_| uuid.clone()
+        });
+        let is_selected = selected.contains(&inside.uuid);
+        let on_check = {
+            let selected = selected.clone();
+            let anchor = anchor.clone();
+            let visible = visible.clone();
+            Callback::from(move |e: MouseEvent| {
+                selected.set(selection::toggle(&selected, &visible, index, *anchor, e.shift_key()));
+                anchor.set(Some(index));
+            })
+        };
+        
+        html! {
+            <Card
+                key={inside.uuid.clone()}
+                id={format!("card-{}", inside.uuid)}
+                class={classes!("inside-card", (*cursor == Some(index)).then_some("cursor"), is_selected.then_some("selected"))}
+            >
+                <input type="checkbox" class="card-select" checked={is_selected} onclick={on_check} title="Select (shift-click for a range)" />
+                <CardContent class="inside-card-content">
+                    <Markdown source={inside.context.clone()} />
+                    <LinkedOutsides uuids={inside.outside_uuids.clone()} />
+                </CardContent>
+                <CardFooter>
+                    <div class="card-meta">
+                        <Link<Route> to={Route::InsideDetail { uuid: inside.uuid.clone() }} classes="date">{inside.date_label()}</Link<Route>>
+                    </div>
+                    <div class="card-actions">
+                        <button class="edit-btn" onclick={edit_callback}>{"✏️"}</button>
+                        <button class="delete-btn" onclick={delete_callback}>{"🗑️"}</button>
+                    </div>
+                </CardFooter>
+            </Card>
+        }
+    };
+
+    let day_filter = view_options.day.map(|day| html! {
+        <div class="day-filter">
+            {format!("Entries on {}", day.format("%A, %B %-d, %Y"))}
+            <button class="day-filter-clear" onclick={clear_day.clone()} title="Show all days">{"×"}</button>
+        </div>
+    });
 
     html! {
         <>
             <Navigation title="Relf" />
             
-            <div class="cards-container">
-                {
-                    insides.iter().map(|inside| {
-                        let edit_callback = show_edit_modal.reform({
-                            let uuid = inside.uuid.clone();
-                            move |_| uuid.clone()
-                        });
-                        let delete_callback = delete_inside_callback.reform({
-                            let uuid = inside.uuid.clone();
-                            move |_| uuid.clone()
-                        });
-                        
-                        html! {
-                            <Card key={inside.uuid.clone()} class="inside-card">
-                                <CardContent class="inside-card-content">
-                                    <p>{&inside.context}</p>
-                                </CardContent>
-                                <CardFooter>
-                                    <div class="card-meta">
-                                        <span class="date">{&inside.date}</span>
-                                    </div>
-                                    <div class="card-actions">
-                                        <button class="edit-btn" onclick={edit_callback}>{"✏️"}</button>
-                                        <button class="delete-btn" onclick={delete_callback}>{"🗑️"}</button>
-                                    </div>
-                                </CardFooter>
-                            </Card>
-                        }
-                    }).collect::<Html>()
+            <ViewMenu
+                options={*view_options}
+                keys={&SortKey::INSIDE[..]}
+                layouts={&Layout::INSIDE[..]}
+                on_change={on_view_change}
+            >
+                <input
+                    type="search"
+                    id="search"
+                    class="view-search"
+                    placeholder="Search…"
+                    value={(*search).clone()}
+                    oninput={on_search}
+                />
+                <input type="date" class="view-jump" title="Jump to date" onchange={on_jump} />
+                <button class="view-button" onclick={select_all.reform(|_| ())} title="Select all shown">{"☑️"}</button>
+            </ViewMenu>
+            
+            {
+                match view_options.layout {
+                    Layout::Cards => html! {
+                        <div class={classes!("cards-container", view_options.density.class(), (!selected_uuids.is_empty()).then_some("selecting"))}>
+                            {day_filter}
+                            {cards.iter().enumerate().map(render_card).collect::<Html>()}
+                        </div>
+                    },
+                    Layout::Calendar => html! {
+                        <div class="inside-layout">
+                            <Calendar
+                                month={*month}
+                                selected={view_options.day}
+                                on_select={on_select_day}
+                                on_month={on_month}
+                            />
+                            if view_options.day.is_some() {
+                                <div class={classes!("cards-container", view_options.density.class(), (!selected_uuids.is_empty()).then_some("selecting"))}>
+                                    {day_filter}
+                                    {cards.iter().enumerate().map(render_card).collect::<Html>()}
+                                </div>
+                            }
+                        </div>
+                    },
+                    Layout::Timeline => html! {
+                        <div class="inside-layout">
+                            <Timeline insides={insides.clone()} />
+                        </div>
+                    },
                 }
-            </div>
+            }
+            
+            <BulkBar
+                count={selected_uuids.len()}
+                matching={visible.len()}
+                on_select_all={select_all}
+                on_clear={clear_selection}
+                on_tag={tag_selection}
+                on_export={export_selection}
+                on_share={share_selection}
+                on_transfer={transfer_selection}
+                on_delete={delete_selection}
+            />
             
-            <button class="fab" onclick={show_add_modal}>{"+"}</button>
+            <button class="fab" onclick={show_add_modal.reform(|_| ())}>{"+"}</button>
             
             <Modal 
                 show={*show_modal} 
@@ -170,15 +603,33 @@ pub fn inside_page() -> Html {
                 title={""}
             >
                 <form class="modal-form" onsubmit={Callback::from(|e: SubmitEvent| e.prevent_default())}>
-                    <label for="context">{"Context:"}</label>
-                    <textarea 
-                        id="context" 
+                    if *conflict {
+                        <div class="conflict-warning">{"This entry was changed elsewhere."}</div>
+                    }
+                    if !co_editors.is_empty() {
+                        <div class="presence-note">{live::describe(&co_editors)}</div>
+                    }
+                    <label for="date">{"Date:"}</label>
+                    <input
+                        type="datetime-local"
+                        id="date"
+                        step="1"
                         required=true
+                        value={(*date_input).clone()}
+                        oninput={on_date_change}
+                    />
+                    
+                    <label for="context">{"Context:"}</label>
+                    <MarkdownEditor
+                        id="context"
                         value={(*context_input).clone()}
                         oninput={on_context_change}
                         rows="10"
                     />
                     
+                    <label>{"Resources:"}</label>
+                    <OutsidePicker selected={(*outside_uuids_input).clone()} on_change={on_outsides_change} />
+                    
                     <button type="button" id="submit-btn" onclick={submit_inside}>{"Submit"}</button>
                 </form>
             </Modal>
@@ -0,0 +1,212 @@
+use yew::prelude::*;
+use yew_router::prelude::*;
+use crate::models::Inside;
+use crate::frontend::components::navigation::Navigation;
+use crate::frontend::components::markdown::Markdown;
+use crate::frontend::components::outside_picker::LinkedOutsides;
+use crate::frontend::components::history::{FieldChange, History, HistoryEntry};
+use crate::frontend::pages::not_found::NotFound;
+use crate::frontend::services::live;
+use crate::frontend::services::related;
+use crate::frontend::services::router::Route;
+use crate::frontend::services::shortcuts::{self, PageCommand, DETAIL_COMMANDS};
+use crate::frontend::services::storage;
+use crate::frontend::services::wiki;
+use crate::frontend::services::store::{use_store, StoreAction};
+use crate::frontend::services::view::by;
+
+fn alert(message: &str) {
+    if let Some(window) = web_sys::window() {
+        let _ = window.alert_with_message(message);
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct InsideDetailProps {
+    pub uuid: String,
+}
+
+// Fields that differ between a record and the version that replaced it
+fn field_changes(before: &Inside, after: &Inside) -> Vec<FieldChange> {
+    let fields = [
+        ("Date", before.date.to_string(), after.date.to_string()),
+        ("Context", before.context.clone(), after.context.clone()),
+    ];
+    fields
+        .into_iter()
+        .filter(|(_, before, after)| before != after)
+        .map(|(field, before, after)| FieldChange { field, before, after })
+        .collect()
+}
+
+fn history_entries(current: &Inside) -> Vec<HistoryEntry> {
+    let mut newer = current.clone();
+    storage::get_history(&current.uuid)
+        .into_iter()
+        .filter_map(|revision| {
+            let before = serde_json::from_value::<Inside>(revision.record).ok()?;
+            let entry = HistoryEntry {
+                replaced_at: revision.replaced_at,
+                changes: field_changes(&before, &newer),
+            };
+            newer = before;
+            Some(entry)
+        })
+        .collect()
+}
+
+#[function_component(InsideDetail)]
+pub fn inside_detail(props: &InsideDetailProps) -> Html {
+    let store = use_store();
+    let navigator = use_navigator();
+    let viewers = live::use_presence(Some(props.uuid.clone()), false);
+    let history = {
+        let store = store.clone();
+        use_memo((props.uuid.clone(), store.revision), move |(uuid, _)| {
+            store.find_inside(uuid).map(history_entries).unwrap_or_default()
+        })
+    };
+
+    // `e` edits and `d` deletes the record shown
+    let on_command = {
+        let store = store.clone();
+        let navigator = navigator.clone();
+        let uuid = props.uuid.clone();
+        Callback::from(move |command: PageCommand| {
+            let Some(navigator) = &navigator else {
+                return;
+            };
+            match command {
+                PageCommand::Edit => navigator.push(&Route::InsideEdit { uuid: uuid.clone() }),
+                PageCommand::Delete
+                    if store.find_inside(&uuid).is_some_and(|record| shortcuts::confirm(&format!("Delete the entry from {}?", record.date))) =>
+                {
+                    match store.run(StoreAction::DeleteInside(uuid.clone())) {
+                        Ok(()) => navigator.push(&Route::Inside),
+                        Err(e) => alert(&format!("Failed to save: {}", e)),
+                    }
+                }
+                _ => {}
+            }
+        })
+    };
+    shortcuts::use_page_commands(&DETAIL_COMMANDS, on_command);
+
+    let Some(inside) = store.find_inside(&props.uuid) else {
+        return html! { <NotFound message="This Inside entry does not exist or was deleted." /> };
+    };
+
+    let delete = {
+        let store = store.clone();
+        let uuid = inside.uuid.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Err(e) = store.run(StoreAction::DeleteInside(uuid.clone())) {
+                alert(&format!("Failed to save: {}", e));
+            } else if let Some(navigator) = &navigator {
+                navigator.push(&Route::Inside);
+            }
+        })
+    };
+
+    let route = Route::InsideDetail { uuid: inside.uuid.clone() };
+    let (backlink_outsides, backlink_insides) = wiki::backlinks(&route, &store.outside, &store.inside);
+    // Entries already listed as backlinks are not repeated under Related
+    let related_outsides: Vec<_> = related::outsides_for_inside(inside, &store.outside)
+        .into_iter()
+        .filter(|o| !backlink_outsides.iter().any(|b| b.uuid == o.uuid) && !inside.outside_uuids.contains(&o.uuid))
+        .collect();
+    let related_insides: Vec<_> = related::insides_for_inside(inside, &store.inside)
+        .into_iter()
+        .filter(|i| !backlink_insides.iter().any(|b| b.uuid == i.uuid))
+        .collect();
+
+    html! {
+        <>
+            <Navigation title="Relf" />
+
+            <div class="detail-container">
+                <Link<Route> to={Route::Inside} classes="detail-back">{"← Inside"}</Link<Route>>
+
+                <div class="detail-card">
+                    <div class="detail-header">
+                        <span class="date">{inside.date_label()}</span>
+                        <div class="card-actions">
+                            <Link<Route> to={Route::InsideEdit { uuid: inside.uuid.clone() }} classes="edit-btn">{"✏️"}</Link<Route>>
+                            <button class="delete-btn" onclick={delete}>{"🗑️"}</button>
+                        </div>
+                    </div>
+                    if inside.updated_at.is_some() || inside.created_by.is_some() || !viewers.is_empty() {
+                        <div class="detail-meta">
+                            if let Some(created_by) = &inside.created_by {
+                                <span>{format!("Added by {}", created_by)}</span>
+                            }
+                            if let Some(updated_at) = &inside.updated_at {
+                                <span>{format!("Updated {}{}", updated_at, by(&inside.updated_by))}</span>
+                            }
+                            if !viewers.is_empty() {
+                                <span class="presence-note">{live::describe(&viewers)}</span>
+                            }
+                        </div>
+                    }
+                    <Markdown source={inside.context.clone()} class="detail-context" />
+                    <LinkedOutsides uuids={inside.outside_uuids.clone()} />
+                </div>
+
+                <section class="detail-section">
+                    <h3>{"Backlinks"}</h3>
+                    if backlink_outsides.is_empty() && backlink_insides.is_empty() {
+                        <p class="detail-empty">{"No entries link here yet."}</p>
+                    }
+                    <ul class="detail-links">
+                        {
+                            backlink_outsides.iter().map(|o| html! {
+                                <li key={o.uuid.clone()}>
+                                    <Link<Route> to={Route::OutsideDetail { uuid: o.uuid.clone() }}>{&o.name}</Link<Route>>
+                                    <span class="detail-link-note">{"Outside"}</span>
+                                </li>
+                            }).collect::<Html>()
+                        }
+                        {
+                            backlink_insides.iter().map(|i| html! {
+                                <li key={i.uuid.clone()}>
+                                    <Link<Route> to={Route::InsideDetail { uuid: i.uuid.clone() }}>{related::excerpt(&i.context, 80)}</Link<Route>>
+                                    <span class="detail-link-note">{i.date.to_string()}</span>
+                                </li>
+                            }).collect::<Html>()
+                        }
+                    </ul>
+                </section>
+
+                <section class="detail-section">
+                    <h3>{"Related"}</h3>
+                    if related_outsides.is_empty() && related_insides.is_empty() {
+                        <p class="detail-empty">{"Nothing related yet."}</p>
+                    }
+                    <ul class="detail-links">
+                        {
+                            related_outsides.iter().map(|o| html! {
+                                <li key={o.uuid.clone()}>
+                                    <Link<Route> to={Route::OutsideDetail { uuid: o.uuid.clone() }}>{&o.name}</Link<Route>>
+                                    <span class="detail-link-note">{"mentioned"}</span>
+                                </li>
+                            }).collect::<Html>()
+                        }
+                        {
+                            related_insides.iter().map(|i| html! {
+                                <li key={i.uuid.clone()}>
+                                    <Link<Route> to={Route::InsideDetail { uuid: i.uuid.clone() }}>{related::excerpt(&i.context, 80)}</Link<Route>>
+                                    <span class="detail-link-note">{"same day"}</span>
+                                </li>
+                            }).collect::<Html>()
+                        }
+                    </ul>
+                </section>
+
+                <section class="detail-section">
+                    <h3>{"History"}</h3>
+                    <History entries={(*history).clone()} />
+                </section>
+            </div>
+        </>
+    }
+}
@@ -1,4 +1,11 @@
 pub mod home;
 pub mod inside;
 pub mod outside;
-pub mod data;
\ No newline at end of file
+pub mod data;
+pub mod unlock;
+pub mod not_found;
+pub mod outside_detail;
+pub mod inside_detail;
+pub mod stats;
+pub mod shared_view;
+pub mod capture;
@@ -0,0 +1,28 @@
+use yew::prelude::*;
+use yew_router::prelude::*;
+use crate::frontend::components::navigation::Navigation;
+use crate::frontend::services::router::Route;
+
+#[derive(Properties, PartialEq)]
+pub struct NotFoundProps {
+    #[prop_or(AttrValue::Static("This page does not exist."))]
+    pub message: AttrValue,
+}
+
+#[function_component(NotFound)]
+pub fn not_found(props: &NotFoundProps) -> Html {
+    html! {
+        <>
+            <Navigation title="Relf" />
+            <div class="not-found">
+                <h1>{"Not Found"}</h1>
+                <p>{props.message.clone()}</p>
+                <div class="not-found-links">
+                    <Link<Route> to={Route::Outside}>{"Outside"}</Link<Route>>
+                    <Link<Route> to={Route::Inside}>{"Inside"}</Link<Route>>
+                    <Link<Route> to={Route::Relf}>{"Home"}</Link<Route>>
+                </div>
+            </div>
+        </>
+    }
+}
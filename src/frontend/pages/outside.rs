@@ -1,61 +1,138 @@
+use std::rc::Rc;
 use yew::prelude::*;
-use crate::models::Outside;
+use yew_router::prelude::*;
+use crate::models::{Outside, Status};
 use crate::frontend::components::navigation::Navigation;
 use crate::frontend::components::card::{Card, CardHeader, CardContent, CardFooter};
 use crate::frontend::components::modal::Modal;
-use crate::frontend::services::storage;
+use crate::frontend::components::markdown::{Markdown, MarkdownEditor};
+use crate::frontend::components::view_menu::ViewMenu;
+use crate::frontend::components::bulk_bar::BulkBar;
+use crate::frontend::pages::not_found::NotFound;
+use crate::frontend::services::capture::Capture;
+use crate::frontend::services::markdown::safe_url;
+use crate::frontend::services::router::Route;
+use crate::frontend::services::{live, selection, storage, tags, url_share};
+use crate::frontend::services::storage::Transfer;
+use crate::frontend::services::shortcuts::{self, PageCommand, LIST_COMMANDS};
+use crate::frontend::services::store::{use_store, StoreAction};
+use crate::frontend::services::tab_sync::Collection;
+use crate::frontend::services::view::{self, SortKey, ViewOptions, ViewQuery};
+
+fn alert(message: &str) {
+    if let Some(window) = web_sys::window() {
+        let _ = window.alert_with_message(message);
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct OutsidePageProps {
+    // Record to open in the edit modal, from `/outside/:uuid/edit`
+    #[prop_or_default]
+    pub edit: Option<String>,
+    // Entry to add with the form filled in, from `/capture`
+    #[prop_or_default]
+    pub capture: Option<Capture>,
+}
 
 #[function_component(OutsidePage)]
-pub fn outside_page() -> Html {
-    let outsides = use_state(|| {
-        let mut data = storage::get_outsides();
-        data.sort_by(|a, b| {
-            match (a.percentage, b.percentage) {
-                (Some(ap), Some(bp)) => {
-                    let cmp = bp.cmp(&ap); // Descending order for percentage
-                    if cmp == std::cmp::Ordering::Equal {
-                        a.name.cmp(&b.name) // Ascending order for name
-                    } else {
-                        cmp
-                    }
-                }
-                (Some(_), None) => std::cmp::Ordering::Less, // Non-null comes first
-                (None, Some(_)) => std::cmp::Ordering::Greater, // Null comes last
-                (None, None) => a.name.cmp(&b.name), // Ascending order for name
-            }
-        });
-        data
-    });
+pub fn outside_page(props: &OutsidePageProps) -> Html {
+    let store = use_store();
     let show_modal = use_state(|| false);
     let edit_uuid = use_state(|| None::<String>);
-    let name_input = use_state(|| String::new());
-    let context_input = use_state(|| String::new());
-    let url_input = use_state(|| String::new());
-    let percentage_input = use_state(|| String::new());
+    let name_input = use_state(String::new);
+    let context_input = use_state(String::new);
+    let url_input = use_state(String::new);
+    let percentage_input = use_state(String::new);
+    let status_input = use_state(|| None::<Status>);
+    let conflict = use_state(|| false);
+    let location = use_location();
+    let navigator = use_navigator();
+    let query = location
+        .as_ref()
+        .and_then(|l| l.query::<ViewQuery>().ok())
+        .unwrap_or_default();
+    let view_options = use_state(|| view::load_outside_view().with_query(&query, &SortKey::OUTSIDE));
+    let search = use_state(|| query.q.clone().unwrap_or_default());
+    // Card under the keyboard cursor
+    let cursor = use_state(|| None::<usize>);
+    // Checked cards, and the last one clicked as the start of a shift-click range
+    let selected = use_state(Vec::<String>::new);
+    let anchor = use_state(|| None::<usize>);
+    let outsides = use_memo((store.revision, *view_options, (*search).clone()), |(_, options, search)| {
+        let mut list: Vec<Outside> = store
+            .outside
+            .iter()
+            .filter(|o| view::outside_matches(o, search))
+            .cloned()
+            .collect();
+        view::sort_outsides(&mut list, options);
+        list
+    });
 
-    let _refresh_data = {
+    // Mirror the view options in the query string so the view can be linked
+    {
+        let navigator = navigator.clone();
+        let routed = props.edit.is_some() || props.capture.is_some();
+        use_effect_with((*view_options, (*search).clone()), move |(options, search)| {
+            if let (Some(navigator), false) = (navigator, routed) {
+                let _ = navigator.replace_with_query(&Route::Outside, &options.to_query().with_search(search));
+            }
+            || ()
+        });
+    }
+
+    {
         let outsides = outsides.clone();
-        Callback::from(move |_: MouseEvent| {
-            let mut outsides_data = storage::get_outsides();
-            outsides_data.sort_by(|a, b| {
-                match (a.percentage, b.percentage) {
-                    (Some(ap), Some(bp)) => {
-                        let cmp = bp.cmp(&ap); // Descending order for percentage
-                        if cmp == std::cmp::Ordering::Equal {
-                            a.name.cmp(&b.name) // Ascending order for name
-                        } else {
-                            cmp
-                        }
-                    }
-                    (Some(_), None) => std::cmp::Ordering::Less, // Non-null comes first
-                    (None, Some(_)) => std::cmp::Ordering::Greater, // Null comes last
-                    (None, None) => a.name.cmp(&b.name), // Ascending order for name
-                }
-            });
-            outsides.set(outsides_data);
+        use_effect_with(*cursor, move |cursor| {
+            if let Some(outside) = cursor.and_then(|i| outsides.get(i)) {
+                shortcuts::reveal(&format!("card-{}", outside.uuid));
+            }
+            || ()
+        });
+    }
+
+    let on_search = {
+        let search = search.clone();
+        let cursor = cursor.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            search.set(input.value());
+            cursor.set(None);
+        })
+    };
+
+    let on_view_change = {
+        let view_options = view_options.clone();
+        Callback::from(move |options: ViewOptions| {
+            view::save_outside_view(&options);
+            view_options.set(options);
         })
     };
 
+    // Flag edits another tab or a teammate made to the record open in the modal
+    {
+        let conflict = conflict.clone();
+        let editing = (*edit_uuid).clone();
+        let remote_change = store.remote_change.clone();
+        use_effect_with(store.revision, move |_| {
+            let touched = editing
+                .zip(remote_change)
+                .is_some_and(|(uuid, change)| change.collection == Collection::Outside && change.touches(&uuid));
+            if touched {
+                conflict.set(true);
+            }
+            || ()
+        });
+    }
+
+    // Let teammates see which record is open for editing
+    let co_editors = {
+        let editing = (*show_modal).then(|| (*edit_uuid).clone()).flatten();
+        let active = editing.is_some();
+        live::use_presence(editing, active)
+    };
+
     let show_add_modal = {
         let show_modal = show_modal.clone();
         let edit_uuid = edit_uuid.clone();
@@ -63,12 +140,16 @@ pub fn outside_page() -> Html {
         let context_input = context_input.clone();
         let url_input = url_input.clone();
         let percentage_input = percentage_input.clone();
-        Callback::from(move |_: MouseEvent| {
+        let status_input = status_input.clone();
+        let conflict = conflict.clone();
+        Callback::from(move |_: ()| {
+            conflict.set(false);
             edit_uuid.set(None);
             name_input.set(String::new());
             context_input.set(String::new());
             url_input.set(String::new());
             percentage_input.set(String::new());
+            status_input.set(None);
             show_modal.set(true);
         })
     };
@@ -80,23 +161,73 @@ pub fn outside_page() -> Html {
         let context_input = context_input.clone();
         let url_input = url_input.clone();
         let percentage_input = percentage_input.clone();
-        let outsides = outsides.clone();
+        let status_input = status_input.clone();
+        let store = store.clone();
+        let conflict = conflict.clone();
         Callback::from(move |uuid: String| {
-            if let Some(outside) = outsides.iter().find(|o| o.uuid == uuid) {
+            if let Some(outside) = store.find_outside(&uuid) {
+                conflict.set(false);
                 name_input.set(outside.name.clone());
                 context_input.set(outside.context.clone());
                 url_input.set(outside.url.clone());
                 percentage_input.set(outside.percentage.map(|p| p.to_string()).unwrap_or_default());
+                status_input.set(outside.status);
                 edit_uuid.set(Some(uuid));
                 show_modal.set(true);
             }
         })
     };
 
+    // Open the edit modal when the URL names a record, close it when the URL moves on
+    {
+        let show_modal = show_modal.clone();
+        let show_edit_modal = show_edit_modal.clone();
+        use_effect_with(props.edit.clone(), move |edit| {
+            match edit {
+                Some(uuid) => show_edit_modal.emit(uuid.clone()),
+                None => show_modal.set(false),
+            }
+            || ()
+        });
+    }
+
+    // Open the add modal filled in from `/capture`
+    {
+        let show_add_modal = show_add_modal.clone();
+        let name_input = name_input.clone();
+        let context_input = context_input.clone();
+        let url_input = url_input.clone();
+        use_effect_with(props.capture.clone(), move |capture| {
+            if let Some(capture) = capture {
+                show_add_modal.emit(());
+                name_input.set(capture.outside_name());
+                context_input.set(capture.text.clone());
+                url_input.set(capture.url.clone());
+            }
+            || ()
+        });
+    }
+
+    let open_edit = {
+        let navigator = navigator.clone();
+        Callback::from(move |uuid: String| {
+            if let Some(navigator) = &navigator {
+                navigator.push(&Route::OutsideEdit { uuid });
+            }
+        })
+    };
+
     let close_modal = {
         let show_modal = show_modal.clone();
+        let navigator = navigator.clone();
+        let routed = props.edit.is_some() || props.capture.is_some();
+        let view_options = view_options.clone();
+        let search = search.clone();
         Callback::from(move |_| {
             show_modal.set(false);
+            if let (Some(navigator), true) = (&navigator, routed) {
+                let _ = navigator.push_with_query(&Route::Outside, &view_options.to_query().with_search(&search));
+            }
         })
     };
 
@@ -129,22 +260,44 @@ pub fn outside_page() -> Html {
         Callback::from(move |e: InputEvent| {
             let input: web_sys::HtmlInputElement = e.target_unchecked_into();
             let value = input.value();
-            // Allow empty string or valid numbers only
-            if value.is_empty() || value.chars().all(|c| c.is_ascii_digit()) {
+            // Allow an empty value or a whole number from 0 to 100
+            let valid = value.is_empty()
+                || (value.chars().all(|c| c.is_ascii_digit()) && value.parse::<u32>().is_ok_and(|p| p <= 100));
+            if valid {
                 percentage_input.set(value);
+            } else {
+                input.set_value(&percentage_input);
             }
         })
     };
 
+    let on_status_change = {
+        let status_input = status_input.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            status_input.set(Status::parse(&select.value()));
+        })
+    };
+
     let submit_outside = {
         let name_input = name_input.clone();
         let context_input = context_input.clone();
         let url_input = url_input.clone();
         let percentage_input = percentage_input.clone();
+        let status_input = status_input.clone();
         let edit_uuid = edit_uuid.clone();
-        let outsides = outsides.clone();
-        let show_modal = show_modal.clone();
+        let store = store.clone();
+        let close_modal = close_modal.clone();
+        let conflict = conflict.clone();
         Callback::from(move |_| {
+            if *conflict {
+                let confirmed = web_sys::window()
+                    .and_then(|w| w.confirm_with_message("This entry was changed elsewhere. Overwrite those changes?").ok())
+                    .unwrap_or(false);
+                if !confirmed {
+                    return;
+                }
+            }
             let name = (*name_input).clone();
             let context = (*context_input).clone();
             let url = (*url_input).clone();
@@ -161,79 +314,170 @@ pub fn outside_page() -> Html {
                 context,
                 url,
                 percentage,
+                status: *status_input,
+                ..Default::default()
             };
 
-            let result = if let Some(ref uuid) = uuid {
-                storage::update_outside(uuid, outside)
+            let saved = store.run(if uuid.is_some() {
+                StoreAction::UpdateOutside(outside)
             } else {
-                storage::add_outside(outside)
-            };
-
-            match result {
-                Ok(_) => {
-                    let mut outsides_data = storage::get_outsides();
-                    outsides_data.sort_by(|a, b| {
-                        match (a.percentage, b.percentage) {
-                            (Some(ap), Some(bp)) => {
-                                let cmp = bp.cmp(&ap); // Descending order for percentage
-                                if cmp == std::cmp::Ordering::Equal {
-                                    a.name.cmp(&b.name) // Ascending order for name
-                                } else {
-                                    cmp
-                                }
-                            }
-                            (Some(_), None) => std::cmp::Ordering::Less, // Non-null comes first
-                            (None, Some(_)) => std::cmp::Ordering::Greater, // Null comes last
-                            (None, None) => a.name.cmp(&b.name), // Ascending order for name
-                        }
-                    });
-                    outsides.set(outsides_data);
-                    web_sys::console::log_1(&"Outside saved successfully!".into());
-                }
-                Err(e) => web_sys::console::log_1(&format!("Failed to save outside: {}", e).into()),
-            }
+                StoreAction::AddOutside(outside)
+            });
             
-            show_modal.set(false);
+            // Keep the form open so nothing typed is lost
+            match saved {
+                Ok(()) => close_modal.emit(()),
+                Err(e) => alert(&format!("Failed to save: {}", e)),
+            }
         })
     };
 
     let delete_outside_callback = {
-        let outsides = outsides.clone();
+        let store = store.clone();
         Callback::from(move |uuid: String| {
-            match storage::delete_outside(&uuid) {
-                Ok(_) => {
-                    let mut outsides_data = storage::get_outsides();
-                    outsides_data.sort_by(|a, b| {
-                        match (a.percentage, b.percentage) {
-                            (Some(ap), Some(bp)) => {
-                                let cmp = bp.cmp(&ap); // Descending order for percentage
-                                if cmp == std::cmp::Ordering::Equal {
-                                    a.name.cmp(&b.name) // Ascending order for name
-                                } else {
-                                    cmp
-                                }
-                            }
-                            (Some(_), None) => std::cmp::Ordering::Less, // Non-null comes first
-                            (None, Some(_)) => std::cmp::Ordering::Greater, // Null comes last
-                            (None, None) => a.name.cmp(&b.name), // Ascending order for name
-                        }
-                    });
-                    outsides.set(outsides_data);
-                    web_sys::console::log_1(&"Outside deleted successfully!".into());
+            store.run(StoreAction::DeleteOutside(uuid)).unwrap_or_else(|e| alert(&format!("Failed to save: {}", e)));
+        })
+    };
+
+    let on_command = {
+        let show_add_modal = show_add_modal.clone();
+        let open_edit = open_edit.clone();
+        let delete_outside_callback = delete_outside_callback.clone();
+        let cursor = cursor.clone();
+        let outsides = outsides.clone();
+        Callback::from(move |command: PageCommand| {
+            let current = cursor.and_then(|i| outsides.get(i));
+            match command {
+                PageCommand::New => show_add_modal.emit(()),
+                PageCommand::Search => shortcuts::focus("search"),
+                PageCommand::Next | PageCommand::Previous => {
+                    cursor.set(shortcuts::step(*cursor, outsides.len(), command));
+                }
+                PageCommand::Edit => {
+                    if let Some(outside) = current {
+                        open_edit.emit(outside.uuid.clone());
+                    }
+                }
+                PageCommand::Delete => {
+                    if let Some(outside) = current.filter(|o| shortcuts::confirm(&format!("Delete \"{}\"?", o.name))) {
+                        delete_outside_callback.emit(outside.uuid.clone());
+                    }
                 }
-                Err(e) => web_sys::console::log_1(&format!("Failed to delete outside: {}", e).into()),
             }
         })
     };
+    shortcuts::use_page_commands(&LIST_COMMANDS, on_command);
+
+    let visible: Rc<Vec<String>> = Rc::new(outsides.iter().map(|r| r.uuid.clone()).collect());
+    let selected_uuids: Vec<String> = selected
+        .iter()
+        .filter(|uuid| store.find_outside(uuid).is_some())
+        .cloned()
+        .collect();
+
+    let select_all = {
+        let selected = selected.clone();
+        let visible = visible.clone();
+        Callback::from(move |_: ()| selected.set((*visible).clone()))
+    };
+
+    let clear_selection = {
+        let selected = selected.clone();
+        Callback::from(move |_: ()| selected.set(Vec::new()))
+    };
+
+    let tag_selection = {
+        let store = store.clone();
+        let selected_uuids = selected_uuids.clone();
+        Callback::from(move |tag: String| {
+            let updated = selected_uuids
+                .iter()
+                .filter_map(|uuid| store.find_outside(uuid))
+                .map(|r| Outside { context: tags::add_tag(&r.context, &tag), ..r.clone() })
+                .collect();
+            store.run(StoreAction::UpdateOutsides(updated)).unwrap_or_else(|e| alert(&format!("Failed to save: {}", e)));
+        })
+    };
+
+    let set_percentage = {
+        let store = store.clone();
+        let selected_uuids = selected_uuids.clone();
+        Callback::from(move |percentage: Option<i32>| {
+            let updated = selected_uuids
+                .iter()
+                .filter_map(|uuid| store.find_outside(uuid))
+                .map(|o| Outside { percentage, ..o.clone() })
+                .collect();
+            store.run(StoreAction::UpdateOutsides(updated)).unwrap_or_else(|e| alert(&format!("Failed to save: {}", e)));
+        })
+    };
+
+    let export_selection = {
+        let selected_uuids = selected_uuids.clone();
+        Callback::from(move |_: ()| storage::download_selection(&selected_uuids, &[]))
+    };
+
+    let share_selection = {
+        let selected_uuids = selected_uuids.clone();
+        Callback::from(move |_: ()| match url_share::link_for(&storage::selection_data(&selected_uuids, &[])) {
+            Ok(link) => url_share::copy_link(link),
+            Err(e) => web_sys::console::log_1(&format!("Failed to create share link: {}", e).into()),
+        })
+    };
+
+    let transfer_selection = {
+        let store = store.clone();
+        let selected = selected.clone();
+        let selected_uuids = selected_uuids.clone();
+        Callback::from(move |(workspace, transfer): (String, Transfer)| {
+            let result = store.run(StoreAction::TransferRecords {
+                workspace,
+                outsides: selected_uuids.clone(),
+                insides: Vec::new(),
+                transfer,
+            });
+            match result {
+                Ok(()) if transfer == Transfer::Move => selected.set(Vec::new()),
+                Ok(()) => {}
+                Err(e) => alert(&format!("Failed to save: {}", e)),
+            }
+        })
+    };
+
+    let delete_selection = {
+        let store = store.clone();
+        let selected = selected.clone();
+        let selected_uuids = selected_uuids.clone();
+        Callback::from(move |_: ()| {
+            store.run(StoreAction::DeleteOutsides(selected_uuids.clone())).unwrap_or_else(|e| alert(&format!("Failed to save: {}", e)));
+            selected.set(Vec::new());
+        })
+    };
+
+    if props.edit.as_deref().is_some_and(|uuid| store.find_outside(uuid).is_none()) {
+        return html! { <NotFound message="This Outside entry does not exist or was deleted." /> };
+    }
 
     html! {
         <>
             <Navigation title="Relf" />
             
-            <div class="cards-container">
+            <ViewMenu options={*view_options} keys={&SortKey::OUTSIDE[..]} on_change={on_view_change}>
+                <input
+                    type="search"
+                    id="search"
+                    class="view-search"
+                    placeholder="Search…"
+                    value={(*search).clone()}
+                    oninput={on_search}
+                />
+                <button class="view-button" onclick={select_all.reform(|_| ())} title="Select all shown">{"☑️"}</button>
+            </ViewMenu>
+            
+            <div class={classes!("cards-container", view_options.density.class(), (!selected_uuids.is_empty()).then_some("selecting"))}>
                 {
-                    outsides.iter().map(|outside| {
-                        let edit_callback = show_edit_modal.reform({
+                    outsides.iter().enumerate().map(|(index, outside)| {
+                        let edit_callback = open_edit.reform({
                             let uuid = outside.uuid.clone();
                             move |_: MouseEvent| uuid.clone()
                         });
@@ -241,14 +485,31 @@ pub fn outside_page() -> Html {
                             let uuid = outside.uuid.clone();
                             move |_: MouseEvent| uuid.clone()
                         });
+                        let is_selected = selected.contains(&outside.uuid);
+                        let on_check = {
+                            let selected = selected.clone();
+                            let anchor = anchor.clone();
+                            let visible = visible.clone();
+                            Callback::from(move |e: MouseEvent| {
+                                selected.set(selection::toggle(&selected, &visible, index, *anchor, e.shift_key()));
+                                anchor.set(Some(index));
+                            })
+                        };
                         
                         html! {
-                            <Card key={outside.uuid.clone()} class="outside-card">
+                            <Card
+                                key={outside.uuid.clone()}
+                                id={format!("card-{}", outside.uuid)}
+                                class={classes!("outside-card", (*cursor == Some(index)).then_some("cursor"), is_selected.then_some("selected"))}
+                            >
+                                <input type="checkbox" class="card-select" checked={is_selected} onclick={on_check} title="Select (shift-click for a range)" />
                                 <CardHeader>
-                                    <h3>{&outside.name}</h3>
+                                    <h3>
+                                        <Link<Route> to={Route::OutsideDetail { uuid: outside.uuid.clone() }} classes="card-title-link">{&outside.name}</Link<Route>>
+                                    </h3>
                                 </CardHeader>
                                 <CardContent class="outside-card-content">
-                                    <p>{&outside.context}</p>
+                                    <Markdown source={outside.context.clone()} />
                                 </CardContent>
                                 <CardFooter>
                                     <div class="card-meta">
@@ -259,9 +520,12 @@ pub fn outside_page() -> Html {
                                                 html! {}
                                             }
                                         }
+                                        if let Some(status) = outside.status {
+                                            <span class={classes!("status-badge", status.as_str())}>{status.label()}</span>
+                                        }
                                     </div>
                                     <div class="card-actions">
-                                        <a href={outside.url.clone()} target="_blank" class="url-link">{"🔗"}</a>
+                                        <a href={safe_url(&outside.url)} target="_blank" rel="noopener noreferrer" class="url-link">{"🔗"}</a>
                                         <button class="edit-btn" onclick={edit_callback}>{"✏️"}</button>
                                         <button class="delete-btn" onclick={delete_callback}>{"🗑️"}</button>
                                     </div>
@@ -272,7 +536,20 @@ pub fn outside_page() -> Html {
                 }
             </div>
             
-            <button class="fab" onclick={show_add_modal}>{"+"}</button>
+            <BulkBar
+                count={selected_uuids.len()}
+                matching={visible.len()}
+                on_select_all={select_all}
+                on_clear={clear_selection}
+                on_tag={tag_selection}
+                on_percentage={set_percentage}
+                on_export={export_selection}
+                on_share={share_selection}
+                on_transfer={transfer_selection}
+                on_delete={delete_selection}
+            />
+            
+            <button class="fab" onclick={show_add_modal.reform(|_| ())}>{"+"}</button>
             
             <Modal 
                 show={*show_modal} 
@@ -280,6 +557,12 @@ pub fn outside_page() -> Html {
                 title={""}
             >
                 <form class="modal-form" onsubmit={Callback::from(|e: SubmitEvent| e.prevent_default())}>
+                    if *conflict {
+                        <div class="conflict-warning">{"This entry was changed elsewhere."}</div>
+                    }
+                    if !co_editors.is_empty() {
+                        <div class="presence-note">{live::describe(&co_editors)}</div>
+                    }
                     <label for="name">{"Name:"}</label>
                     <input 
                         type="text" 
@@ -290,9 +573,8 @@ pub fn outside_page() -> Html {
                     />
                     
                     <label for="context">{"Context:"}</label>
-                    <textarea 
-                        id="context" 
-                        required=true
+                    <MarkdownEditor
+                        id="context"
                         value={(*context_input).clone()}
                         oninput={on_context_change}
                     />
@@ -306,13 +588,38 @@ pub fn outside_page() -> Html {
                         oninput={on_url_change}
                     />
                     
+                    <label for="status">{"Status:"}</label>
+                    <select id="status" class="status-select" onchange={on_status_change}>
+                        <option value="" selected={status_input.is_none()}>{"No status"}</option>
+                        {
+                            Status::ALL.iter().map(|status| html! {
+                                <option value={status.as_str()} selected={*status_input == Some(*status)}>
+                                    {status.label()}
+                                </option>
+                            }).collect::<Html>()
+                        }
+                    </select>
+                    
                     <label for="percentage">{"Percentage:"}</label>
-                    <input
-                        type="text"
-                        id="percentage"
-                        value={(*percentage_input).clone()}
-                        oninput={on_percentage_change}
-                    />
+                    <div class="progress-control">
+                        <input
+                            type="range"
+                            min="0"
+                            max="100"
+                            class={classes!("progress-slider", percentage_input.is_empty().then_some("unset"))}
+                            value={if percentage_input.is_empty() { "0".to_string() } else { (*percentage_input).clone() }}
+                            oninput={on_percentage_change.clone()}
+                        />
+                        <input
+                            type="text"
+                            id="percentage"
+                            inputmode="numeric"
+                            placeholder="—"
+                            value={(*percentage_input).clone()}
+                            oninput={on_percentage_change}
+                        />
+                        <span class="progress-unit">{"%"}</span>
+                    </div>
                     
                     <button type="button" id="submit-btn" onclick={submit_outside}>{"Submit"}</button>
                 </form>
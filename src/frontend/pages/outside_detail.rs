@@ -0,0 +1,272 @@
+use yew::prelude::*;
+use yew_router::prelude::*;
+use crate::models::{Outside, Status};
+use crate::frontend::components::navigation::Navigation;
+use crate::frontend::components::markdown::Markdown;
+use crate::frontend::components::history::{FieldChange, History, HistoryEntry};
+use crate::frontend::pages::not_found::NotFound;
+use crate::frontend::services::live;
+use crate::frontend::services::markdown::safe_url;
+use crate::frontend::services::related;
+use crate::frontend::services::router::Route;
+use crate::frontend::services::shortcuts::{self, PageCommand, DETAIL_COMMANDS};
+use crate::frontend::services::storage;
+use crate::frontend::services::wiki;
+use crate::frontend::services::store::{use_store, StoreAction};
+use crate::frontend::services::view::{by, domain_of};
+
+fn alert(message: &str) {
+    if let Some(window) = web_sys::window() {
+        let _ = window.alert_with_message(message);
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct OutsideDetailProps {
+    pub uuid: String,
+}
+
+fn percentage_label(percentage: Option<i32>) -> String {
+    percentage.map(|p| format!("{}%", p)).unwrap_or_else(|| "—".to_string())
+}
+
+fn status_label(status: Option<Status>) -> String {
+    status.map(|s| s.label().to_string()).unwrap_or_else(|| "—".to_string())
+}
+
+// Fields that differ between a record and the version that replaced it
+fn field_changes(before: &Outside, after: &Outside) -> Vec<FieldChange> {
+    let fields = [
+        ("Name", before.name.clone(), after.name.clone()),
+        ("Context", before.context.clone(), after.context.clone()),
+        ("URL", before.url.clone(), after.url.clone()),
+        ("Percentage", percentage_label(before.percentage), percentage_label(after.percentage)),
+        ("Status", status_label(before.status), status_label(after.status)),
+    ];
+    fields
+        .into_iter()
+        .filter(|(_, before, after)| before != after)
+        .map(|(field, before, after)| FieldChange { field, before, after })
+        .collect()
+}
+
+fn history_entries(current: &Outside) -> Vec<HistoryEntry> {
+    let mut newer = current.clone();
+    storage::get_history(&current.uuid)
+        .into_iter()
+        .filter_map(|revision| {
+            let before = serde_json::from_value::<Outside>(revision.record).ok()?;
+            let entry = HistoryEntry {
+                replaced_at: revision.replaced_at,
+                changes: field_changes(&before, &newer),
+            };
+            newer = before;
+            Some(entry)
+        })
+        .collect()
+}
+
+#[function_component(OutsideDetail)]
+pub fn outside_detail(props: &OutsideDetailProps) -> Html {
+    let store = use_store();
+    let navigator = use_navigator();
+    let viewers = live::use_presence(Some(props.uuid.clone()), false);
+    let history = {
+        let store = store.clone();
+        use_memo((props.uuid.clone(), store.revision), move |(uuid, _)| {
+            store.find_outside(uuid).map(history_entries).unwrap_or_default()
+        })
+    };
+
+    // `e` edits and `d` deletes the record shown
+    let on_command = {
+        let store = store.clone();
+        let navigator = navigator.clone();
+        let uuid = props.uuid.clone();
+        Callback::from(move |command: PageCommand| {
+            let Some(navigator) = &navigator else {
+                return;
+            };
+            match command {
+                PageCommand::Edit => navigator.push(&Route::OutsideEdit { uuid: uuid.clone() }),
+                PageCommand::Delete
+                    if store.find_outside(&uuid).is_some_and(|record| shortcuts::confirm(&format!("Delete \"{}\"?", record.name))) =>
+                {
+                    match store.run(StoreAction::DeleteOutside(uuid.clone())) {
+                        Ok(()) => navigator.push(&Route::Outside),
+                        Err(e) => alert(&format!("Failed to save: {}", e)),
+                    }
+                }
+                _ => {}
+            }
+        })
+    };
+    shortcuts::use_page_commands(&DETAIL_COMMANDS, on_command);
+
+    let Some(outside) = store.find_outside(&props.uuid) else {
+        return html! { <NotFound message="This Outside entry does not exist or was deleted." /> };
+    };
+
+    let delete = {
+        let store = store.clone();
+        let uuid = outside.uuid.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Err(e) = store.run(StoreAction::DeleteOutside(uuid.clone())) {
+                alert(&format!("Failed to save: {}", e));
+            } else if let Some(navigator) = &navigator {
+                navigator.push(&Route::Outside);
+            }
+        })
+    };
+
+    let route = Route::OutsideDetail { uuid: outside.uuid.clone() };
+    let (backlink_outsides, backlink_insides) = wiki::backlinks(&route, &store.outside, &store.inside);
+    // Entries already listed as backlinks are not repeated under Related
+    let related_outsides: Vec<_> = related::outsides_for_outside(outside, &store.outside)
+        .into_iter()
+        .filter(|o| !backlink_outsides.iter().any(|b| b.uuid == o.uuid))
+        .collect();
+    let timeline = related::linked_insides(outside, &store.inside);
+    let related_insides: Vec<_> = related::insides_for_outside(outside, &store.inside)
+        .into_iter()
+        .filter(|i| !backlink_insides.iter().chain(&timeline).any(|b| b.uuid == i.uuid))
+        .collect();
+    let domain = domain_of(&outside.url);
+
+    html! {
+        <>
+            <Navigation title="Relf" />
+
+            <div class="detail-container">
+                <Link<Route> to={Route::Outside} classes="detail-back">{"← Outside"}</Link<Route>>
+
+                <div class="detail-card">
+                    <div class="detail-header">
+                        <h2>{&outside.name}</h2>
+                        <div class="card-actions">
+                            <a href={safe_url(&outside.url)} target="_blank" rel="noopener noreferrer" class="url-link">{"🔗"}</a>
+                            <Link<Route> to={Route::OutsideEdit { uuid: outside.uuid.clone() }} classes="edit-btn">{"✏️"}</Link<Route>>
+                            <button class="delete-btn" onclick={delete}>{"🗑️"}</button>
+                        </div>
+                    </div>
+                    <div class="detail-meta">
+                        if let Some(p) = outside.percentage {
+                            <span class="percentage">{format!("{}%", p)}</span>
+                        }
+                        if let Some(status) = outside.status {
+                            <span class={classes!("status-badge", status.as_str())}>{status.label()}</span>
+                        }
+                        if !domain.is_empty() {
+                            <span>{domain.clone()}</span>
+                        }
+                        if let Some(created_at) = &outside.created_at {
+                            <span>{format!("Added {}{}", created_at, by(&outside.created_by))}</span>
+                        }
+                        if let Some(updated_at) = &outside.updated_at {
+                            <span>{format!("Updated {}{}", updated_at, by(&outside.updated_by))}</span>
+                        }
+                        if !viewers.is_empty() {
+                            <span class="presence-note">{live::describe(&viewers)}</span>
+                        }
+                    </div>
+                    <Markdown source={outside.context.clone()} class="detail-context" />
+                </div>
+
+                <section class="detail-section">
+                    <h3>{"Progress"}</h3>
+                    if outside.progress_history.is_empty() {
+                        <p class="detail-empty">{"No progress recorded yet."}</p>
+                    }
+                    <ul class="progress-history">
+                        {
+                            outside.progress_history.iter().rev().map(|entry| html! {
+                                <li class="progress-entry">
+                                    <span class="progress-date">
+                                        {if entry.at.is_empty() { "Before tracking" } else { entry.at.as_str() }}
+                                    </span>
+                                    <div class="progress-bar">
+                                        <div class="progress-fill" style={format!("width: {}%", entry.percentage.unwrap_or(0))}></div>
+                                    </div>
+                                    <span class="progress-value">{percentage_label(entry.percentage)}</span>
+                                    <span class="progress-status">{status_label(entry.status)}</span>
+                                </li>
+                            }).collect::<Html>()
+                        }
+                    </ul>
+                </section>
+
+                <section class="detail-section">
+                    <h3>{"Timeline"}</h3>
+                    if timeline.is_empty() {
+                        <p class="detail-empty">{"No Inside notes are linked to this resource yet."}</p>
+                    }
+                    <ul class="timeline">
+                        {
+                            timeline.iter().map(|i| html! {
+                                <li key={i.uuid.clone()} class="timeline-item">
+                                    <Link<Route> to={Route::InsideDetail { uuid: i.uuid.clone() }} classes="timeline-date">{i.date.to_string()}</Link<Route>>
+                                    <Markdown source={i.context.clone()} class="timeline-context" />
+                                </li>
+                            }).collect::<Html>()
+                        }
+                    </ul>
+                </section>
+
+                <section class="detail-section">
+                    <h3>{"Backlinks"}</h3>
+                    if backlink_outsides.is_empty() && backlink_insides.is_empty() {
+                        <p class="detail-empty">{"No entries link here yet."}</p>
+                    }
+                    <ul class="detail-links">
+                        {
+                            backlink_outsides.iter().map(|o| html! {
+                                <li key={o.uuid.clone()}>
+                                    <Link<Route> to={Route::OutsideDetail { uuid: o.uuid.clone() }}>{&o.name}</Link<Route>>
+                                    <span class="detail-link-note">{"Outside"}</span>
+                                </li>
+                            }).collect::<Html>()
+                        }
+                        {
+                            backlink_insides.iter().map(|i| html! {
+                                <li key={i.uuid.clone()}>
+                                    <Link<Route> to={Route::InsideDetail { uuid: i.uuid.clone() }}>{related::excerpt(&i.context, 80)}</Link<Route>>
+                                    <span class="detail-link-note">{i.date.to_string()}</span>
+                                </li>
+                            }).collect::<Html>()
+                        }
+                    </ul>
+                </section>
+
+                <section class="detail-section">
+                    <h3>{"Related"}</h3>
+                    if related_outsides.is_empty() && related_insides.is_empty() {
+                        <p class="detail-empty">{"Nothing related yet."}</p>
+                    }
+                    <ul class="detail-links">
+                        {
+                            related_outsides.iter().map(|o| html! {
+                                <li key={o.uuid.clone()}>
+                                    <Link<Route> to={Route::OutsideDetail { uuid: o.uuid.clone() }}>{&o.name}</Link<Route>>
+                                    <span class="detail-link-note">{"same site"}</span>
+                                </li>
+                            }).collect::<Html>()
+                        }
+                        {
+                            related_insides.iter().map(|i| html! {
+                                <li key={i.uuid.clone()}>
+                                    <Link<Route> to={Route::InsideDetail { uuid: i.uuid.clone() }}>{related::excerpt(&i.context, 80)}</Link<Route>>
+                                    <span class="detail-link-note">{i.date.to_string()}</span>
+                                </li>
+                            }).collect::<Html>()
+                        }
+                    </ul>
+                </section>
+
+                <section class="detail-section">
+                    <h3>{"History"}</h3>
+                    <History entries={(*history).clone()} />
+                </section>
+            </div>
+        </>
+    }
+}
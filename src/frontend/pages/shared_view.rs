@@ -0,0 +1,164 @@
+use gloo::events::EventListener;
+use web_sys::window;
+use yew::prelude::*;
+use yew_router::prelude::*;
+use crate::frontend::components::markdown::Markdown;
+use crate::frontend::components::navigation::Navigation;
+use crate::frontend::pages::not_found::NotFound;
+use crate::frontend::services::markdown::safe_url;
+use crate::frontend::services::router::Route;
+use crate::frontend::services::storage::{self, ExportInside, ExportOutside};
+use crate::frontend::services::store::use_store;
+use crate::frontend::services::url_share;
+use crate::frontend::services::view::domain_of;
+use crate::frontend::services::workspace;
+
+fn current_fragment() -> String {
+    window().and_then(|w| w.location().hash().ok()).unwrap_or_default()
+}
+
+fn plural(count: usize, one: &str, many: &str) -> String {
+    format!("{} {}", count, if count == 1 { one } else { many })
+}
+
+fn outside_card(outside: &ExportOutside) -> Html {
+    let domain = domain_of(&outside.url);
+    html! {
+        <div class="detail-card shared-card">
+            <div class="detail-header">
+                <h2>{&outside.name}</h2>
+                if !outside.url.trim().is_empty() {
+                    <div class="card-actions">
+                        <a href={safe_url(&outside.url)} target="_blank" rel="noopener noreferrer" class="url-link">{"🔗"}</a>
+                    </div>
+                }
+            </div>
+            <div class="detail-meta">
+                if let Some(p) = outside.percentage {
+                    <span class="percentage">{format!("{}%", p)}</span>
+                }
+                if let Some(status) = outside.status {
+                    <span class={classes!("status-badge", status.as_str())}>{status.label()}</span>
+                }
+                if !domain.is_empty() {
+                    <span>{domain}</span>
+                }
+            </div>
+            <Markdown source={outside.context.clone()} class="detail-context" />
+        </div>
+    }
+}
+
+fn inside_card(inside: &ExportInside) -> Html {
+    html! {
+        <div class="detail-card shared-card">
+            <div class="detail-header">
+                <span class="date">{inside.date.to_string()}</span>
+            </div>
+            if !inside.related.is_empty() {
+                <div class="detail-meta">
+                    <span>{format!("About {}", inside.related.join(", "))}</span>
+                </div>
+            }
+            <Markdown source={inside.context.clone()} class="detail-context" />
+        </div>
+    }
+}
+
+// Records decoded from the link fragment, shown read-only until imported
+#[function_component(SharedView)]
+pub fn shared_view() -> Html {
+    let store = use_store();
+    let navigator = use_navigator();
+    let fragment = use_state(current_fragment);
+
+    // Opening another share link while on this page only changes the fragment
+    {
+        let fragment = fragment.clone();
+        use_effect_with((), move |_| {
+            let listener = window().map(|w| {
+                EventListener::new(&w, "hashchange", move |_| fragment.set(current_fragment()))
+            });
+            move || drop(listener)
+        });
+    }
+
+    let shared = use_memo((*fragment).clone(), |fragment| url_share::decode(fragment));
+
+    let import = {
+        let store = store.clone();
+        let shared = shared.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Ok(data) = &*shared else {
+                return;
+            };
+            let name = workspace::find(&store.workspace).map(|w| w.name).unwrap_or_default();
+            let message = format!(
+                "Add {} and {} to \"{}\"?",
+                plural(data.outside.len(), "Outside resource", "Outside resources"),
+                plural(data.inside.len(), "Inside entry", "Inside entries"),
+                name
+            );
+            let confirmed = window()
+                .and_then(|w| w.confirm_with_message(&message).ok())
+                .unwrap_or(false);
+            if !confirmed {
+                return;
+            }
+            match storage::append_data(data.clone()) {
+                Ok(_) => {
+                    store.reload();
+                    if let Some(navigator) = &navigator {
+                        navigator.push(&if data.outside.is_empty() { Route::Inside } else { Route::Outside });
+                    }
+                }
+                Err(e) => {
+                    web_sys::console::log_1(&format!("Failed to import shared records: {}", e).into());
+                    if let Some(window) = window() {
+                        let _ = window.alert_with_message(&format!("Import failed: {}", e));
+                    }
+                }
+            }
+        })
+    };
+
+    let data = match &*shared {
+        Ok(data) => data,
+        Err(message) => return html! { <NotFound message={AttrValue::from(message.clone())} /> },
+    };
+
+    html! {
+        <>
+            <Navigation title="Relf" />
+
+            <div class="detail-container">
+                <div class="shared-header">
+                    <div>
+                        <h2>{"Shared with you"}</h2>
+                        <p class="shared-summary">
+                            {format!(
+                                "{} and {}, read-only",
+                                plural(data.outside.len(), "Outside resource", "Outside resources"),
+                                plural(data.inside.len(), "Inside entry", "Inside entries")
+                            )}
+                        </p>
+                    </div>
+                    <button class="shared-import" onclick={import}>{"📥 Import into my relf"}</button>
+                </div>
+
+                if !data.outside.is_empty() {
+                    <section class="detail-section">
+                        <h3>{"Outside"}</h3>
+                        { data.outside.iter().map(outside_card).collect::<Html>() }
+                    </section>
+                }
+                if !data.inside.is_empty() {
+                    <section class="detail-section">
+                        <h3>{"Inside"}</h3>
+                        { data.inside.iter().map(inside_card).collect::<Html>() }
+                    </section>
+                }
+            </div>
+        </>
+    }
+}
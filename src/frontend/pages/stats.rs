@@ -0,0 +1,192 @@
+use chrono::{Datelike, Days, Local};
+use yew::prelude::*;
+use crate::frontend::components::navigation::Navigation;
+use crate::frontend::services::stats::{self, PERCENTAGE_BUCKETS};
+use crate::frontend::services::store::use_store;
+
+const HEATMAP_WEEKS: u64 = 53;
+const RECENT_WEEKS: u64 = 12;
+const TOP_DOMAINS: usize = 10;
+
+// Width of a bar relative to the largest value
+fn bar_width(value: usize, max: usize) -> String {
+    let percent = (value * 100).checked_div(max).unwrap_or(0);
+    format!("width: {}%", percent)
+}
+
+#[function_component(Stats)]
+pub fn stats_page() -> Html {
+    let store = use_store();
+    let today = Local::now().date_naive();
+
+    let days = use_memo(store.revision, |_| stats::entries_per_day(&store.inside));
+    let streaks = stats::streaks(&days, today);
+    let weeks = stats::entries_per_week(&days, today, RECENT_WEEKS);
+    let max_week = weeks.iter().map(|(_, count)| *count).max().unwrap_or(0);
+
+    let inside_words: usize = store.inside.iter().map(|i| stats::word_count(&i.context)).sum();
+    let outside_words: usize = store.outside.iter().map(|o| stats::word_count(&o.context)).sum();
+    let average_words = inside_words.checked_div(store.inside.len()).unwrap_or(0);
+
+    let distribution = stats::percentage_distribution(&store.outside);
+    let max_bucket = distribution.iter().copied().max().unwrap_or(0);
+    let tags = stats::completion_by_tag(&store.outside);
+    let domains = stats::top_domains(&store.outside, &store.inside, TOP_DOMAINS);
+    let max_domain = domains.first().map(|(_, count)| *count).unwrap_or(0);
+
+    // Heatmap columns are weeks, rows Monday to Sunday
+    let first_week = stats::week_start(today) - Days::new((HEATMAP_WEEKS - 1) * 7);
+    let max_day = days
+        .range(first_week..=today)
+        .map(|(_, count)| *count)
+        .max()
+        .unwrap_or(0);
+    let heatmap = (0..HEATMAP_WEEKS).map(|week| {
+        let monday = first_week + Days::new(week * 7);
+        let month_label = (monday.day() <= 7).then(|| monday.format("%b").to_string());
+        html! {
+            <div class="heatmap-week">
+                <span class="heatmap-month">{month_label.unwrap_or_default()}</span>
+                {
+                    (0..7).map(|weekday| {
+                        let day = monday + Days::new(weekday);
+                        if day > today {
+                            return html! { <span class="heatmap-day future"></span> };
+                        }
+                        let count = days.get(&day).copied().unwrap_or(0);
+                        let title = format!("{}: {} {}", day, count, if count == 1 { "entry" } else { "entries" });
+                        html! {
+                            <span
+                                class={classes!("heatmap-day", format!("level-{}", stats::heat_level(count, max_day)))}
+                                title={title}
+                            ></span>
+                        }
+                    }).collect::<Html>()
+                }
+            </div>
+        }
+    }).collect::<Html>();
+
+    html! {
+        <>
+            <Navigation title="Relf" />
+
+            <div class="detail-container stats-container">
+                <div class="stats-tiles">
+                    <div class="stats-tile">
+                        <span class="stats-value">{store.inside.len()}</span>
+                        <span class="stats-label">{"Inside entries"}</span>
+                    </div>
+                    <div class="stats-tile">
+                        <span class="stats-value">{store.outside.len()}</span>
+                        <span class="stats-label">{"Outside resources"}</span>
+                    </div>
+                    <div class="stats-tile">
+                        <span class="stats-value">{streaks.current}</span>
+                        <span class="stats-label">{"Current streak (days)"}</span>
+                    </div>
+                    <div class="stats-tile">
+                        <span class="stats-value">{streaks.longest}</span>
+                        <span class="stats-label">{"Longest streak (days)"}</span>
+                    </div>
+                </div>
+
+                <section class="detail-section">
+                    <h3>{"Activity"}</h3>
+                    <div class="heatmap">{heatmap}</div>
+                    <div class="heatmap-legend">
+                        {"Less"}
+                        {(0..=4).map(|level| html! { <span class={classes!("heatmap-day", format!("level-{}", level))}></span> }).collect::<Html>()}
+                        {"More"}
+                    </div>
+                </section>
+
+                <section class="detail-section">
+                    <h3>{"Entries per week"}</h3>
+                    <ul class="stats-bars">
+                        {
+                            weeks.iter().map(|(monday, count)| html! {
+                                <li>
+                                    <span class="stats-bar-label">{monday.format("%b %-d").to_string()}</span>
+                                    <span class="stats-bar"><span class="stats-bar-fill" style={bar_width(*count, max_week)}></span></span>
+                                    <span class="stats-bar-value">{count}</span>
+                                </li>
+                            }).collect::<Html>()
+                        }
+                    </ul>
+                </section>
+
+                <section class="detail-section">
+                    <h3>{"Words"}</h3>
+                    <div class="stats-tiles">
+                        <div class="stats-tile">
+                            <span class="stats-value">{inside_words}</span>
+                            <span class="stats-label">{"Written in Inside"}</span>
+                        </div>
+                        <div class="stats-tile">
+                            <span class="stats-value">{average_words}</span>
+                            <span class="stats-label">{"Per Inside entry"}</span>
+                        </div>
+                        <div class="stats-tile">
+                            <span class="stats-value">{outside_words}</span>
+                            <span class="stats-label">{"Written in Outside"}</span>
+                        </div>
+                    </div>
+                </section>
+
+                <section class="detail-section">
+                    <h3>{"Outside progress"}</h3>
+                    <ul class="stats-bars">
+                        {
+                            PERCENTAGE_BUCKETS.iter().zip(distribution).map(|(label, count)| html! {
+                                <li>
+                                    <span class="stats-bar-label">{*label}</span>
+                                    <span class="stats-bar"><span class="stats-bar-fill" style={bar_width(count, max_bucket)}></span></span>
+                                    <span class="stats-bar-value">{count}</span>
+                                </li>
+                            }).collect::<Html>()
+                        }
+                    </ul>
+                </section>
+
+                <section class="detail-section">
+                    <h3>{"Completion by tag"}</h3>
+                    if tags.is_empty() {
+                        <p class="detail-empty">{"Add #tags to Outside contexts to see how many are finished."}</p>
+                    } else {
+                        <ul class="stats-bars">
+                            {
+                                tags.iter().map(|tag| html! {
+                                    <li>
+                                        <span class="stats-bar-label">{format!("#{}", tag.tag)}</span>
+                                        <span class="stats-bar"><span class="stats-bar-fill" style={bar_width(tag.done, tag.total)}></span></span>
+                                        <span class="stats-bar-value">{format!("{}/{}", tag.done, tag.total)}</span>
+                                    </li>
+                                }).collect::<Html>()
+                            }
+                        </ul>
+                    }
+                </section>
+
+                <section class="detail-section">
+                    <h3>{"Most linked domains"}</h3>
+                    if domains.is_empty() {
+                        <p class="detail-empty">{"No links yet."}</p>
+                    } else {
+                        <ul class="stats-bars">
+                            {
+                                domains.iter().map(|(domain, count)| html! {
+                                    <li>
+                                        <span class="stats-bar-label">{domain}</span>
+                                        <span class="stats-bar"><span class="stats-bar-fill" style={bar_width(*count, max_domain)}></span></span>
+                                        <span class="stats-bar-value">{count}</span>
+                                    </li>
+                                }).collect::<Html>()
+                            }
+                        </ul>
+                    }
+                </section>
+            </div>
+        </>
+    }
+}
@@ -0,0 +1,74 @@
+use yew::prelude::*;
+use web_sys::{window, HtmlInputElement};
+use crate::frontend::services::crypto;
+
+#[derive(Properties, PartialEq)]
+pub struct UnlockProps {
+    pub on_unlock: Callback<()>,
+}
+
+#[function_component(Unlock)]
+pub fn unlock(props: &UnlockProps) -> Html {
+    let passphrase = use_state(String::new);
+    let error = use_state(|| None::<String>);
+
+    let on_passphrase_change = {
+        let passphrase = passphrase.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            passphrase.set(input.value());
+        })
+    };
+
+    let submit = {
+        let passphrase = passphrase.clone();
+        let error = error.clone();
+        let on_unlock = props.on_unlock.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            match crypto::unlock(&passphrase) {
+                Ok(_) => {
+                    error.set(None);
+                    on_unlock.emit(());
+                }
+                Err(e) => error.set(Some(e)),
+            }
+        })
+    };
+
+    // Forgotten passphrase: the data cannot be recovered, start over
+    let reset = Callback::from(|_: MouseEvent| {
+        let confirmed = window()
+            .and_then(|w| w.confirm_with_message("Delete all encrypted data and start over? This cannot be undone.").ok())
+            .unwrap_or(false);
+        if confirmed {
+            if let Some(storage) = window().and_then(|w| w.local_storage().ok().flatten()) {
+                let _ = storage.clear();
+            }
+            if let Some(window) = window() {
+                let _ = window.location().reload();
+            }
+        }
+    });
+
+    html! {
+        <div class="container unlock">
+            <h1 class="title">{"Relf"}</h1>
+            <form class="unlock-form" onsubmit={submit}>
+                <label for="passphrase">{"Passphrase:"}</label>
+                <input
+                    type="password"
+                    id="passphrase"
+                    autocomplete="current-password"
+                    value={(*passphrase).clone()}
+                    oninput={on_passphrase_change}
+                />
+                if let Some(message) = &*error {
+                    <div class="unlock-error">{message}</div>
+                }
+                <button type="submit">{"Unlock"}</button>
+            </form>
+            <a class="unlock-reset" onclick={reset}>{"Forgot passphrase?"}</a>
+        </div>
+    }
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use web_sys::window;
+use crate::frontend::services::markdown::is_web_url;
+
+// Something to add, from `/capture?url=…&title=…&text=…`: sent by the bookmarklet or by the
+// share sheet once relf is installed. `to=inside` opens the Inside form instead of Outside
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Capture {
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+}
+
+impl Capture {
+    pub fn wants_inside(&self) -> bool {
+        self.to.as_deref() == Some("inside")
+    }
+
+    // Share sheets often put the link in `text` rather than `url`. Anything but a web address
+    // is dropped, since any page can open `/capture`
+    pub fn normalized(self) -> Self {
+        let url = self.url.trim();
+        let mut capture = Capture {
+            url: if is_web_url(url) { url.to_string() } else { String::new() },
+            title: self.title.trim().to_string(),
+            text: self.text.trim().to_string(),
+            to: self.to,
+        };
+        if capture.url.is_empty()
+            && let Some(link) = capture
+                .text
+                .split_whitespace()
+                .find(|word| word.starts_with("https://") || word.starts_with("http://"))
+                .map(str::to_string)
+        {
+            capture.text = capture.text.replacen(&link, "", 1).trim().to_string();
+            capture.url = link;
+        }
+        // Some apps send the title again as the text
+        if capture.text == capture.title {
+            capture.text.clear();
+        }
+        capture
+    }
+
+    // Name for a new Outside entry: the page title, or the link when there is none
+    pub fn outside_name(&self) -> String {
+        if self.title.is_empty() { self.url.clone() } else { self.title.clone() }
+    }
+
+    // Context for a new Inside entry: the text, then a Markdown link to where it came from
+    pub fn inside_context(&self) -> String {
+        let source = match (self.title.is_empty(), self.url.is_empty()) {
+            (_, true) => self.title.clone(),
+            (true, false) => self.url.clone(),
+            (false, false) => format!("[{}]({})", self.title.replace(['[', ']'], ""), self.url),
+        };
+        [self.text.as_str(), source.as_str()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+// A `javascript:` link that opens `/capture` on this origin for the page it is clicked on,
+// with any selected text
+pub fn bookmarklet(inside: bool) -> String {
+    let origin = window()
+        .and_then(|w| w.location().origin().ok())
+        .unwrap_or_default();
+    let target = if inside { "&to=inside" } else { "" };
+    format!(
+        "javascript:(function(){{var e=encodeURIComponent;window.open('{}/capture?url='+e(location.href)+'&title='+e(document.title)+'&text='+e(String(window.getSelection())){}');}})();",
+        origin, target
+    )
+}
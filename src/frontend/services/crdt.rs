@@ -0,0 +1,649 @@
+// Records as a conflict-free replicated document, so concurrent edits from several devices
+// merge the same way everywhere, whatever order the operations arrive in.
+//
+// Every operation carries a Lamport stamp. Text fields are sequences of characters that keep
+// the stamp they were inserted with (an RGA), so concurrent insertions interleave instead of
+// overwriting each other; every other field keeps the value with the highest stamp. `Doc` has
+// no browser dependencies: replicas can be simulated natively by applying each other's
+// operations in any order.
+use std::collections::{BTreeMap, BTreeSet};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use crate::frontend::services::tab_sync::Collection;
+
+// Fields merged character by character; the others keep the last value written
+const TEXT_FIELDS: &[&str] = &["name", "context"];
+
+// Orders operations: by counter, with ties broken by the replica that made them.
+// Stored as "<counter>@<replica>" since documents hold one per run of text
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Stamp {
+    pub counter: u64,
+    pub replica: String,
+}
+
+impl Serialize for Stamp {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{}@{}", self.counter, self.replica))
+    }
+}
+
+impl<'de> Deserialize<'de> for Stamp {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        let (counter, replica) = text
+            .split_once('@')
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid stamp: {}", text)))?;
+        Ok(Stamp {
+            counter: counter.parse().map_err(serde::de::Error::custom)?,
+            replica: replica.to_string(),
+        })
+    }
+}
+
+impl Stamp {
+    fn offset(&self, n: usize) -> Stamp {
+        Stamp {
+            counter: self.counter + n as u64,
+            replica: self.replica.clone(),
+        }
+    }
+}
+
+// Characters inserted together; character `n` has the stamp `id + n`.
+// Removed characters stay as a tombstone without text so later insertions can find their place
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct Span {
+    id: Stamp,
+    len: usize,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    text: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Text {
+    spans: Vec<Span>,
+}
+
+impl Text {
+    pub fn value(&self) -> String {
+        self.spans.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    // Stamps and characters that are not removed, in order
+    fn visible(&self) -> (Vec<Stamp>, Vec<char>) {
+        self.spans
+            .iter()
+            .flat_map(|span| span.text.chars().enumerate().map(|(n, c)| (span.id.offset(n), c)))
+            .unzip()
+    }
+
+    // Span holding a character, and its offset there
+    fn find(&self, id: &Stamp) -> Option<(usize, usize)> {
+        self.spans.iter().enumerate().find_map(|(index, span)| {
+            let contains =
+                span.id.replica == id.replica && id.counter >= span.id.counter && id.counter < span.id.counter + span.len as u64;
+            contains.then(|| (index, (id.counter - span.id.counter) as usize))
+        })
+    }
+
+    // Cut a span so that a new one starts `offset` characters into it
+    fn split(&mut self, index: usize, offset: usize) {
+        let span = &mut self.spans[index];
+        let text = if span.text.is_empty() {
+            String::new()
+        } else {
+            let at = span.text.char_indices().nth(offset).map(|(i, _)| i).unwrap_or(span.text.len());
+            span.text.split_off(at)
+        };
+        let tail = Span {
+            id: span.id.offset(offset),
+            len: span.len - offset,
+            text,
+        };
+        span.len = offset;
+        self.spans.insert(index + 1, tail);
+    }
+
+    // False while the character to insert after is unknown
+    fn insert(&mut self, id: &Stamp, after: Option<&Stamp>, text: &str) -> bool {
+        if text.is_empty() || self.find(id).is_some() {
+            return true;
+        }
+        let mut index = match after {
+            None => 0,
+            Some(after) => {
+                let Some((index, offset)) = self.find(after) else {
+                    return false;
+                };
+                if offset + 1 < self.spans[index].len {
+                    self.split(index, offset + 1);
+                }
+                index + 1
+            }
+        };
+        // Concurrent insertions at the same place go in descending stamp order
+        while self.spans.get(index).is_some_and(|s| s.id > *id) {
+            index += 1;
+        }
+        self.spans.insert(index, Span {
+            id: id.clone(),
+            len: text.chars().count(),
+            text: text.to_string(),
+        });
+        true
+    }
+
+    // False while the characters to remove are unknown
+    fn remove(&mut self, id: &Stamp, len: usize) -> bool {
+        if len == 0 {
+            return true;
+        }
+        if self.find(id).is_none() || self.find(&id.offset(len - 1)).is_none() {
+            return false;
+        }
+        let end = id.counter + len as u64;
+        let mut next = id.clone();
+        while next.counter < end {
+            let Some((mut index, offset)) = self.find(&next) else {
+                break;
+            };
+            if offset > 0 {
+                self.split(index, offset);
+                index += 1;
+            }
+            let remaining = (end - next.counter) as usize;
+            if self.spans[index].len > remaining {
+                self.split(index, remaining);
+            }
+            self.spans[index].text.clear();
+            next = next.offset(self.spans[index].len);
+        }
+        true
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+struct Register {
+    value: Value,
+    stamp: Stamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+struct RecordState {
+    // Earliest stamp seen for the record, which orders records the same way everywhere
+    #[serde(default)]
+    created: Option<Stamp>,
+    #[serde(default)]
+    fields: BTreeMap<String, Register>,
+    #[serde(default)]
+    texts: BTreeMap<String, Text>,
+    #[serde(default)]
+    deleted: Option<Stamp>,
+    // Latest stamp a value or text was written with; newer than `deleted` brings the record back
+    #[serde(default)]
+    written: Option<Stamp>,
+}
+
+impl RecordState {
+    fn visible(&self) -> bool {
+        let deleted = self.deleted.as_ref().is_some_and(|deleted| self.written.as_ref().is_none_or(|written| written < deleted));
+        !deleted && !self.fields.is_empty()
+    }
+
+    fn write(&mut self, stamp: &Stamp) {
+        if self.written.as_ref().is_none_or(|written| stamp > written) {
+            self.written = Some(stamp.clone());
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum Op {
+    // Give a field a new value; text fields get one when they first appear
+    Set {
+        collection: Collection,
+        uuid: String,
+        field: String,
+        value: Value,
+        stamp: Stamp,
+    },
+    // Insert text after a character, or at the start
+    Insert {
+        collection: Collection,
+        uuid: String,
+        field: String,
+        id: Stamp,
+        #[serde(default)]
+        after: Option<Stamp>,
+        text: String,
+    },
+    // Remove `len` characters, starting at `id`, that were inserted by one replica in a row
+    Remove {
+        collection: Collection,
+        uuid: String,
+        field: String,
+        id: Stamp,
+        len: usize,
+    },
+    // Delete the record; whichever is newer wins between it and a write to the record, so a
+    // record added again with the same uuid comes back
+    Delete {
+        collection: Collection,
+        uuid: String,
+        stamp: Stamp,
+    },
+}
+
+impl Op {
+    // Highest counter the operation uses, which later local operations must exceed
+    fn last_counter(&self) -> u64 {
+        match self {
+            Op::Set { stamp, .. } | Op::Delete { stamp, .. } => stamp.counter,
+            Op::Insert { id, text, .. } => id.counter + text.chars().count().saturating_sub(1) as u64,
+            Op::Remove { id, len, .. } => id.counter + len.saturating_sub(1) as u64,
+        }
+    }
+
+    fn target(&self) -> (Collection, &str) {
+        match self {
+            Op::Set { collection, uuid, .. }
+            | Op::Insert { collection, uuid, .. }
+            | Op::Remove { collection, uuid, .. }
+            | Op::Delete { collection, uuid, .. } => (*collection, uuid),
+        }
+    }
+}
+
+// Runs of stamps one replica made in a row, as (first stamp, length)
+fn runs(ids: &[Stamp]) -> Vec<(Stamp, usize)> {
+    let mut runs: Vec<(Stamp, usize)> = Vec::new();
+    for id in ids {
+        match runs.last_mut() {
+            Some((first, len)) if first.replica == id.replica && first.counter + *len as u64 == id.counter => *len += 1,
+            _ => runs.push((id.clone(), 1)),
+        }
+    }
+    runs
+}
+
+// One replica's copy of the records of a workspace
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Doc {
+    pub replica: String,
+    clock: u64,
+    #[serde(default)]
+    outside: BTreeMap<String, RecordState>,
+    #[serde(default)]
+    inside: BTreeMap<String, RecordState>,
+    // Operations that arrived before the text they refer to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pending: Vec<Op>,
+}
+
+impl Doc {
+    pub fn new(replica: impl Into<String>) -> Self {
+        Self {
+            replica: replica.into(),
+            ..Default::default()
+        }
+    }
+
+    fn states(&self, collection: Collection) -> &BTreeMap<String, RecordState> {
+        match collection {
+            Collection::Outside => &self.outside,
+            Collection::Inside => &self.inside,
+        }
+    }
+
+    fn state(&self, collection: Collection, uuid: &str) -> Option<&RecordState> {
+        self.states(collection).get(uuid)
+    }
+
+    fn state_mut(&mut self, op: &Op) -> &mut RecordState {
+        let (collection, uuid) = op.target();
+        let states = match collection {
+            Collection::Outside => &mut self.outside,
+            Collection::Inside => &mut self.inside,
+        };
+        let state = states.entry(uuid.to_string()).or_default();
+        let stamp = match op {
+            Op::Set { stamp, .. } | Op::Delete { stamp, .. } => stamp,
+            Op::Insert { id, .. } | Op::Remove { id, .. } => id,
+        };
+        if state.created.as_ref().is_none_or(|created| stamp < created) {
+            state.created = Some(stamp.clone());
+        }
+        state
+    }
+
+    // A stamp for a new local operation using `n` counters
+    fn tick(&mut self, n: usize) -> Stamp {
+        let stamp = Stamp {
+            counter: self.clock + 1,
+            replica: self.replica.clone(),
+        };
+        self.clock += n.max(1) as u64;
+        stamp
+    }
+
+    // False when the operation has to wait for others
+    fn integrate(&mut self, op: &Op) -> bool {
+        self.clock = self.clock.max(op.last_counter());
+        match op {
+            Op::Set { field, value, stamp, .. } => {
+                let state = self.state_mut(op);
+                state.write(stamp);
+                if state.fields.get(field).is_none_or(|r| *stamp > r.stamp) {
+                    state.fields.insert(field.clone(), Register {
+                        value: value.clone(),
+                        stamp: stamp.clone(),
+                    });
+                }
+                true
+            }
+            Op::Insert { field, id, after, text, .. } => {
+                let state = self.state_mut(op);
+                state.write(id);
+                state.texts.entry(field.clone()).or_default().insert(id, after.as_ref(), text)
+            }
+            Op::Remove { field, id, len, .. } => {
+                self.state_mut(op).texts.entry(field.clone()).or_default().remove(id, *len)
+            }
+            Op::Delete { stamp, .. } => {
+                let state = self.state_mut(op);
+                if state.deleted.as_ref().is_none_or(|deleted| stamp > deleted) {
+                    state.deleted = Some(stamp.clone());
+                }
+                true
+            }
+        }
+    }
+
+    // Take another replica's document in place of this one, e.g. after the server compacted its
+    // journal, keeping this replica's id and the stamps it has used
+    pub fn replace(&mut self, doc: Doc) {
+        let clock = self.clock.max(doc.clock);
+        *self = Doc {
+            replica: std::mem::take(&mut self.replica),
+            clock,
+            ..doc
+        };
+    }
+
+    // Merge operations from any replica; applying one twice changes nothing
+    pub fn apply(&mut self, ops: impl IntoIterator<Item = Op>) {
+        for op in ops {
+            if !self.integrate(&op) {
+                self.pending.push(op);
+            }
+        }
+        loop {
+            let waiting = std::mem::take(&mut self.pending);
+            let count = waiting.len();
+            for op in waiting {
+                if !self.integrate(&op) {
+                    self.pending.push(op);
+                }
+            }
+            if self.pending.len() == count {
+                break;
+            }
+        }
+    }
+
+    fn local(&mut self, op: Op, ops: &mut Vec<Op>) {
+        self.integrate(&op);
+        ops.push(op);
+    }
+
+    fn edit_text(&mut self, collection: Collection, uuid: &str, field: &str, target: &str, ops: &mut Vec<Op>) {
+        let text = self.state(collection, uuid).and_then(|s| s.texts.get(field));
+        let known = text.is_some() || self.state(collection, uuid).is_some_and(|s| s.fields.contains_key(field));
+        let (ids, current) = text.map(Text::visible).unwrap_or_default();
+        if !known {
+            let stamp = self.tick(1);
+            self.local(Op::Set {
+                collection,
+                uuid: uuid.to_string(),
+                field: field.to_string(),
+                value: Value::String(String::new()),
+                stamp,
+            }, ops);
+        }
+
+        let target: Vec<char> = target.chars().collect();
+        let prefix = current.iter().zip(&target).take_while(|(a, b)| a == b).count();
+        let suffix = current[prefix..]
+            .iter()
+            .rev()
+            .zip(target[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        for (id, len) in runs(&ids[prefix..ids.len() - suffix]) {
+            self.local(Op::Remove {
+                collection,
+                uuid: uuid.to_string(),
+                field: field.to_string(),
+                id,
+                len,
+            }, ops);
+        }
+        let inserted: String = target[prefix..target.len() - suffix].iter().collect();
+        if !inserted.is_empty() {
+            let id = self.tick(inserted.chars().count());
+            self.local(Op::Insert {
+                collection,
+                uuid: uuid.to_string(),
+                field: field.to_string(),
+                id,
+                after: prefix.checked_sub(1).map(|i| ids[i].clone()),
+                text: inserted,
+            }, ops);
+        }
+    }
+
+    // Record local edits: the operations that turn the document's records of a collection
+    // into `records`, already applied here and ready to send to the other replicas
+    pub fn update<T: Serialize>(&mut self, collection: Collection, records: &[T]) -> Vec<Op> {
+        let mut ops = Vec::new();
+        let mut present = BTreeSet::new();
+        for record in records {
+            let Ok(Value::Object(mut fields)) = serde_json::to_value(record) else {
+                continue;
+            };
+            let Some(Value::String(uuid)) = fields.remove("uuid") else {
+                continue;
+            };
+            // A deleted record saved again, e.g. moved back from another workspace, is written
+            // anew so the write is newer than the delete
+            let revived = self.state(collection, &uuid).is_some_and(|s| s.deleted.is_some() && !s.visible());
+            // Fields left out of the JSON were cleared
+            let cleared: Vec<String> = self
+                .state(collection, &uuid)
+                .map(|s| {
+                    s.fields
+                        .iter()
+                        .filter(|(field, register)| !register.value.is_null() && !fields.contains_key(*field))
+                        .map(|(field, _)| field.clone())
+                        .collect()
+                })
+                .unwrap_or_default();
+            for field in cleared {
+                fields.insert(field, Value::Null);
+            }
+            for (field, value) in fields {
+                match value {
+                    Value::String(target) if TEXT_FIELDS.contains(&field.as_str()) => {
+                        self.edit_text(collection, &uuid, &field, &target, &mut ops);
+                    }
+                    value => {
+                        let unchanged = self
+                            .state(collection, &uuid)
+                            .and_then(|s| s.fields.get(&field))
+                            .is_some_and(|r| r.value == value);
+                        if !unchanged || revived {
+                            let stamp = self.tick(1);
+                            self.local(Op::Set {
+                                collection,
+                                uuid: uuid.clone(),
+                                field,
+                                value,
+                                stamp,
+                            }, &mut ops);
+                        }
+                    }
+                }
+            }
+            present.insert(uuid);
+        }
+
+        let removed: Vec<String> = self
+            .states(collection)
+            .iter()
+            .filter(|(uuid, state)| state.visible() && !present.contains(*uuid))
+            .map(|(uuid, _)| uuid.clone())
+            .collect();
+        for uuid in removed {
+            let stamp = self.tick(1);
+            self.local(Op::Delete { collection, uuid, stamp }, &mut ops);
+        }
+        ops
+    }
+
+    // The records of a collection, in the same order on every replica that has the same operations
+    pub fn records<T: DeserializeOwned>(&self, collection: Collection) -> Vec<T> {
+        let mut states: Vec<(&String, &RecordState)> = self.states(collection).iter().filter(|(_, s)| s.visible()).collect();
+        states.sort_by(|a, b| a.1.created.cmp(&b.1.created));
+        states
+            .into_iter()
+            .filter_map(|(uuid, state)| {
+                let mut object = Map::new();
+                object.insert("uuid".to_string(), Value::String(uuid.clone()));
+                for (field, register) in state.fields.iter().filter(|(_, r)| !r.value.is_null()) {
+                    object.insert(field.clone(), register.value.clone());
+                }
+                for (field, text) in &state.texts {
+                    object.insert(field.clone(), Value::String(text.value()));
+                }
+                serde_json::from_value(Value::Object(object)).ok()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    fn records(doc: &Doc) -> Vec<Value> {
+        doc.records(Collection::Outside)
+    }
+
+    fn save(doc: &mut Doc, records: Value) -> Vec<Op> {
+        let Value::Array(records) = records else {
+            panic!("records must be an array");
+        };
+        doc.update(Collection::Outside, &records)
+    }
+
+    // Two replicas that start from the same record
+    fn pair() -> (Doc, Doc) {
+        let mut a = Doc::new("a");
+        let ops = save(&mut a, json!([{"uuid": "1", "name": "Hello", "percentage": 10}]));
+        let mut b = Doc::new("b");
+        b.apply(ops);
+        (a, b)
+    }
+
+    #[test]
+    fn concurrent_text_inserts_keep_both() {
+        let (mut a, mut b) = pair();
+        let from_a = save(&mut a, json!([{"uuid": "1", "name": "Hello A", "percentage": 10}]));
+        let from_b = save(&mut b, json!([{"uuid": "1", "name": "Hello B", "percentage": 10}]));
+        a.apply(from_b);
+        b.apply(from_a);
+        assert_eq!(records(&a), records(&b));
+        let name = records(&a)[0]["name"].as_str().unwrap().to_string();
+        assert!(name == "Hello A B" || name == "Hello B A", "{}", name);
+    }
+
+    #[test]
+    fn concurrent_field_writes_keep_the_highest_stamp() {
+        let (mut a, mut b) = pair();
+        let from_a = save(&mut a, json!([{"uuid": "1", "name": "Hello", "percentage": 50}]));
+        let from_b = save(&mut b, json!([{"uuid": "1", "name": "Hello", "percentage": 70}]));
+        a.apply(from_b);
+        b.apply(from_a);
+        assert_eq!(records(&a), records(&b));
+        // Same counter on both, so the replica id decides
+        assert_eq!(records(&a)[0]["percentage"], 70);
+    }
+
+    #[test]
+    fn concurrent_delete_and_edit_agree() {
+        let (mut a, mut b) = pair();
+        let delete = save(&mut a, json!([]));
+        let edit = save(&mut b, json!([{"uuid": "1", "name": "Hello", "percentage": 20}]));
+        a.apply(edit);
+        b.apply(delete);
+        assert_eq!(records(&a), records(&b));
+        // Same counter on both, so the replica id decides
+        assert_eq!(records(&a)[0]["percentage"], 20);
+    }
+
+    #[test]
+    fn record_saved_again_after_a_delete_comes_back() {
+        let (mut a, mut b) = pair();
+        b.apply(save(&mut a, json!([])));
+        assert!(records(&b).is_empty());
+        // Moved back from another workspace, unchanged
+        let ops = save(&mut b, json!([{"uuid": "1", "name": "Hello", "percentage": 10}]));
+        a.apply(ops);
+        assert_eq!(records(&a), records(&b));
+        assert_eq!(records(&a).len(), 1);
+    }
+
+    #[test]
+    fn applying_an_operation_twice_changes_nothing() {
+        let (mut a, mut b) = pair();
+        let ops = save(&mut a, json!([{"uuid": "1", "name": "Hi there", "percentage": 30}]));
+        b.apply(ops.clone());
+        let once = b.clone();
+        b.apply(ops);
+        assert_eq!(b, once);
+        assert_eq!(records(&a), records(&b));
+    }
+
+    #[test]
+    fn operations_merge_in_any_order() {
+        let mut a = Doc::new("a");
+        let mut ops = save(&mut a, json!([{"uuid": "1", "name": "Hello", "percentage": 10}]));
+        ops.extend(save(&mut a, json!([{"uuid": "1", "name": "Hello world", "percentage": 10}])));
+        ops.extend(save(&mut a, json!([{"uuid": "1", "name": "Hi world", "percentage": 40}])));
+        let mut b = Doc::new("b");
+        b.apply(ops.into_iter().rev());
+        assert_eq!(records(&b), records(&a));
+        assert!(b.pending.is_empty());
+    }
+
+    #[test]
+    fn replica_replaced_by_a_compacted_document_keeps_merging() {
+        let (mut a, mut b) = pair();
+        // `b` edits offline with stamps `a` has not seen, then takes `a`'s document
+        let offline = save(&mut b, json!([{"uuid": "1", "name": "Hello B", "percentage": 10}]));
+        save(&mut a, json!([{"uuid": "1", "name": "Hello A", "percentage": 10}]));
+        b.replace(a.clone());
+        assert_eq!(b.replica, "b");
+        b.apply(offline.clone());
+        a.apply(offline);
+        assert_eq!(records(&a), records(&b));
+        // Later edits from `b` do not reuse the stamps it sent before
+        let later = save(&mut b, json!([{"uuid": "1", "name": "Hello B A!", "percentage": 10}]));
+        a.apply(later);
+        assert_eq!(records(&a), records(&b));
+    }
+}
@@ -0,0 +1,204 @@
+use std::cell::RefCell;
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use web_sys::window;
+
+const CRYPTO_KEY: &str = "relf_crypto";
+const KDF: &str = "pbkdf2-sha256";
+const ITERATIONS: u32 = 200_000;
+// Envelopes come from imported files too; a huge count would freeze the tab
+const MAX_ITERATIONS: u32 = 10 * ITERATIONS;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const CHECK_TEXT: &str = "relf";
+
+// Authenticated ciphertext together with everything needed to derive its key
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Envelope {
+    pub relf_encrypted: u32,
+    pub kdf: String,
+    pub iterations: u32,
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+// Stored once encryption is enabled; `check` verifies the passphrase on unlock
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct CryptoConfig {
+    salt: String,
+    iterations: u32,
+    check: Envelope,
+}
+
+struct UnlockedKey {
+    key: [u8; 32],
+    salt: String,
+    iterations: u32,
+}
+
+thread_local! {
+    static KEY: RefCell<Option<UnlockedKey>> = const { RefCell::new(None) };
+}
+
+fn random_bytes(len: usize) -> Result<Vec<u8>, String> {
+    let mut buf = vec![0u8; len];
+    window()
+        .and_then(|w| w.crypto().ok())
+        .ok_or_else(|| "Web Crypto is not available".to_string())?
+        .get_random_values_with_u8_array(&mut buf)
+        .map_err(|e| format!("Failed to generate random bytes: {:?}", e))?;
+    Ok(buf)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<[u8; 32], String> {
+    if !(ITERATIONS..=MAX_ITERATIONS).contains(&iterations) {
+        return Err(format!("Unsupported key derivation iteration count: {}", iterations));
+    }
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+    Ok(key)
+}
+
+fn seal(key: &[u8; 32], salt: &str, iterations: u32, plaintext: &str) -> Result<Envelope, String> {
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| "Invalid key".to_string())?;
+    let nonce = random_bytes(NONCE_LEN)?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plaintext.as_bytes())
+        .map_err(|_| "Encryption failed".to_string())?;
+    Ok(Envelope {
+        relf_encrypted: 1,
+        kdf: KDF.to_string(),
+        iterations,
+        salt: salt.to_string(),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+fn open(key: &[u8; 32], envelope: &Envelope) -> Result<String, String> {
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| "Invalid key".to_string())?;
+    let nonce = BASE64.decode(&envelope.nonce).map_err(|_| "Invalid nonce".to_string())?;
+    if nonce.len() != NONCE_LEN {
+        return Err("Invalid nonce".to_string());
+    }
+    let ciphertext = BASE64
+        .decode(&envelope.ciphertext)
+        .map_err(|_| "Invalid ciphertext".to_string())?;
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| "Wrong passphrase or corrupted data".to_string())?;
+    String::from_utf8(plaintext).map_err(|_| "Corrupted data".to_string())
+}
+
+fn config() -> Option<CryptoConfig> {
+    LocalStorage::get(CRYPTO_KEY).ok()
+}
+
+pub fn parse_envelope(text: &str) -> Option<Envelope> {
+    serde_json::from_str::<Envelope>(text)
+        .ok()
+        .filter(|e| e.relf_encrypted == 1 && e.kdf == KDF)
+}
+
+pub fn is_envelope(text: &str) -> bool {
+    parse_envelope(text).is_some()
+}
+
+pub fn is_enabled() -> bool {
+    config().is_some()
+}
+
+pub fn is_unlocked() -> bool {
+    KEY.with(|k| k.borrow().is_some())
+}
+
+pub fn is_locked() -> bool {
+    is_enabled() && !is_unlocked()
+}
+
+pub fn unlock(passphrase: &str) -> Result<(), String> {
+    let config = config().ok_or_else(|| "Encryption is not enabled".to_string())?;
+    let salt = BASE64.decode(&config.salt).map_err(|_| "Invalid salt".to_string())?;
+    let key = derive_key(passphrase, &salt, config.iterations)?;
+    open(&key, &config.check).map_err(|_| "Wrong passphrase".to_string())?;
+    KEY.with(|k| {
+        *k.borrow_mut() = Some(UnlockedKey {
+            key,
+            salt: config.salt,
+            iterations: config.iterations,
+        })
+    });
+    Ok(())
+}
+
+pub fn lock() {
+    KEY.with(|k| *k.borrow_mut() = None);
+}
+
+// Create a new key from the passphrase; callers re-save their data afterwards
+pub fn enable(passphrase: &str) -> Result<(), String> {
+    if passphrase.is_empty() {
+        return Err("Passphrase must not be empty".to_string());
+    }
+    let salt = BASE64.encode(random_bytes(SALT_LEN)?);
+    let key = derive_key(passphrase, &BASE64.decode(&salt).unwrap_or_default(), ITERATIONS)?;
+    let check = seal(&key, &salt, ITERATIONS, CHECK_TEXT)?;
+    LocalStorage::set(CRYPTO_KEY, &CryptoConfig { salt: salt.clone(), iterations: ITERATIONS, check })
+        .map_err(|e| format!("Failed to save encryption settings: {:?}", e))?;
+    KEY.with(|k| *k.borrow_mut() = Some(UnlockedKey { key, salt, iterations: ITERATIONS }));
+    Ok(())
+}
+
+// Forget the key; callers re-save their data in plaintext afterwards
+pub fn disable() {
+    LocalStorage::delete(CRYPTO_KEY);
+    lock();
+}
+
+// Encrypt with the unlocked key
+pub fn encrypt(plaintext: &str) -> Result<String, String> {
+    KEY.with(|k| {
+        let guard = k.borrow();
+        let unlocked = guard.as_ref().ok_or_else(|| "Data is locked".to_string())?;
+        let envelope = seal(&unlocked.key, &unlocked.salt, unlocked.iterations, plaintext)?;
+        serde_json::to_string_pretty(&envelope).map_err(|e| format!("Failed to encode encrypted data: {:?}", e))
+    })
+}
+
+// Decrypt with the unlocked key when the envelope was sealed with it
+pub fn decrypt(text: &str) -> Result<String, String> {
+    let envelope = parse_envelope(text).ok_or_else(|| "Not encrypted data".to_string())?;
+    KEY.with(|k| {
+        let guard = k.borrow();
+        match guard.as_ref() {
+            Some(unlocked) if unlocked.salt == envelope.salt => open(&unlocked.key, &envelope),
+            _ => Err("Data was encrypted with a different passphrase".to_string()),
+        }
+    })
+}
+
+pub fn decrypt_with_passphrase(text: &str, passphrase: &str) -> Result<String, String> {
+    let envelope = parse_envelope(text).ok_or_else(|| "Not encrypted data".to_string())?;
+    let salt = BASE64.decode(&envelope.salt).map_err(|_| "Invalid salt".to_string())?;
+    open(&derive_key(passphrase, &salt, envelope.iterations)?, &envelope)
+}
+
+// Plaintext of imported data, asking for the passphrase of foreign encrypted files
+pub fn decrypt_import(text: &str) -> Result<String, String> {
+    if !is_envelope(text) {
+        return Ok(text.to_string());
+    }
+    if let Ok(plaintext) = decrypt(text) {
+        return Ok(plaintext);
+    }
+    let passphrase = window()
+        .and_then(|w| w.prompt_with_message("Passphrase for the encrypted data:").ok().flatten())
+        .ok_or_else(|| "Import cancelled".to_string())?;
+    decrypt_with_passphrase(text, &passphrase)
+}
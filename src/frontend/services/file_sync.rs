@@ -0,0 +1,292 @@
+use std::cell::{Cell, RefCell};
+use gloo::timers::callback::Interval;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{window, File, FileSystemFileHandle, FileSystemWritableFileStream};
+use yew::Callback;
+use gloo::storage::{LocalStorage, Storage};
+use crate::frontend::services::{crypto, workspace};
+use crate::frontend::services::idb::{self, HANDLE_STORE};
+use crate::frontend::services::storage;
+
+const HANDLE_KEY: &str = "sync_file";
+const WORKSPACE_KEY: &str = "relf_sync_workspace";
+const POLL_INTERVAL_MS: u32 = 3000;
+const PICKER_OPTIONS: &str = r#"{
+    "suggestedName": "relf_data.json",
+    "types": [{ "description": "Relf data", "accept": { "application/json": [".json"] } }]
+}"#;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SyncStatus {
+    Unsupported,
+    Unlinked,
+    Linked(String),
+    NeedsPermission(String),
+}
+
+thread_local! {
+    // Handle of the linked file once write permission is granted
+    static HANDLE: RefCell<Option<FileSystemFileHandle>> = const { RefCell::new(None) };
+    // Handle restored from a previous session that still needs permission
+    static PENDING_HANDLE: RefCell<Option<FileSystemFileHandle>> = const { RefCell::new(None) };
+    // Modification time of the file as of our last read or write
+    static LAST_MODIFIED: Cell<f64> = const { Cell::new(0.0) };
+    static WRITE_PENDING: Cell<bool> = const { Cell::new(false) };
+    static SUPPRESS_WRITES: Cell<bool> = const { Cell::new(false) };
+    // Told when the linked file was reloaded after an outside modification
+    static ON_CHANGE: RefCell<Option<Callback<()>>> = const { RefCell::new(None) };
+}
+
+pub fn is_supported() -> bool {
+    window()
+        .map(|w| js_sys::Reflect::has(&w, &"showSaveFilePicker".into()).unwrap_or(false))
+        .unwrap_or(false)
+}
+
+pub fn status() -> SyncStatus {
+    if !is_supported() {
+        return SyncStatus::Unsupported;
+    }
+    if let Some(handle) = HANDLE.with(|h| h.borrow().clone()) {
+        return SyncStatus::Linked(handle.name());
+    }
+    if let Some(handle) = PENDING_HANDLE.with(|h| h.borrow().clone()) {
+        return SyncStatus::NeedsPermission(handle.name());
+    }
+    SyncStatus::Unlinked
+}
+
+// Workspace the linked file holds; files linked before workspaces existed hold the default one
+pub fn linked_workspace() -> String {
+    LocalStorage::get(WORKSPACE_KEY).unwrap_or_else(|_| workspace::DEFAULT_ID.to_string())
+}
+
+async fn await_promise(value: JsValue) -> Result<JsValue, String> {
+    JsFuture::from(js_sys::Promise::from(value))
+        .await
+        .map_err(|e| format!("{:?}", e))
+}
+
+async fn call_picker(name: &str) -> Result<JsValue, String> {
+    let window = window().ok_or_else(|| "No window available".to_string())?;
+    let picker: js_sys::Function = js_sys::Reflect::get(&window, &name.into())
+        .ok()
+        .and_then(|f| f.dyn_into().ok())
+        .ok_or_else(|| "File System Access API is not available".to_string())?;
+    let options = js_sys::JSON::parse(PICKER_OPTIONS).unwrap_or(JsValue::UNDEFINED);
+    let promise = picker
+        .call1(&window, &options)
+        .map_err(|e| format!("Failed to open file picker: {:?}", e))?;
+    await_promise(promise).await
+}
+
+async fn has_permission(handle: &FileSystemFileHandle, method: &str) -> bool {
+    let Some(function) = js_sys::Reflect::get(handle, &method.into())
+        .ok()
+        .and_then(|f| f.dyn_into::<js_sys::Function>().ok())
+    else {
+        // Browsers without the permission methods grant access with the handle
+        return true;
+    };
+    let descriptor = js_sys::JSON::parse(r#"{ "mode": "readwrite" }"#).unwrap_or(JsValue::UNDEFINED);
+    match function.call1(handle, &descriptor) {
+        Ok(promise) => await_promise(promise)
+            .await
+            .map(|state| state.as_string().as_deref() == Some("granted"))
+            .unwrap_or(false),
+        Err(_) => false,
+    }
+}
+
+async fn read_file(handle: &FileSystemFileHandle) -> Result<File, String> {
+    let file = JsFuture::from(handle.get_file())
+        .await
+        .map_err(|e| format!("Failed to read linked file: {:?}", e))?;
+    Ok(file.unchecked_into())
+}
+
+async fn read_text(file: &File) -> Result<String, String> {
+    JsFuture::from(file.text())
+        .await
+        .ok()
+        .and_then(|text| text.as_string())
+        .ok_or_else(|| "Failed to read linked file".to_string())
+}
+
+async fn write_file(handle: &FileSystemFileHandle, contents: &str) -> Result<(), String> {
+    let writable: FileSystemWritableFileStream = JsFuture::from(handle.create_writable())
+        .await
+        .map_err(|e| format!("Failed to open linked file for writing: {:?}", e))?
+        .unchecked_into();
+    let write = writable
+        .write_with_str(contents)
+        .map_err(|e| format!("Failed to write linked file: {:?}", e))?;
+    JsFuture::from(write)
+        .await
+        .map_err(|e| format!("Failed to write linked file: {:?}", e))?;
+    JsFuture::from(writable.close())
+        .await
+        .map_err(|e| format!("Failed to write linked file: {:?}", e))?;
+
+    // Remember our own write so it is not mistaken for an outside change
+    let file = read_file(handle).await?;
+    LAST_MODIFIED.with(|m| m.set(file.last_modified()));
+    Ok(())
+}
+
+async fn link(handle: FileSystemFileHandle) -> Result<String, String> {
+    idb::put(HANDLE_STORE, HANDLE_KEY, &handle).await?;
+    let name = handle.name();
+    PENDING_HANDLE.with(|h| *h.borrow_mut() = None);
+    HANDLE.with(|h| *h.borrow_mut() = Some(handle));
+    Ok(name)
+}
+
+// The file follows the workspace it was linked in, whichever one is shown later
+async fn link_to_active(handle: FileSystemFileHandle) -> Result<String, String> {
+    let name = link(handle).await?;
+    LocalStorage::set(WORKSPACE_KEY, workspace::active_id()).map_err(|e| format!("{:?}", e))?;
+    Ok(name)
+}
+
+// Pick a new file and write the current workspace to it
+pub async fn link_new_file() -> Result<String, String> {
+    let handle: FileSystemFileHandle = call_picker("showSaveFilePicker").await?.unchecked_into();
+    write_file(&handle, &storage::export_for_file()?).await?;
+    link_to_active(handle).await
+}
+
+// Pick an existing file, load its data and keep it in sync from then on
+pub async fn link_existing_file() -> Result<String, String> {
+    let handles = call_picker("showOpenFilePicker").await?;
+    let handle: FileSystemFileHandle = js_sys::Array::from(&handles).get(0).unchecked_into();
+    if !has_permission(&handle, "requestPermission").await {
+        return Err("Write permission was not granted".to_string());
+    }
+    let file = read_file(&handle).await?;
+    let text = read_text(&file).await?;
+    if !text.trim().is_empty() {
+        load_external(&workspace::active_id(), &text)?;
+    }
+    LAST_MODIFIED.with(|m| m.set(file.last_modified()));
+    link_to_active(handle).await
+}
+
+pub async fn unlink() -> Result<(), String> {
+    HANDLE.with(|h| *h.borrow_mut() = None);
+    PENDING_HANDLE.with(|h| *h.borrow_mut() = None);
+    LocalStorage::delete(WORKSPACE_KEY);
+    idb::delete(HANDLE_STORE, HANDLE_KEY).await
+}
+
+// Unlink the file a deleted workspace was kept in. Other tabs find the link gone and let go
+// of their handle, so they do not write another workspace to the file
+pub async fn forget(workspace_id: &str) -> Result<(), String> {
+    if LocalStorage::get::<String>(WORKSPACE_KEY).is_ok_and(|id| id == workspace_id) {
+        return unlink().await;
+    }
+    let stored = idb::get(HANDLE_STORE, HANDLE_KEY).await?;
+    if stored.is_undefined() || stored.is_null() {
+        HANDLE.with(|h| *h.borrow_mut() = None);
+        PENDING_HANDLE.with(|h| *h.borrow_mut() = None);
+    }
+    Ok(())
+}
+
+// Restore the file linked in a previous session
+pub async fn restore_link() -> Result<(), String> {
+    if !is_supported() {
+        return Ok(());
+    }
+    let stored = idb::get(HANDLE_STORE, HANDLE_KEY).await?;
+    if stored.is_undefined() || stored.is_null() {
+        return Ok(());
+    }
+    let handle: FileSystemFileHandle = stored.unchecked_into();
+    if has_permission(&handle, "queryPermission").await {
+        HANDLE.with(|h| *h.borrow_mut() = Some(handle));
+        check_for_changes().await
+    } else {
+        PENDING_HANDLE.with(|h| *h.borrow_mut() = Some(handle));
+        Ok(())
+    }
+}
+
+// Ask again for permission on a restored file; must run from a user gesture
+pub async fn reconnect() -> Result<String, String> {
+    let handle = PENDING_HANDLE
+        .with(|h| h.borrow().clone())
+        .ok_or_else(|| "No linked file to reconnect".to_string())?;
+    if !has_permission(&handle, "requestPermission").await {
+        return Err("Write permission was not granted".to_string());
+    }
+    let name = link(handle).await?;
+    check_for_changes().await?;
+    Ok(name)
+}
+
+// Write the linked workspace to its file after it was saved, coalescing bursts of saves
+pub fn notify_change(workspace_id: &str) {
+    if workspace_id != linked_workspace() || SUPPRESS_WRITES.with(|s| s.get()) || WRITE_PENDING.with(|p| p.get()) {
+        return;
+    }
+    let Some(handle) = HANDLE.with(|h| h.borrow().clone()) else {
+        return;
+    };
+    WRITE_PENDING.with(|p| p.set(true));
+    wasm_bindgen_futures::spawn_local(async move {
+        WRITE_PENDING.with(|p| p.set(false));
+        let result = match storage::export_workspace_for_file(&linked_workspace()) {
+            Ok(contents) => write_file(&handle, &contents).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            web_sys::console::log_1(&format!("File sync failed: {}", e).into());
+        }
+    });
+}
+
+fn load_external(workspace_id: &str, text: &str) -> Result<(), String> {
+    SUPPRESS_WRITES.with(|s| s.set(true));
+    let result = storage::import_workspace_from_json(workspace_id, text);
+    SUPPRESS_WRITES.with(|s| s.set(false));
+    result
+}
+
+// Reload the linked file if it was modified outside relf
+async fn check_for_changes() -> Result<(), String> {
+    let Some(handle) = HANDLE.with(|h| h.borrow().clone()) else {
+        return Ok(());
+    };
+    let file = read_file(&handle).await?;
+    if file.last_modified() <= LAST_MODIFIED.with(|m| m.get()) {
+        return Ok(());
+    }
+    LAST_MODIFIED.with(|m| m.set(file.last_modified()));
+
+    let text = read_text(&file).await?;
+    let plaintext = if crypto::is_envelope(&text) { crypto::decrypt(&text)? } else { text.clone() };
+    let workspace_id = linked_workspace();
+    if plaintext.trim().is_empty() || plaintext.trim() == storage::export_workspace(&workspace_id).trim() {
+        return Ok(());
+    }
+    load_external(&workspace_id, &text)?;
+    web_sys::console::log_1(&"Linked file changed outside relf, reloading".into());
+    if let Some(on_change) = ON_CHANGE.with(|c| c.borrow().clone()) {
+        on_change.emit(());
+    }
+    Ok(())
+}
+
+// Poll the linked file for outside modifications while the returned interval is alive
+pub fn watch(on_change: Callback<()>) -> Interval {
+    ON_CHANGE.with(|c| *c.borrow_mut() = Some(on_change));
+    Interval::new(POLL_INTERVAL_MS, || {
+        wasm_bindgen_futures::spawn_local(async {
+            if let Err(e) = check_for_changes().await {
+                web_sys::console::log_1(&format!("File sync failed: {}", e).into());
+            }
+        });
+    })
+}
@@ -0,0 +1,214 @@
+// Minimal syntax highlighting for fenced code blocks: comments, strings, numbers and keywords
+
+struct Syntax {
+    keywords: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+    case_insensitive: bool,
+}
+
+const RUST: Syntax = Syntax {
+    keywords: &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "false", "fn",
+        "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+        "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where",
+        "while",
+    ],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"'],
+    case_insensitive: false,
+};
+
+const JAVASCRIPT: Syntax = Syntax {
+    keywords: &[
+        "async", "await", "break", "case", "catch", "class", "const", "continue", "default", "delete", "do",
+        "else", "export", "extends", "false", "finally", "for", "from", "function", "if", "import", "in",
+        "instanceof", "interface", "let", "new", "null", "of", "return", "static", "switch", "this",
+        "throw", "true", "try", "type", "typeof", "undefined", "var", "void", "while", "yield",
+    ],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\'', '`'],
+    case_insensitive: false,
+};
+
+const PYTHON: Syntax = Syntax {
+    keywords: &[
+        "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif", "else",
+        "except", "False", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda", "None",
+        "nonlocal", "not", "or", "pass", "raise", "return", "True", "try", "while", "with", "yield",
+    ],
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    case_insensitive: false,
+};
+
+const SHELL: Syntax = Syntax {
+    keywords: &[
+        "case", "do", "done", "echo", "elif", "else", "esac", "exit", "export", "fi", "for", "function", "if",
+        "in", "local", "return", "then", "while",
+    ],
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    case_insensitive: false,
+};
+
+const C_LIKE: Syntax = Syntax {
+    keywords: &[
+        "auto", "bool", "break", "case", "catch", "char", "class", "const", "continue", "default", "defer",
+        "delete", "do", "double", "else", "enum", "extends", "false", "final", "float", "for", "func", "go",
+        "if", "implements", "import", "include", "int", "interface", "long", "map", "namespace", "new",
+        "nil", "null", "package", "private", "protected", "public", "range", "return", "short", "static",
+        "struct", "switch", "template", "this", "throw", "true", "try", "type", "typedef", "unsigned",
+        "var", "void", "while",
+    ],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\''],
+    case_insensitive: false,
+};
+
+const SQL: Syntax = Syntax {
+    keywords: &[
+        "and", "as", "by", "create", "delete", "desc", "from", "group", "having", "insert", "into", "join",
+        "left", "limit", "not", "null", "on", "or", "order", "select", "set", "table", "update", "values",
+        "where",
+    ],
+    line_comments: &["--"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['\''],
+    case_insensitive: true,
+};
+
+const CONFIG: Syntax = Syntax {
+    keywords: &["true", "false", "null"],
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    case_insensitive: false,
+};
+
+const JSON: Syntax = Syntax {
+    keywords: &["true", "false", "null"],
+    line_comments: &[],
+    block_comment: None,
+    quotes: &['"'],
+    case_insensitive: false,
+};
+
+fn syntax_for(lang: &str) -> Option<&'static Syntax> {
+    match lang.to_lowercase().as_str() {
+        "rust" | "rs" => Some(&RUST),
+        "js" | "javascript" | "jsx" | "ts" | "typescript" | "tsx" => Some(&JAVASCRIPT),
+        "py" | "python" => Some(&PYTHON),
+        "sh" | "bash" | "shell" | "zsh" => Some(&SHELL),
+        "c" | "h" | "cpp" | "c++" | "java" | "go" | "kotlin" | "swift" | "cs" => Some(&C_LIKE),
+        "sql" => Some(&SQL),
+        "toml" | "yaml" | "yml" | "ini" => Some(&CONFIG),
+        "json" => Some(&JSON),
+        _ => None,
+    }
+}
+
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn push_span(out: &mut String, class: &str, text: &str) {
+    out.push_str(&format!("<span class=\"hl-{}\">{}</span>", class, escape_html(text)));
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+// Byte length of the token starting at `rest`, ending after `end` or at the end of the text
+fn until(rest: &str, start: usize, end: &str) -> usize {
+    rest[start..].find(end).map(|i| start + i + end.len()).unwrap_or(rest.len())
+}
+
+// Byte length of a quoted string, honouring backslash escapes
+fn string_len(rest: &str, quote: char) -> usize {
+    let mut escaped = false;
+    for (i, c) in rest.char_indices().skip(1) {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            return i + c.len_utf8();
+        } else if c == '\n' && quote != '`' {
+            return i;
+        }
+    }
+    rest.len()
+}
+
+// Escaped HTML for a code block, with tokens wrapped in `hl-*` spans when the language is known
+pub fn highlight(code: &str, lang: &str) -> String {
+    let Some(syntax) = syntax_for(lang) else {
+        return escape_html(code);
+    };
+
+    let mut out = String::with_capacity(code.len() * 2);
+    let mut pos = 0;
+    let mut previous = ' ';
+    while pos < code.len() {
+        let rest = &code[pos..];
+        let c = rest.chars().next().unwrap_or(' ');
+
+        let token = if let Some(marker) = syntax.line_comments.iter().find(|m| rest.starts_with(**m)) {
+            let len = rest.find('\n').unwrap_or(rest.len());
+            push_span(&mut out, "comment", &rest[..len.max(marker.len())]);
+            len.max(marker.len())
+        } else if let Some((open, close)) = syntax.block_comment.filter(|(open, _)| rest.starts_with(open)) {
+            let len = until(rest, open.len(), close);
+            push_span(&mut out, "comment", &rest[..len]);
+            len
+        } else if syntax.quotes.contains(&c) {
+            let len = string_len(rest, c);
+            push_span(&mut out, "string", &rest[..len]);
+            len
+        } else if c.is_ascii_digit() && !is_ident(previous) {
+            let len = rest.find(|ch: char| !(is_ident(ch) || ch == '.')).unwrap_or(rest.len());
+            push_span(&mut out, "number", &rest[..len]);
+            len
+        } else if is_ident(c) {
+            let len = rest.find(|ch: char| !is_ident(ch)).unwrap_or(rest.len());
+            let word = &rest[..len];
+            let is_keyword = if syntax.case_insensitive {
+                syntax.keywords.contains(&word.to_lowercase().as_str())
+            } else {
+                syntax.keywords.contains(&word)
+            };
+            if is_keyword {
+                push_span(&mut out, "keyword", word);
+            } else {
+                out.push_str(&escape_html(word));
+            }
+            len
+        } else {
+            out.push_str(&escape_html(&rest[..c.len_utf8()]));
+            c.len_utf8()
+        };
+
+        previous = code[..pos + token].chars().next_back().unwrap_or(' ');
+        pos += token;
+    }
+    out
+}
@@ -0,0 +1,94 @@
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{window, IdbDatabase, IdbObjectStore, IdbRequest, IdbTransactionMode};
+
+const DB_NAME: &str = "relf";
+const DB_VERSION: u32 = 2;
+
+pub const SNAPSHOT_STORE: &str = "snapshots";
+pub const HANDLE_STORE: &str = "handles";
+const STORES: [&str; 2] = [SNAPSHOT_STORE, HANDLE_STORE];
+
+// Resolve an IndexedDB request as a future yielding its result
+fn request_to_future(request: &IdbRequest) -> JsFuture {
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        let success_request = request.clone();
+        let on_success = Closure::once_into_js(move || {
+            let result = success_request.result().unwrap_or(JsValue::UNDEFINED);
+            let _ = resolve.call1(&JsValue::NULL, &result);
+        });
+        let on_error = Closure::once_into_js(move || {
+            let _ = reject.call1(&JsValue::NULL, &"IndexedDB request failed".into());
+        });
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    });
+    JsFuture::from(promise)
+}
+
+async fn open_db() -> Result<IdbDatabase, String> {
+    let factory = window()
+        .and_then(|w| w.indexed_db().ok().flatten())
+        .ok_or_else(|| "IndexedDB is not available".to_string())?;
+    let request = factory
+        .open_with_u32(DB_NAME, DB_VERSION)
+        .map_err(|e| format!("Failed to open database: {:?}", e))?;
+
+    let upgrade_request = request.clone();
+    let on_upgrade = Closure::once_into_js(move || {
+        if let Ok(result) = upgrade_request.result() {
+            let db: IdbDatabase = result.unchecked_into();
+            for name in STORES {
+                if !db.object_store_names().contains(name) {
+                    let _ = db.create_object_store(name);
+                }
+            }
+        }
+    });
+    request.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+
+    let db = request_to_future(&request)
+        .await
+        .map_err(|e| format!("Failed to open database: {:?}", e))?;
+    Ok(db.unchecked_into())
+}
+
+fn object_store(db: &IdbDatabase, name: &str, mode: IdbTransactionMode) -> Result<IdbObjectStore, String> {
+    db.transaction_with_str_and_mode(name, mode)
+        .and_then(|tx| tx.object_store(name))
+        .map_err(|e| format!("Failed to access {}: {:?}", name, e))
+}
+
+async fn run(name: &str, mode: IdbTransactionMode, op: impl FnOnce(&IdbObjectStore) -> Result<IdbRequest, JsValue>) -> Result<JsValue, String> {
+    let db = open_db().await?;
+    let store = object_store(&db, name, mode)?;
+    let request = op(&store).map_err(|e| format!("Failed to access {}: {:?}", name, e))?;
+    request_to_future(&request)
+        .await
+        .map_err(|e| format!("Failed to access {}: {:?}", name, e))
+}
+
+pub async fn get(name: &str, key: &str) -> Result<JsValue, String> {
+    run(name, IdbTransactionMode::Readonly, |store| store.get(&JsValue::from_str(key))).await
+}
+
+pub async fn get_all(name: &str) -> Result<js_sys::Array, String> {
+    let values = run(name, IdbTransactionMode::Readonly, |store| store.get_all()).await?;
+    Ok(js_sys::Array::from(&values))
+}
+
+pub async fn get_all_keys(name: &str) -> Result<js_sys::Array, String> {
+    let keys = run(name, IdbTransactionMode::Readonly, |store| store.get_all_keys()).await?;
+    Ok(js_sys::Array::from(&keys))
+}
+
+pub async fn put(name: &str, key: &str, value: &JsValue) -> Result<(), String> {
+    run(name, IdbTransactionMode::Readwrite, |store| store.put_with_key(value, &JsValue::from_str(key))).await?;
+    Ok(())
+}
+
+pub async fn delete(name: &str, key: &str) -> Result<(), String> {
+    run(name, IdbTransactionMode::Readwrite, |store| store.delete(&JsValue::from_str(key))).await?;
+    Ok(())
+}
@@ -0,0 +1,752 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use gloo::events::EventListener;
+use gloo::storage::{LocalStorage, Storage};
+use gloo::timers::callback::Timeout;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::{window, MessageEvent, WebSocket};
+use yew::prelude::*;
+use crate::models::{Inside, Outside};
+use crate::frontend::services::crdt::{Doc, Op};
+use crate::frontend::services::server_api::{self, OpsPage, RemoteSpace};
+use crate::frontend::services::tab_sync::{Change, Collection};
+use crate::frontend::services::{storage, workspace};
+
+const LINK_KEY: &str = "relf_live_link";
+const RECONNECT_MS: u32 = 5000;
+const MERGE_KEY_PREFIX: &str = "relf_merge_";
+const SYNCED_KEY_PREFIX: &str = "relf_synced_";
+
+// A local workspace kept in step with records on the server
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Link {
+    pub workspace: String,
+    pub space: RemoteSpace,
+    // Merge concurrent edits field by field instead of keeping the last record saved
+    #[serde(default)]
+    pub merge: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LiveStatus {
+    Unlinked,
+    Connecting,
+    Connected,
+    Offline(String),
+}
+
+// Someone else connected to the same records; `record` is empty while on a list
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct Viewer {
+    pub username: String,
+    #[serde(default)]
+    pub record: Option<String>,
+    #[serde(default)]
+    pub editing: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum ServerMessage {
+    Ready {
+        #[serde(default)]
+        role: String,
+    },
+    Change(RecordEvent),
+    Presence { viewers: Vec<Viewer> },
+    Resync,
+    Ops {
+        #[serde(default)]
+        epoch: u64,
+        seq: usize,
+    },
+    Error { message: String },
+}
+
+#[derive(Deserialize)]
+struct RecordEvent {
+    action: String,
+    collection: String,
+    uuid: String,
+    #[serde(default)]
+    record: Option<serde_json::Value>,
+    #[serde(default)]
+    origin: Option<String>,
+}
+
+// This device's replica of the linked workspace in merge mode
+#[derive(Serialize, Deserialize)]
+struct MergeState {
+    doc: Doc,
+    // The server journal's epoch, and how many of its operations `doc` has seen
+    #[serde(default)]
+    epoch: u64,
+    seq: usize,
+    // Local operations the server has not taken yet
+    #[serde(default)]
+    outbox: Vec<Op>,
+}
+
+struct Connection {
+    socket: WebSocket,
+    listeners: Vec<EventListener>,
+}
+
+// Records as the server last had them, by uuid, as JSON
+#[derive(Serialize, Deserialize, Default)]
+struct Synced {
+    outside: HashMap<String, String>,
+    inside: HashMap<String, String>,
+}
+
+impl Synced {
+    fn of(&mut self, collection: Collection) -> &mut HashMap<String, String> {
+        match collection {
+            Collection::Outside => &mut self.outside,
+            Collection::Inside => &mut self.inside,
+        }
+    }
+}
+
+thread_local! {
+    static CONNECTION: RefCell<Option<Connection>> = const { RefCell::new(None) };
+    static RECONNECT: RefCell<Option<Timeout>> = const { RefCell::new(None) };
+    static STATUS: RefCell<LiveStatus> = const { RefCell::new(LiveStatus::Unlinked) };
+    static VIEWERS: RefCell<Vec<Viewer>> = const { RefCell::new(Vec::new()) };
+    // What this tab is looking at, sent again after reconnecting
+    static PRESENCE: RefCell<(Option<String>, bool)> = const { RefCell::new((None, false)) };
+    // Empty until the first pull, so local records are not mistaken for new ones; stored per
+    // workspace so edits made offline are still sent after a reload
+    static SYNCED: RefCell<Option<Synced>> = const { RefCell::new(None) };
+    // Viewers of a team workspace receive changes but cannot send any
+    static READ_ONLY: Cell<bool> = const { Cell::new(false) };
+    static PUSH_PENDING: Cell<bool> = const { Cell::new(false) };
+    static PUSH_AGAIN: Cell<bool> = const { Cell::new(false) };
+    static SUPPRESS_PUSH: Cell<bool> = const { Cell::new(false) };
+    // Told which records a teammate changed in the active workspace
+    static ON_CHANGE: RefCell<Option<Callback<Change>>> = const { RefCell::new(None) };
+    // Components showing the status or presence
+    static LISTENERS: RefCell<Vec<(u64, Callback<()>)>> = const { RefCell::new(Vec::new()) };
+    static NEXT_LISTENER: Cell<u64> = const { Cell::new(0) };
+}
+
+pub fn link() -> Option<Link> {
+    LocalStorage::get(LINK_KEY).ok()
+}
+
+pub fn status() -> LiveStatus {
+    STATUS.with(|s| s.borrow().clone())
+}
+
+fn merging() -> bool {
+    link().is_some_and(|l| l.merge)
+}
+
+fn viewers() -> Vec<Viewer> {
+    VIEWERS.with(|v| v.borrow().clone())
+}
+
+fn notify_listeners() {
+    let listeners: Vec<Callback<()>> = LISTENERS.with(|l| l.borrow().iter().map(|(_, c)| c.clone()).collect());
+    for listener in listeners {
+        listener.emit(());
+    }
+}
+
+fn set_status(status: LiveStatus) {
+    STATUS.with(|s| *s.borrow_mut() = status);
+    notify_listeners();
+}
+
+fn log(message: &str) {
+    web_sys::console::log_1(&format!("Live sync: {}", message).into());
+}
+
+fn socket_url() -> Option<String> {
+    let location = window()?.location();
+    let scheme = if location.protocol().ok()? == "https:" { "wss" } else { "ws" };
+    Some(format!("{}://{}/api/live", scheme, location.host().ok()?))
+}
+
+fn send(message: serde_json::Value) {
+    CONNECTION.with(|c| {
+        if let Some(connection) = c.borrow().as_ref() {
+            let _ = connection.socket.send_with_str(&message.to_string());
+        }
+    });
+}
+
+fn send_presence() {
+    let (record, editing) = PRESENCE.with(|p| p.borrow().clone());
+    send(serde_json::json!({ "type": "presence", "record": record, "editing": editing }));
+}
+
+fn connect() {
+    RECONNECT.with(|r| *r.borrow_mut() = None);
+    let (Some(linked), Some(session), Some(url)) = (link(), server_api::session(), socket_url()) else {
+        return;
+    };
+    let socket = match WebSocket::new(&url) {
+        Ok(socket) => socket,
+        Err(e) => {
+            set_status(LiveStatus::Offline(format!("{:?}", e)));
+            return;
+        }
+    };
+    set_status(LiveStatus::Connecting);
+
+    let hello = serde_json::json!({ "type": "hello", "token": session.token, "workspace": linked.space.id });
+    let sender = socket.clone();
+    let open = EventListener::new(&socket, "open", move |_| {
+        let _ = sender.send_with_str(&hello.to_string());
+    });
+    let message = EventListener::new(&socket, "message", |event| {
+        if let Some(text) = event.dyn_ref::<MessageEvent>().and_then(|e| e.data().as_string()) {
+            receive(&text);
+        }
+    });
+    let close = EventListener::new(&socket, "close", |_| {
+        CONNECTION.with(|c| *c.borrow_mut() = None);
+        VIEWERS.with(|v| v.borrow_mut().clear());
+        // A connection the server turned down stays down until the link changes
+        if matches!(status(), LiveStatus::Offline(_)) {
+            notify_listeners();
+            return;
+        }
+        set_status(LiveStatus::Offline("Disconnected".to_string()));
+        // Keep trying while the link lasts
+        if link().is_some() {
+            let timeout = Timeout::new(RECONNECT_MS, connect);
+            RECONNECT.with(|r| *r.borrow_mut() = Some(timeout));
+        }
+    });
+    CONNECTION.with(|c| {
+        *c.borrow_mut() = Some(Connection {
+            socket,
+            listeners: vec![open, message, close],
+        })
+    });
+}
+
+fn disconnect() {
+    RECONNECT.with(|r| *r.borrow_mut() = None);
+    // Dropping the listeners first keeps the close handler from reconnecting
+    if let Some(connection) = CONNECTION.with(|c| c.borrow_mut().take()) {
+        drop(connection.listeners);
+        let _ = connection.socket.close();
+    }
+    VIEWERS.with(|v| v.borrow_mut().clear());
+}
+
+fn receive(text: &str) {
+    let Ok(message) = serde_json::from_str::<ServerMessage>(text) else {
+        return;
+    };
+    match message {
+        ServerMessage::Ready { role } => {
+            READ_ONLY.with(|r| r.set(role == "viewer"));
+            set_status(LiveStatus::Connected);
+            send_presence();
+            spawn_resync();
+        }
+        ServerMessage::Resync => spawn_resync(),
+        // Merged records arrive as operations instead
+        ServerMessage::Change(event) => {
+            if !merging() && event.origin.as_deref() != Some(server_api::client_id().as_str()) {
+                apply(event);
+            }
+        }
+        ServerMessage::Ops { epoch, seq } => {
+            let behind = link()
+                .and_then(|l| load_merge(&l.workspace))
+                .is_none_or(|state| state.epoch != epoch || seq > state.seq);
+            if merging() && behind {
+                schedule_sync();
+            }
+        }
+        ServerMessage::Presence { viewers } => {
+            VIEWERS.with(|v| *v.borrow_mut() = viewers);
+            notify_listeners();
+        }
+        ServerMessage::Error { message } => {
+            log(&message);
+            set_status(LiveStatus::Offline(message));
+        }
+    }
+}
+
+fn emit_change(workspace_id: &str, change: Change) {
+    if change.uuids.is_empty() || workspace_id != workspace::active_id() {
+        return;
+    }
+    if let Some(on_change) = ON_CHANGE.with(|c| c.borrow().clone()) {
+        on_change.emit(change);
+    }
+}
+
+// Store a collection without pushing it straight back to the server
+fn save_quietly(save: impl FnOnce() -> Result<(), String>) {
+    SUPPRESS_PUSH.with(|s| s.set(true));
+    if let Err(e) = save() {
+        log(&e);
+    }
+    SUPPRESS_PUSH.with(|s| s.set(false));
+}
+
+// Put one record from the server into a local collection, or remove it
+fn merge<T: Serialize + DeserializeOwned>(records: &mut Vec<T>, uuid: &str, record: Option<&serde_json::Value>, uuid_of: fn(&T) -> &str) -> Option<String> {
+    let position = records.iter().position(|r| uuid_of(r) == uuid);
+    match record.and_then(|r| serde_json::from_value::<T>(r.clone()).ok()) {
+        Some(record) => {
+            let json = serde_json::to_string(&record).ok();
+            match position {
+                Some(index) => records[index] = record,
+                None => records.push(record),
+            }
+            json
+        }
+        None => {
+            if let Some(index) = position {
+                records.remove(index);
+            }
+            None
+        }
+    }
+}
+
+// A teammate's change, pushed by the server
+fn apply(event: RecordEvent) {
+    let Some(link) = link() else {
+        return;
+    };
+    let record = if event.action == "deleted" { None } else { event.record.as_ref() };
+    let (collection, json) = match event.collection.as_str() {
+        "outside" => {
+            let mut outsides = storage::get_outsides_in(&link.workspace);
+            let json = merge(&mut outsides, &event.uuid, record, |o: &Outside| &o.uuid);
+            save_quietly(|| storage::save_outsides_in(&link.workspace, &outsides));
+            (Collection::Outside, json)
+        }
+        "inside" => {
+            let mut insides = storage::get_insides_in(&link.workspace);
+            let json = merge(&mut insides, &event.uuid, record, |i: &Inside| &i.uuid);
+            save_quietly(|| storage::save_insides_in(&link.workspace, &insides));
+            (Collection::Inside, json)
+        }
+        _ => return,
+    };
+    SYNCED.with(|s| {
+        if let Some(synced) = s.borrow_mut().as_mut() {
+            match json {
+                Some(json) => synced.of(collection).insert(event.uuid.clone(), json),
+                None => synced.of(collection).remove(&event.uuid),
+            };
+        }
+    });
+    save_synced();
+    emit_change(&link.workspace, Change {
+        collection,
+        uuids: vec![event.uuid],
+    });
+}
+
+fn to_json_map<T: Serialize>(records: &[T], uuid_of: fn(&T) -> &str) -> HashMap<String, String> {
+    records
+        .iter()
+        .map(|r| (uuid_of(r).to_string(), serde_json::to_string(r).unwrap_or_default()))
+        .collect()
+}
+
+// Send the local records that differ from what the server last had
+async fn push<T: Serialize>(space: Option<&str>, collection: Collection, records: &[T], uuid_of: fn(&T) -> &str) -> Result<(), String> {
+    let Some(mut synced) = SYNCED.with(|s| s.borrow_mut().as_mut().map(|synced| std::mem::take(synced.of(collection)))) else {
+        return Ok(());
+    };
+    let mut result = Ok(());
+    for record in records {
+        let uuid = uuid_of(record);
+        let json = serde_json::to_string(record).unwrap_or_default();
+        let sent = match synced.get(uuid) {
+            Some(previous) if *previous == json => continue,
+            // The server may have lost the record meanwhile
+            Some(_) => match server_api::update(space, collection, uuid, record).await {
+                Ok(()) => Ok(()),
+                Err(_) => server_api::create(space, collection, record).await,
+            },
+            None => server_api::create(space, collection, record).await,
+        };
+        match sent {
+            Ok(()) => {
+                synced.insert(uuid.to_string(), json);
+            }
+            Err(e) => result = Err(e),
+        }
+    }
+    let local: Vec<&str> = records.iter().map(uuid_of).collect();
+    let removed: Vec<String> = synced.keys().filter(|uuid| !local.contains(&uuid.as_str())).cloned().collect();
+    for uuid in removed {
+        match server_api::delete(space, collection, &uuid).await {
+            Ok(()) => {
+                synced.remove(&uuid);
+            }
+            Err(e) => result = Err(e),
+        }
+    }
+    SYNCED.with(|s| {
+        if let Some(current) = s.borrow_mut().as_mut() {
+            // Events that arrived during the requests are newer than what was sent
+            let newer = std::mem::take(current.of(collection));
+            synced.extend(newer);
+            *current.of(collection) = synced;
+        }
+    });
+    save_synced();
+    result
+}
+
+async fn push_all() -> Result<(), String> {
+    let Some(link) = link() else {
+        return Ok(());
+    };
+    load_synced(&link.workspace);
+    let space = link.space.id.as_deref();
+    push(space, Collection::Outside, &storage::get_outsides_in(&link.workspace), |o| &o.uuid).await?;
+    push(space, Collection::Inside, &storage::get_insides_in(&link.workspace), |i| &i.uuid).await
+}
+
+// Replace the linked workspace with the server's records
+async fn pull() -> Result<(), String> {
+    let Some(link) = link() else {
+        return Ok(());
+    };
+    let space = link.space.id.as_deref();
+    let outsides: Vec<Outside> = server_api::list(space, Collection::Outside).await?;
+    let insides: Vec<Inside> = server_api::list(space, Collection::Inside).await?;
+    SYNCED.with(|s| {
+        *s.borrow_mut() = Some(Synced {
+            outside: to_json_map(&outsides, |o| &o.uuid),
+            inside: to_json_map(&insides, |i| &i.uuid),
+        })
+    });
+    save_synced();
+    replace_local(&link.workspace, &outsides, &insides);
+    Ok(())
+}
+
+// Store records from the server in place of the local ones and report what changed
+fn replace_local(workspace_id: &str, outsides: &[Outside], insides: &[Inside]) {
+    let changed_outsides = storage::changed_uuids(&storage::get_outsides_in(workspace_id), outsides, |o| &o.uuid);
+    let changed_insides = storage::changed_uuids(&storage::get_insides_in(workspace_id), insides, |i| &i.uuid);
+    save_quietly(|| {
+        storage::save_outsides_in(workspace_id, outsides)?;
+        storage::save_insides_in(workspace_id, insides)
+    });
+    emit_change(workspace_id, Change {
+        collection: Collection::Outside,
+        uuids: changed_outsides,
+    });
+    emit_change(workspace_id, Change {
+        collection: Collection::Inside,
+        uuids: changed_insides,
+    });
+}
+
+fn synced_key(workspace_id: &str) -> String {
+    format!("{}{}", SYNCED_KEY_PREFIX, workspace_id)
+}
+
+// Take the stored snapshot after a reload; one this tab already has is newer
+fn load_synced(workspace_id: &str) {
+    if SYNCED.with(|s| s.borrow().is_some()) {
+        return;
+    }
+    let stored = storage::read_value::<Synced>(&synced_key(workspace_id)).ok();
+    SYNCED.with(|s| *s.borrow_mut() = stored);
+}
+
+fn save_synced() {
+    let Some(link) = link() else {
+        return;
+    };
+    let key = synced_key(&link.workspace);
+    SYNCED.with(|s| match s.borrow().as_ref() {
+        Some(synced) => {
+            if let Err(e) = storage::write_value(&key, synced) {
+                log(&format!("Failed to store synced records: {}", e));
+            }
+        }
+        None => LocalStorage::delete(key),
+    });
+}
+
+// Keys of what is stored about the linked workspace, re-saved when encryption changes
+pub fn stored_keys() -> Vec<String> {
+    link()
+        .map(|link| vec![synced_key(&link.workspace), merge_key(&link.workspace)])
+        .unwrap_or_default()
+}
+
+fn merge_key(workspace_id: &str) -> String {
+    format!("{}{}", MERGE_KEY_PREFIX, workspace_id)
+}
+
+// The stored replica, taken over by this tab: its operations get this tab's replica id
+fn load_merge(workspace_id: &str) -> Option<MergeState> {
+    let mut state: MergeState = storage::read_value(&merge_key(workspace_id)).ok()?;
+    state.doc.replica = server_api::client_id();
+    Some(state)
+}
+
+fn save_merge(workspace_id: &str, state: &MergeState) {
+    if let Err(e) = storage::write_value(&merge_key(workspace_id), state) {
+        log(&format!("Failed to store merged records: {}", e));
+    }
+}
+
+// Drop the stored replica; the next sync rebuilds it from the server's journal
+fn forget_merged() {
+    if let Some(link) = link() {
+        LocalStorage::delete(merge_key(&link.workspace));
+    }
+}
+
+// Turn local saves since the last sync into operations waiting to be sent
+fn record_local(workspace_id: &str, state: &mut MergeState) {
+    if READ_ONLY.with(|r| r.get()) {
+        return;
+    }
+    let outsides = storage::get_outsides_in(workspace_id);
+    let insides = storage::get_insides_in(workspace_id);
+    let mut ops = state.doc.update(Collection::Outside, &outsides);
+    ops.extend(state.doc.update(Collection::Inside, &insides));
+    state.outbox.extend(ops);
+}
+
+// Operations after a point in the server's journal, starting the journal when there is none yet
+async fn fetch_ops(space: Option<&str>, epoch: u64, since: usize) -> Result<OpsPage, String> {
+    let page = server_api::ops_since(space, epoch, since).await?;
+    if page.epoch != 0 {
+        return Ok(page);
+    }
+    // The server starts it from its records when sent operations, even none
+    server_api::send_ops(space, &[]).await?;
+    server_api::ops_since(space, epoch, since).await
+}
+
+// Apply a page asked for from `since` operations into `epoch`. A page from another epoch holds
+// the journal after a compaction, which replaces the replica
+fn take_page(state: &mut MergeState, page: OpsPage, epoch: u64, since: usize) {
+    let start = if page.epoch == epoch { since } else { 0 };
+    if page.epoch != state.epoch {
+        state.doc.replace(page.base.unwrap_or_default());
+        state.epoch = page.epoch;
+        state.seq = 0;
+    }
+    // Another tab may have taken some of them meanwhile
+    if page.seq > state.seq {
+        let skip = state.seq.saturating_sub(start);
+        state.doc.apply(page.ops.into_iter().skip(skip));
+        state.seq = page.seq;
+    }
+}
+
+// Merge mode: send local edits as operations, take the server's new operations and keep
+// the merged records. The replica is stored around every request so tabs sharing it stay in step
+async fn merge_sync() -> Result<(), String> {
+    let Some(link) = link() else {
+        return Ok(());
+    };
+    let space = link.space.id.as_deref();
+    let mut state = match load_merge(&link.workspace) {
+        Some(state) => state,
+        None => {
+            let mut state = MergeState {
+                doc: Doc::new(server_api::client_id()),
+                epoch: 0,
+                seq: 0,
+                outbox: Vec::new(),
+            };
+            take_page(&mut state, fetch_ops(space, 0, 0).await?, 0, 0);
+            state
+        }
+    };
+    record_local(&link.workspace, &mut state);
+    save_merge(&link.workspace, &state);
+
+    if !state.outbox.is_empty() && !READ_ONLY.with(|r| r.get()) {
+        let sent = state.outbox.clone();
+        server_api::send_ops(space, &sent).await?;
+        state = load_merge(&link.workspace).unwrap_or(state);
+        state.outbox.retain(|op| !sent.contains(op));
+        save_merge(&link.workspace, &state);
+    }
+
+    let (epoch, since) = (state.epoch, state.seq);
+    let page = fetch_ops(space, epoch, since).await?;
+    state = load_merge(&link.workspace).unwrap_or(state);
+    take_page(&mut state, page, epoch, since);
+    // Saves made during the requests are kept rather than overwritten
+    record_local(&link.workspace, &mut state);
+    let outsides: Vec<Outside> = state.doc.records(Collection::Outside);
+    let insides: Vec<Inside> = state.doc.records(Collection::Inside);
+    save_merge(&link.workspace, &state);
+    replace_local(&link.workspace, &outsides, &insides);
+    Ok(())
+}
+
+// After (re)connecting: send what changed offline, then take the server's records
+fn spawn_resync() {
+    if merging() {
+        schedule_sync();
+        return;
+    }
+    wasm_bindgen_futures::spawn_local(async {
+        if !READ_ONLY.with(|r| r.get())
+            && let Err(e) = push_all().await
+        {
+            log(&format!("Failed to send offline changes: {}", e));
+        }
+        if let Err(e) = pull().await {
+            log(&format!("Failed to fetch records: {}", e));
+        }
+    });
+}
+
+// Send the linked workspace's local saves to the server, coalescing bursts of saves
+pub fn notify_change(workspace_id: &str) {
+    if SUPPRESS_PUSH.with(|s| s.get()) || READ_ONLY.with(|r| r.get()) || link().is_none_or(|l| l.workspace != workspace_id) || status() != LiveStatus::Connected {
+        return;
+    }
+    schedule_sync();
+}
+
+// Run one sync at a time, and once more when asked again meanwhile
+fn schedule_sync() {
+    if PUSH_PENDING.with(|p| p.get()) {
+        PUSH_AGAIN.with(|a| a.set(true));
+        return;
+    }
+    PUSH_PENDING.with(|p| p.set(true));
+    wasm_bindgen_futures::spawn_local(async {
+        loop {
+            let result = if merging() { merge_sync().await } else { push_all().await };
+            if let Err(e) = result {
+                log(&format!("Failed to sync changes: {}", e));
+            }
+            if !PUSH_AGAIN.with(|a| a.replace(false)) {
+                break;
+            }
+        }
+        PUSH_PENDING.with(|p| p.set(false));
+    });
+}
+
+// Link the active workspace to records on the server; an empty server copy is filled
+// with the local records, otherwise the server's records replace the local ones
+pub async fn link_active(space: RemoteSpace, merge: bool) -> Result<(), String> {
+    let id = space.id.as_deref();
+    let outsides: Vec<Outside> = server_api::list(id, Collection::Outside).await?;
+    let insides: Vec<Inside> = server_api::list(id, Collection::Inside).await?;
+    let upload = outsides.is_empty() && insides.is_empty();
+    disconnect();
+    forget_merged();
+    SYNCED.with(|s| *s.borrow_mut() = None);
+    save_synced();
+    let link = Link {
+        workspace: workspace::active_id(),
+        space,
+        merge,
+    };
+    LocalStorage::set(LINK_KEY, &link).map_err(|e| format!("{:?}", e))?;
+    // Merge mode starts from the server's records, so only later edits count as local ones
+    if merge && !upload {
+        replace_local(&link.workspace, &outsides, &insides);
+    }
+    SYNCED.with(|s| *s.borrow_mut() = upload.then(Synced::default));
+    save_synced();
+    connect();
+    Ok(())
+}
+
+pub fn unlink() {
+    forget_merged();
+    SYNCED.with(|s| *s.borrow_mut() = None);
+    save_synced();
+    LocalStorage::delete(LINK_KEY);
+    disconnect();
+    set_status(LiveStatus::Unlinked);
+}
+
+// Stop following a deleted workspace and drop what is stored about it. A tab told of the
+// deletion after another tab unlinked it finds the link gone and disconnects too
+pub fn forget(workspace_id: &str) {
+    let linked = link().map(|link| link.workspace);
+    if linked.as_deref() == Some(workspace_id) || (linked.is_none() && CONNECTION.with(|c| c.borrow().is_some())) {
+        unlink();
+    }
+    LocalStorage::delete(synced_key(workspace_id));
+    LocalStorage::delete(merge_key(workspace_id));
+}
+
+// Connect the linked workspace, reporting teammates' changes while connected
+pub fn start(on_change: Callback<Change>) {
+    ON_CHANGE.with(|c| *c.borrow_mut() = Some(on_change));
+    if CONNECTION.with(|c| c.borrow().is_none()) {
+        connect();
+    }
+}
+
+pub fn stop() {
+    ON_CHANGE.with(|c| *c.borrow_mut() = None);
+    disconnect();
+}
+
+fn set_presence(record: Option<String>, editing: bool) {
+    PRESENCE.with(|p| *p.borrow_mut() = (record, editing));
+    send_presence();
+}
+
+// Re-render on status and presence updates
+#[hook]
+pub fn use_live_status() -> (LiveStatus, Vec<Viewer>) {
+    let state = use_state(|| (status(), viewers()));
+    {
+        let state = state.clone();
+        use_effect_with((), move |_| {
+            let id = NEXT_LISTENER.with(|n| n.replace(n.get() + 1));
+            let listener = Callback::from(move |_| state.set((status(), viewers())));
+            LISTENERS.with(|l| l.borrow_mut().push((id, listener)));
+            move || LISTENERS.with(|l| l.borrow_mut().retain(|(i, _)| *i != id))
+        });
+    }
+    (*state).clone()
+}
+
+// Tell teammates this tab shows or edits a record, and list the others on it
+#[hook]
+pub fn use_presence(record: Option<String>, editing: bool) -> Vec<Viewer> {
+    let (_, viewers) = use_live_status();
+    let linked = link().is_some_and(|l| l.workspace == workspace::active_id());
+    use_effect_with((record.clone(), editing, linked), |(record, editing, linked)| {
+        if *linked {
+            set_presence(record.clone(), *editing);
+        }
+        || set_presence(None, false)
+    });
+    let me = server_api::session().map(|s| s.username);
+    viewers
+        .into_iter()
+        .filter(|v| linked && record.is_some() && v.record == record && Some(&v.username) != me.as_ref())
+        .collect()
+}
+
+// "✏️ alice editing · 👀 bob viewing"
+pub fn describe(viewers: &[Viewer]) -> String {
+    viewers
+        .iter()
+        .map(|v| if v.editing { format!("✏️ {} editing", v.username) } else { format!("👀 {} viewing", v.username) })
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
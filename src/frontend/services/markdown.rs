@@ -0,0 +1,214 @@
+use pulldown_cmark::{html, CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd};
+use crate::frontend::services::highlight::{escape_html, highlight};
+use crate::frontend::services::wiki;
+
+// Link targets that cannot run script; anything else is dropped
+pub fn safe_url(url: &str) -> String {
+    let cleaned: String = url.chars().filter(|c| !c.is_whitespace() && !c.is_control()).collect();
+    let scheme = cleaned
+        .split_once(':')
+        .filter(|(scheme, _)| !scheme.contains(['/', '?', '#']))
+        .map(|(scheme, _)| scheme.to_lowercase());
+    match scheme.as_deref() {
+        None | Some("http") | Some("https") | Some("mailto") => url.trim().to_string(),
+        Some(_) => "#".to_string(),
+    }
+}
+
+// Whether a record's link can be stored: none at all, or a web address
+pub fn is_web_url(url: &str) -> bool {
+    let url = url.trim();
+    url.is_empty()
+        || ["http://", "https://"]
+            .iter()
+            .any(|scheme| url.get(..scheme.len()).is_some_and(|start| start.eq_ignore_ascii_case(scheme)))
+}
+
+// Plain text with its `[[wiki links]]` turned into internal links
+fn push_text(events: &mut Vec<Event<'static>>, text: &mut String, resolve: &dyn Fn(&str) -> Option<String>) {
+    if text.is_empty() {
+        return;
+    }
+    let links = wiki::parse_links(text);
+    if links.is_empty() {
+        events.push(Event::Text(CowStr::from(std::mem::take(text))));
+        return;
+    }
+
+    let mut rendered = String::new();
+    let mut last = 0;
+    for link in links {
+        rendered.push_str(&escape_html(&text[last..link.range.start]));
+        match resolve(&link.target) {
+            Some(href) => rendered.push_str(&format!(
+                "<a href=\"{}\" class=\"wiki-link\">{}</a>",
+                escape_html(&href),
+                escape_html(&link.label)
+            )),
+            None => rendered.push_str(&format!(
+                "<span class=\"wiki-link missing\" title=\"No entry named {}\">{}</span>",
+                escape_html(&link.target),
+                escape_html(&link.label)
+            )),
+        }
+        last = link.range.end;
+    }
+    rendered.push_str(&escape_html(&text[last..]));
+    events.push(Event::Html(CowStr::from(rendered)));
+    text.clear();
+}
+
+// Render CommonMark to HTML that is safe to inject: raw HTML is shown as text,
+// links open in a new tab and only use safe schemes, line breaks are kept, fenced code
+// is highlighted, and `[[wiki links]]` point to the path `resolve` returns for their target
+pub fn render(source: &str, resolve: &dyn Fn(&str) -> Option<String>) -> String {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut events: Vec<Event<'static>> = Vec::new();
+    let mut code_block: Option<(String, String)> = None;
+    // Text is buffered so links split across several text events are still found
+    let mut text = String::new();
+    // Depth of links and images, whose text is left alone
+    let mut anchor_depth = 0;
+
+    for event in Parser::new_ext(source, options) {
+        if let Some((lang, code)) = code_block.as_mut() {
+            match event {
+                Event::Text(chunk) => code.push_str(&chunk),
+                Event::End(TagEnd::CodeBlock) => {
+                    let class = if lang.is_empty() { String::new() } else {
+                        format!(" class=\"language-{}\"", escape_html(lang))
+                    };
+                    let block = format!("<pre><code{}>{}</code></pre>\n", class, highlight(code, lang));
+                    events.push(Event::Html(block.into()));
+                    code_block = None;
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        if let Event::Text(chunk) = &event
+            && anchor_depth == 0
+        {
+            text.push_str(chunk);
+            continue;
+        }
+        push_text(&mut events, &mut text, resolve);
+
+        let event = match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let lang = match kind {
+                    CodeBlockKind::Fenced(info) => info.split_whitespace().next().unwrap_or("").to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                code_block = Some((lang, String::new()));
+                continue;
+            }
+            Event::Start(Tag::Link { dest_url, title, .. }) => {
+                anchor_depth += 1;
+                let title = if title.is_empty() { String::new() } else {
+                    format!(" title=\"{}\"", escape_html(&title))
+                };
+                Event::Html(
+                    format!(
+                        "<a href=\"{}\"{} target=\"_blank\" rel=\"noopener noreferrer\">",
+                        escape_html(&safe_url(&dest_url)),
+                        title
+                    )
+                    .into(),
+                )
+            }
+            Event::End(TagEnd::Link) => {
+                anchor_depth -= 1;
+                Event::Html("</a>".into())
+            }
+            Event::Start(Tag::Image { link_type, dest_url, title, id }) => {
+                anchor_depth += 1;
+                Event::Start(Tag::Image {
+                    link_type,
+                    dest_url: safe_url(&dest_url).into(),
+                    title,
+                    id,
+                })
+            }
+            Event::End(TagEnd::Image) => {
+                anchor_depth -= 1;
+                Event::End(TagEnd::Image)
+            }
+            Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+            // Notes are written line by line, so keep single line breaks
+            Event::SoftBreak => Event::HardBreak,
+            other => other,
+        };
+        events.push(event.into_static());
+    }
+    push_text(&mut events, &mut text, resolve);
+
+    let mut output = String::new();
+    html::push_html(&mut output, events.into_iter());
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(source: &str) -> String {
+        render(source, &|_| None)
+    }
+
+    #[test]
+    fn script_urls_are_dropped() {
+        assert_eq!(safe_url("javascript:alert(1)"), "#");
+        assert_eq!(safe_url("JaVaScRiPt:alert(1)"), "#");
+        assert_eq!(safe_url(" \t javascript:alert(1)"), "#");
+        assert_eq!(safe_url("java\u{0}script:alert(1)"), "#");
+        assert_eq!(safe_url("java\nscript:alert(1)"), "#");
+        assert_eq!(safe_url("\u{1}javascript:alert(1)"), "#");
+        assert_eq!(safe_url("data:text/html,<script>alert(1)</script>"), "#");
+        assert_eq!(safe_url("vbscript:msgbox(1)"), "#");
+    }
+
+    #[test]
+    fn web_and_relative_urls_are_kept() {
+        assert_eq!(safe_url("https://example.com/a?b=c#d"), "https://example.com/a?b=c#d");
+        assert_eq!(safe_url("  http://example.com "), "http://example.com");
+        assert_eq!(safe_url("mailto:someone@example.com"), "mailto:someone@example.com");
+        assert_eq!(safe_url("/outside/1"), "/outside/1");
+        assert_eq!(safe_url("page?next=javascript:x"), "page?next=javascript:x");
+    }
+
+    #[test]
+    fn only_web_links_can_be_stored() {
+        assert!(is_web_url(""));
+        assert!(is_web_url(" HTTPS://example.com"));
+        assert!(is_web_url("http://example.com"));
+        assert!(!is_web_url("javascript:alert(1)"));
+        assert!(!is_web_url("data:text/html,x"));
+        assert!(!is_web_url("example.com"));
+    }
+
+    #[test]
+    fn raw_html_is_escaped() {
+        let html = plain("<script>alert(1)</script>\n\nText <img src=x onerror=alert(1)>");
+        assert!(!html.contains("<script"), "{}", html);
+        assert!(!html.contains("<img"), "{}", html);
+        assert!(html.contains("&lt;script&gt;"), "{}", html);
+    }
+
+    #[test]
+    fn links_use_safe_urls() {
+        let html = plain("[click](javascript:alert(1)) and ![img](data:image/svg+xml,x)");
+        assert!(html.contains("href=\"#\""), "{}", html);
+        assert!(!html.contains("javascript:"), "{}", html);
+        assert!(!html.contains("data:"), "{}", html);
+        assert!(plain("[site](https://example.com)").contains("href=\"https://example.com\""));
+    }
+
+    #[test]
+    fn wiki_links_are_escaped() {
+        let html = render("See [[Tom & \"Jerry\"]]", &|_| Some("/outside/\"1".to_string()));
+        assert!(html.contains("href=\"/outside/&quot;1\""), "{}", html);
+        assert!(html.contains(">Tom &amp; &quot;Jerry&quot;</a>"), "{}", html);
+    }
+}
@@ -1,2 +1,24 @@
+pub mod capture;
+pub mod crdt;
+pub mod crypto;
+pub mod file_sync;
+pub mod highlight;
+pub mod idb;
+pub mod live;
+pub mod markdown;
+pub mod pwa;
+pub mod related;
 pub mod router;
-pub mod storage;
\ No newline at end of file
+pub mod selection;
+pub mod server_api;
+pub mod shortcuts;
+pub mod snapshot;
+pub mod stats;
+pub mod storage;
+pub mod store;
+pub mod tab_sync;
+pub mod tags;
+pub mod url_share;
+pub mod view;
+pub mod wiki;
+pub mod workspace;
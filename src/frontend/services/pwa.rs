@@ -0,0 +1,101 @@
+use std::cell::{Cell, RefCell};
+use gloo::events::EventListener;
+use gloo::timers::callback::Interval;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{window, ServiceWorker, ServiceWorkerContainer, ServiceWorkerRegistration, ServiceWorkerState};
+use yew::Callback;
+
+// Copied from pwa/sw.js by Trunk, with the build's files listed by pwa/precache.sh
+const SCRIPT: &str = "/sw.js";
+const SKIP_WAITING: &str = "skip-waiting";
+// How often a tab left open looks for a new deployment
+const CHECK_MS: u32 = 60 * 60 * 1000;
+
+thread_local! {
+    static LISTENERS: RefCell<Vec<EventListener>> = const { RefCell::new(Vec::new()) };
+    static CHECK: RefCell<Option<Interval>> = const { RefCell::new(None) };
+    // Set once an update was accepted, so the page reloads when the new worker takes over
+    static RELOAD: Cell<bool> = const { Cell::new(false) };
+}
+
+fn log(message: &str) {
+    web_sys::console::log_1(&format!("Service worker: {}", message).into());
+}
+
+// Only there on https and localhost
+fn container() -> Option<ServiceWorkerContainer> {
+    let navigator = window()?.navigator();
+    js_sys::Reflect::has(&navigator, &JsValue::from_str("serviceWorker"))
+        .ok()?
+        .then(|| navigator.service_worker())
+}
+
+// Report a new worker once it has installed while an older one still controls the page
+fn watch(worker: ServiceWorker, container: ServiceWorkerContainer, on_update: Callback<ServiceWorker>) {
+    let listener = EventListener::new(&worker.clone(), "statechange", move |_| {
+        if worker.state() == ServiceWorkerState::Installed && container.controller().is_some() {
+            on_update.emit(worker.clone());
+        }
+    });
+    LISTENERS.with(|l| l.borrow_mut().push(listener));
+}
+
+// Register the service worker that keeps the app shell offline; `on_update` gets the worker
+// of a newer build once it waits to take over
+pub fn register(on_update: Callback<ServiceWorker>) {
+    let Some(container) = container() else {
+        return;
+    };
+    wasm_bindgen_futures::spawn_local(async move {
+        let registration: ServiceWorkerRegistration = match JsFuture::from(container.register(SCRIPT)).await {
+            Ok(registration) => registration.unchecked_into(),
+            Err(e) => {
+                log(&format!("Failed to register: {:?}", e));
+                return;
+            }
+        };
+        // Installed on an earlier visit and still waiting
+        if let Some(waiting) = registration.waiting()
+            && container.controller().is_some()
+        {
+            on_update.emit(waiting);
+        }
+
+        let found = {
+            let registration = registration.clone();
+            let container = container.clone();
+            EventListener::new(&registration.clone(), "updatefound", move |_| {
+                if let Some(worker) = registration.installing() {
+                    watch(worker, container.clone(), on_update.clone());
+                }
+            })
+        };
+        let took_over = EventListener::new(&container, "controllerchange", |_| {
+            if RELOAD.with(|r| r.get())
+                && let Some(window) = window()
+            {
+                let _ = window.location().reload();
+            }
+        });
+        LISTENERS.with(|l| *l.borrow_mut() = vec![found, took_over]);
+
+        let check = Interval::new(CHECK_MS, move || {
+            if let Ok(promise) = registration.update() {
+                // Fails quietly while offline
+                wasm_bindgen_futures::spawn_local(async move {
+                    let _ = JsFuture::from(promise).await;
+                });
+            }
+        });
+        CHECK.with(|c| *c.borrow_mut() = Some(check));
+    });
+}
+
+// Let the waiting worker take over; the page reloads into the new build once it has
+pub fn activate(worker: &ServiceWorker) {
+    RELOAD.with(|r| r.set(true));
+    if let Err(e) = worker.post_message(&JsValue::from_str(SKIP_WAITING)) {
+        log(&format!("Failed to activate the update: {:?}", e));
+    }
+}
@@ -0,0 +1,62 @@
+use crate::models::{Outside, Inside};
+use crate::frontend::services::view::domain_of;
+
+fn mentions(text: &str, name: &str) -> bool {
+    let name = name.trim();
+    !name.is_empty() && text.to_lowercase().contains(&name.to_lowercase())
+}
+
+// Diary entries linked to the resource, newest first
+pub fn linked_insides<'a>(outside: &Outside, insides: &'a [Inside]) -> Vec<&'a Inside> {
+    let mut linked: Vec<&Inside> = insides
+        .iter()
+        .filter(|i| i.outside_uuids.contains(&outside.uuid))
+        .collect();
+    linked.sort_by_key(|i| std::cmp::Reverse(i.date));
+    linked
+}
+
+// Other resources hosted on the same site
+pub fn outsides_for_outside<'a>(outside: &Outside, outsides: &'a [Outside]) -> Vec<&'a Outside> {
+    let domain = domain_of(&outside.url);
+    if domain.is_empty() {
+        return Vec::new();
+    }
+    outsides
+        .iter()
+        .filter(|o| o.uuid != outside.uuid && domain_of(&o.url) == domain)
+        .collect()
+}
+
+// Diary entries that mention the resource by name
+pub fn insides_for_outside<'a>(outside: &Outside, insides: &'a [Inside]) -> Vec<&'a Inside> {
+    insides
+        .iter()
+        .filter(|i| mentions(&i.context, &outside.name))
+        .collect()
+}
+
+// Resources mentioned by name in the entry
+pub fn outsides_for_inside<'a>(inside: &Inside, outsides: &'a [Outside]) -> Vec<&'a Outside> {
+    outsides
+        .iter()
+        .filter(|o| mentions(&inside.context, &o.name))
+        .collect()
+}
+
+// Other entries written the same day
+pub fn insides_for_inside<'a>(inside: &Inside, insides: &'a [Inside]) -> Vec<&'a Inside> {
+    insides
+        .iter()
+        .filter(|i| i.uuid != inside.uuid && i.date.day() == inside.date.day())
+        .collect()
+}
+
+// First characters of a text, for labelling links to an entry
+pub fn excerpt(text: &str, max_chars: usize) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", text[..end].trim_end()),
+        None => text.to_string(),
+    }
+}
@@ -8,11 +8,37 @@ pub enum Route {
     Relf,
     #[at("/outside")]
     Outside,
+    #[at("/outside/:uuid")]
+    OutsideDetail { uuid: String },
+    #[at("/outside/:uuid/edit")]
+    OutsideEdit { uuid: String },
     #[at("/inside")]
     Inside,
+    #[at("/inside/:uuid")]
+    InsideDetail { uuid: String },
+    #[at("/inside/:uuid/edit")]
+    InsideEdit { uuid: String },
+    #[at("/stats")]
+    Stats,
     #[at("/data")]
     Data,
+    // Records packed into the fragment, see services::url_share
+    #[at("/view")]
+    View,
+    // Prefilled add form for the bookmarklet and share sheet, see services::capture
+    #[at("/capture")]
+    Capture,
     #[not_found]
     #[at("/404")]
     NotFound,
-}
\ No newline at end of file
+}
+
+impl Route {
+    pub fn is_outside(&self) -> bool {
+        matches!(self, Route::Outside | Route::OutsideDetail { .. } | Route::OutsideEdit { .. })
+    }
+
+    pub fn is_inside(&self) -> bool {
+        matches!(self, Route::Inside | Route::InsideDetail { .. } | Route::InsideEdit { .. })
+    }
+}
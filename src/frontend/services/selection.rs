@@ -0,0 +1,23 @@
+// Selection after clicking the checkbox of `visible[index]`; with shift held the whole
+// range from the previously clicked card takes the clicked card's new state
+pub fn toggle(selected: &[String], visible: &[String], index: usize, anchor: Option<usize>, shift: bool) -> Vec<String> {
+    let Some(uuid) = visible.get(index) else {
+        return selected.to_vec();
+    };
+    let select = !selected.contains(uuid);
+    let range = match anchor.filter(|a| shift && *a < visible.len()) {
+        Some(anchor) => anchor.min(index)..=anchor.max(index),
+        None => index..=index,
+    };
+    let mut result: Vec<String> = selected.to_vec();
+    for uuid in &visible[range] {
+        let present = result.contains(uuid);
+        if select && !present {
+            result.push(uuid.clone());
+        } else if !select && present {
+            result.retain(|u| u != uuid);
+        }
+    }
+    result
+}
+
@@ -0,0 +1,185 @@
+use gloo::net::http::{Request, RequestBuilder, Response};
+use gloo::storage::{LocalStorage, Storage};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use crate::frontend::services::crdt::{Doc, Op};
+use crate::frontend::services::tab_sync::Collection;
+
+const SESSION_KEY: &str = "relf_server_session";
+// Lets the server tell our own changes apart in the events it pushes back
+const CLIENT_HEADER: &str = "X-Relf-Client";
+
+// A signed-in account on the relf server serving this page
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Session {
+    pub token: String,
+    pub username: String,
+}
+
+// Records on the server: the account's own when `id` is empty, or a team workspace's
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RemoteSpace {
+    pub id: Option<String>,
+    pub name: String,
+    pub role: String,
+}
+
+#[derive(Deserialize)]
+struct SessionReply {
+    token: String,
+    user: Account,
+}
+
+#[derive(Deserialize)]
+struct Account {
+    username: String,
+}
+
+#[derive(Deserialize)]
+struct TeamWorkspace {
+    id: String,
+    name: String,
+    role: String,
+}
+
+// Operations of a merge journal after the requested point, and its epoch and length. Asked
+// from another epoch, all of them come with the document they continue from
+#[derive(Deserialize)]
+pub struct OpsPage {
+    #[serde(default)]
+    pub epoch: u64,
+    pub seq: usize,
+    pub ops: Vec<Op>,
+    #[serde(default)]
+    pub base: Option<Doc>,
+}
+
+#[derive(Deserialize)]
+struct ErrorReply {
+    error: String,
+}
+
+thread_local! {
+    static CLIENT_ID: String = uuid::Uuid::new_v4().to_string();
+}
+
+pub fn client_id() -> String {
+    CLIENT_ID.with(|id| id.clone())
+}
+
+pub fn session() -> Option<Session> {
+    LocalStorage::get(SESSION_KEY).ok()
+}
+
+fn authorized(builder: RequestBuilder) -> RequestBuilder {
+    let builder = builder.header(CLIENT_HEADER, &client_id());
+    match session() {
+        Some(session) => builder.header("Authorization", &format!("Bearer {}", session.token)),
+        None => builder,
+    }
+}
+
+// The response if it succeeded, otherwise the error message the server gave
+async fn checked(request: Result<Response, gloo::net::Error>) -> Result<Response, String> {
+    let response = request.map_err(|e| format!("Server unreachable: {}", e))?;
+    if response.ok() {
+        return Ok(response);
+    }
+    let status = response.status();
+    Err(match response.json::<ErrorReply>().await {
+        Ok(reply) => reply.error,
+        Err(_) => format!("Server responded with status {}", status),
+    })
+}
+
+async fn send<T: Serialize>(builder: RequestBuilder, body: Option<&T>) -> Result<Response, String> {
+    let builder = authorized(builder);
+    let request = match body {
+        Some(body) => builder.json(body).map_err(|e| format!("Failed to serialize request: {}", e))?.send().await,
+        None => builder.send().await,
+    };
+    checked(request).await
+}
+
+async fn read<T: DeserializeOwned>(response: Response) -> Result<T, String> {
+    response.json().await.map_err(|e| format!("Invalid server response: {}", e))
+}
+
+pub async fn sign_in(username: &str, password: &str, register: bool) -> Result<Session, String> {
+    let url = if register { "/api/auth/register" } else { "/api/auth/login" };
+    let credentials = serde_json::json!({ "username": username, "password": password });
+    let reply: SessionReply = read(send(Request::post(url), Some(&credentials)).await?).await?;
+    let session = Session {
+        token: reply.token,
+        username: reply.user.username,
+    };
+    LocalStorage::set(SESSION_KEY, &session).map_err(|e| format!("{:?}", e))?;
+    Ok(session)
+}
+
+// Forget the session even when the server cannot be reached
+pub async fn sign_out() {
+    if let Err(e) = send::<()>(Request::post("/api/auth/logout"), None).await {
+        web_sys::console::log_1(&format!("Failed to end server session: {}", e).into());
+    }
+    LocalStorage::delete(SESSION_KEY);
+}
+
+// The account's own records followed by the team workspaces it belongs to
+pub async fn spaces() -> Result<Vec<RemoteSpace>, String> {
+    let teams: Vec<TeamWorkspace> = read(send::<()>(Request::get("/api/workspaces"), None).await?).await?;
+    let mut spaces = vec![RemoteSpace {
+        id: None,
+        name: "Personal".to_string(),
+        role: "owner".to_string(),
+    }];
+    spaces.extend(teams.into_iter().map(|t| RemoteSpace {
+        id: Some(t.id),
+        name: t.name,
+        role: t.role,
+    }));
+    Ok(spaces)
+}
+
+fn space_url(space: Option<&str>, path: &str) -> String {
+    match space {
+        Some(id) => format!("/api/workspaces/{}/{}", id, path),
+        None => format!("/api/{}", path),
+    }
+}
+
+fn collection_url(space: Option<&str>, collection: Collection) -> String {
+    let path = match collection {
+        Collection::Outside => "outside",
+        Collection::Inside => "inside",
+    };
+    space_url(space, path)
+}
+
+pub async fn list<T: DeserializeOwned>(space: Option<&str>, collection: Collection) -> Result<Vec<T>, String> {
+    read(send::<()>(Request::get(&collection_url(space, collection)), None).await?).await
+}
+
+pub async fn create<T: Serialize>(space: Option<&str>, collection: Collection, record: &T) -> Result<(), String> {
+    send(Request::post(&collection_url(space, collection)), Some(record)).await.map(|_| ())
+}
+
+pub async fn update<T: Serialize>(space: Option<&str>, collection: Collection, uuid: &str, record: &T) -> Result<(), String> {
+    let url = format!("{}/{}", collection_url(space, collection), uuid);
+    send(Request::put(&url), Some(record)).await.map(|_| ())
+}
+
+pub async fn delete(space: Option<&str>, collection: Collection, uuid: &str) -> Result<(), String> {
+    let url = format!("{}/{}", collection_url(space, collection), uuid);
+    send::<()>(Request::delete(&url), None).await.map(|_| ())
+}
+
+pub async fn ops_since(space: Option<&str>, epoch: u64, since: usize) -> Result<OpsPage, String> {
+    let url = format!("{}?epoch={}&since={}", space_url(space, "ops"), epoch, since);
+    read(send::<()>(Request::get(&url), None).await?).await
+}
+
+pub async fn send_ops(space: Option<&str>, ops: &[Op]) -> Result<(), String> {
+    let body = serde_json::json!({ "ops": ops });
+    send(Request::post(&space_url(space, "ops")), Some(&body)).await.map(|_| ())
+}
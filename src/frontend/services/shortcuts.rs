@@ -0,0 +1,190 @@
+use std::cell::RefCell;
+use wasm_bindgen::JsCast;
+use web_sys::{window, HtmlElement, KeyboardEvent};
+use yew::prelude::*;
+use crate::frontend::services::router::Route;
+
+// Actions the current page performs, usually on the card under the keyboard cursor
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PageCommand {
+    New,
+    Search,
+    Next,
+    Previous,
+    Edit,
+    Delete,
+}
+
+impl PageCommand {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PageCommand::New => "New entry",
+            PageCommand::Search => "Search",
+            PageCommand::Next => "Next card",
+            PageCommand::Previous => "Previous card",
+            PageCommand::Edit => "Edit",
+            PageCommand::Delete => "Delete",
+        }
+    }
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            PageCommand::New => "n",
+            PageCommand::Search => "/",
+            PageCommand::Next => "j",
+            PageCommand::Previous => "k",
+            PageCommand::Edit => "e",
+            PageCommand::Delete => "d",
+        }
+    }
+}
+
+// Commands of the Outside and Inside card lists
+pub const LIST_COMMANDS: [PageCommand; 6] = [
+    PageCommand::New,
+    PageCommand::Search,
+    PageCommand::Next,
+    PageCommand::Previous,
+    PageCommand::Edit,
+    PageCommand::Delete,
+];
+
+// Commands of a detail page, acting on the record it shows
+pub const DETAIL_COMMANDS: [PageCommand; 2] = [PageCommand::Edit, PageCommand::Delete];
+
+// Pages reached with `g` followed by a key
+pub const GO_TO: [(&str, &str, Route); 5] = [
+    ("o", "Outside", Route::Outside),
+    ("i", "Inside", Route::Inside),
+    ("s", "Stats", Route::Stats),
+    ("d", "Data", Route::Data),
+    ("h", "Home", Route::Home),
+];
+
+#[derive(Clone, PartialEq)]
+pub enum Shortcut {
+    Page(PageCommand),
+    Go(Route),
+    Palette,
+}
+
+// `after_g` is set when the previous key was a lone `g`
+pub fn shortcut_for(key: &str, after_g: bool) -> Option<Shortcut> {
+    if after_g {
+        return GO_TO
+            .iter()
+            .find(|(k, _, _)| *k == key)
+            .map(|(_, _, route)| Shortcut::Go(route.clone()));
+    }
+    let command = match key {
+        "n" => PageCommand::New,
+        "/" => PageCommand::Search,
+        "j" => PageCommand::Next,
+        "k" => PageCommand::Previous,
+        "e" => PageCommand::Edit,
+        "d" => PageCommand::Delete,
+        "?" => return Some(Shortcut::Palette),
+        _ => return None,
+    };
+    Some(Shortcut::Page(command))
+}
+
+pub fn is_palette_key(event: &KeyboardEvent) -> bool {
+    (event.ctrl_key() || event.meta_key()) && event.key().eq_ignore_ascii_case("k")
+}
+
+// Keys typed into a form field are text, not shortcuts
+pub fn is_typing(event: &KeyboardEvent) -> bool {
+    event
+        .target()
+        .and_then(|target| target.dyn_into::<HtmlElement>().ok())
+        .is_some_and(|element| {
+            matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT") || element.is_content_editable()
+        })
+}
+
+thread_local! {
+    // Commands of the page currently shown and its handler
+    static PAGE: RefCell<Option<(&'static [PageCommand], Callback<PageCommand>)>> = const { RefCell::new(None) };
+}
+
+// Commands the current page handles
+pub fn page_commands() -> &'static [PageCommand] {
+    PAGE.with(|page| page.borrow().as_ref().map(|(commands, _)| *commands).unwrap_or_default())
+}
+
+// Run a command on the current page; false if the page does not handle it
+pub fn run(command: PageCommand) -> bool {
+    let handler = PAGE.with(|page| {
+        page.borrow()
+            .as_ref()
+            .filter(|(commands, _)| commands.contains(&command))
+            .map(|(_, handler)| handler.clone())
+    });
+    match handler {
+        Some(handler) => {
+            handler.emit(command);
+            true
+        }
+        None => false,
+    }
+}
+
+// Register the page's handler for keyboard and palette commands while it is shown
+#[hook]
+pub fn use_page_commands(commands: &'static [PageCommand], handler: Callback<PageCommand>) {
+    use_effect_with(handler, move |handler| {
+        let handler = handler.clone();
+        PAGE.with(|page| *page.borrow_mut() = Some((commands, handler.clone())));
+        move || {
+            // The next page may already have registered its own handler
+            PAGE.with(|page| {
+                let mut page = page.borrow_mut();
+                if page.as_ref().is_some_and(|(_, current)| *current == handler) {
+                    *page = None;
+                }
+            });
+        }
+    });
+}
+
+pub fn focus(id: &str) {
+    if let Some(element) = window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.get_element_by_id(id))
+        .and_then(|e| e.dyn_into::<HtmlElement>().ok())
+    {
+        let _ = element.focus();
+    }
+}
+
+// Bring a card into view without jumping when it is already visible
+pub fn reveal(id: &str) {
+    if let Some(element) = window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.get_element_by_id(id))
+    {
+        let options = web_sys::ScrollIntoViewOptions::new();
+        options.set_block(web_sys::ScrollLogicalPosition::Nearest);
+        element.scroll_into_view_with_scroll_into_view_options(&options);
+    }
+}
+
+// Move a keyboard cursor through `len` cards
+pub fn step(cursor: Option<usize>, len: usize, command: PageCommand) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    Some(match (cursor, command) {
+        (None, _) => 0,
+        (Some(i), PageCommand::Next) => (i + 1).min(len - 1),
+        (Some(i), PageCommand::Previous) => i.saturating_sub(1),
+        (Some(i), _) => i.min(len - 1),
+    })
+}
+
+pub fn confirm(message: &str) -> bool {
+    window()
+        .and_then(|w| w.confirm_with_message(message).ok())
+        .unwrap_or(false)
+}
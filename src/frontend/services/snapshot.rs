@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+use crate::frontend::services::crypto;
+use crate::frontend::services::idb::{self, SNAPSHOT_STORE};
+use crate::frontend::services::storage::{self, AllWorkspacesData};
+
+const MAX_SNAPSHOTS: usize = 7;
+
+// One snapshot per day, keyed by the day it was taken (e.g. "2025-01-01")
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub id: String,
+    pub taken_at: String,
+    pub outside_count: usize,
+    pub inside_count: usize,
+    pub data: String,
+}
+
+impl Snapshot {
+    pub fn filename(&self) -> String {
+        format!("relf_snapshot_{}.json", self.id)
+    }
+}
+
+// List all stored snapshots, newest first
+pub async fn list_snapshots() -> Result<Vec<Snapshot>, String> {
+    let mut snapshots: Vec<Snapshot> = idb::get_all(SNAPSHOT_STORE)
+        .await?
+        .iter()
+        .filter_map(|value| value.as_string())
+        .filter_map(|json| serde_json::from_str(&json).ok())
+        .collect();
+    snapshots.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(snapshots)
+}
+
+// Store every workspace as today's snapshot, replacing an earlier one from today
+pub async fn take_snapshot() -> Result<(), String> {
+    let now = chrono::Local::now();
+    let (outside_count, inside_count) = storage::count_everything();
+    let snapshot = Snapshot {
+        id: now.format("%Y-%m-%d").to_string(),
+        taken_at: now.format("%Y-%m-%d %H:%M:%S").to_string(),
+        outside_count,
+        inside_count,
+        data: storage::export_everything_for_file()?,
+    };
+    let json = serde_json::to_string(&snapshot)
+        .map_err(|e| format!("Failed to serialize snapshot: {:?}", e))?;
+
+    idb::put(SNAPSHOT_STORE, &snapshot.id, &JsValue::from_str(&json)).await?;
+    prune_snapshots().await
+}
+
+// Take a snapshot only if none has been taken today
+pub async fn take_daily_snapshot() -> Result<(), String> {
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let snapshots = list_snapshots().await?;
+    if snapshots.iter().any(|s| s.id == today) {
+        return Ok(());
+    }
+    take_snapshot().await
+}
+
+async fn prune_snapshots() -> Result<(), String> {
+    let mut ids: Vec<String> = idb::get_all_keys(SNAPSHOT_STORE)
+        .await?
+        .iter()
+        .filter_map(|key| key.as_string())
+        .collect();
+    ids.sort_by(|a, b| b.cmp(a));
+
+    for id in ids.into_iter().skip(MAX_SNAPSHOTS) {
+        idb::delete(SNAPSHOT_STORE, &id).await?;
+    }
+    Ok(())
+}
+
+// Encrypt snapshots taken before encryption was enabled
+pub async fn encrypt_existing() -> Result<(), String> {
+    for mut snapshot in list_snapshots().await? {
+        if crypto::is_envelope(&snapshot.data) {
+            continue;
+        }
+        snapshot.data = crypto::encrypt(&snapshot.data)?;
+        let json = serde_json::to_string(&snapshot)
+            .map_err(|e| format!("Failed to serialize snapshot: {:?}", e))?;
+        idb::put(SNAPSHOT_STORE, &snapshot.id, &JsValue::from_str(&json)).await?;
+    }
+    Ok(())
+}
+
+// Take a deleted workspace out of the snapshots; those from before workspaces existed are
+// left as they are
+pub async fn forget_workspace(name: &str) -> Result<(), String> {
+    for mut snapshot in list_snapshots().await? {
+        let encrypted = crypto::is_envelope(&snapshot.data);
+        let json = if encrypted { crypto::decrypt(&snapshot.data)? } else { snapshot.data.clone() };
+        let Ok(mut data) = serde_json::from_str::<AllWorkspacesData>(&json) else {
+            continue;
+        };
+        let count = data.workspaces.len();
+        data.workspaces.retain(|w| !w.name.trim().eq_ignore_ascii_case(name.trim()));
+        if data.workspaces.len() == count {
+            continue;
+        }
+        snapshot.outside_count = data.workspaces.iter().map(|w| w.data.outside.len()).sum();
+        snapshot.inside_count = data.workspaces.iter().map(|w| w.data.inside.len()).sum();
+        let json = serde_json::to_string_pretty(&data)
+            .map_err(|e| format!("Failed to serialize snapshot: {:?}", e))?;
+        snapshot.data = if encrypted { crypto::encrypt(&json)? } else { json };
+        let json = serde_json::to_string(&snapshot)
+            .map_err(|e| format!("Failed to serialize snapshot: {:?}", e))?;
+        idb::put(SNAPSHOT_STORE, &snapshot.id, &JsValue::from_str(&json)).await?;
+    }
+    Ok(())
+}
+
+// Snapshots from before workspaces existed restore into the current workspace
+pub fn restore_snapshot(snapshot: &Snapshot) -> Result<(), String> {
+    storage::import_everything_from_json(&snapshot.data)
+}
+
+pub fn download_snapshot(snapshot: &Snapshot) {
+    storage::download_file(&snapshot.filename(), &snapshot.data);
+}
@@ -0,0 +1,136 @@
+use std::collections::{BTreeMap, HashMap};
+use chrono::{Datelike, Days, NaiveDate};
+use crate::models::{Inside, Outside, Status};
+use crate::frontend::services::tags::parse_tags;
+use crate::frontend::services::view::domain_of;
+
+pub const PERCENTAGE_BUCKETS: [&str; 6] = ["Not set", "0–24%", "25–49%", "50–74%", "75–99%", "100%"];
+
+// Monday of the week containing `day`
+pub fn week_start(day: NaiveDate) -> NaiveDate {
+    day - Days::new(u64::from(day.weekday().num_days_from_monday()))
+}
+
+pub fn entries_per_day(insides: &[Inside]) -> BTreeMap<NaiveDate, usize> {
+    let mut days = BTreeMap::new();
+    for inside in insides {
+        *days.entry(inside.date.day()).or_insert(0) += 1;
+    }
+    days
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct Streaks {
+    // Consecutive days with entries up to today, or yesterday if today has none yet
+    pub current: usize,
+    pub longest: usize,
+}
+
+pub fn streaks(days: &BTreeMap<NaiveDate, usize>, today: NaiveDate) -> Streaks {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for day in days.keys().filter(|day| **day <= today) {
+        run = match previous {
+            Some(previous) if previous.succ_opt() == Some(*day) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(*day);
+    }
+    let current = match previous {
+        Some(last) if last == today || last.succ_opt() == Some(today) => run,
+        _ => 0,
+    };
+    Streaks { current, longest }
+}
+
+// Entries in each of the last `weeks` weeks, oldest first, keyed by Monday
+pub fn entries_per_week(days: &BTreeMap<NaiveDate, usize>, today: NaiveDate, weeks: u64) -> Vec<(NaiveDate, usize)> {
+    let this_week = week_start(today);
+    (0..weeks)
+        .rev()
+        .map(|ago| {
+            let start = this_week - Days::new(ago * 7);
+            let end = start + Days::new(6);
+            (start, days.range(start..=end).map(|(_, count)| count).sum())
+        })
+        .collect()
+}
+
+// Shade of a heatmap cell, 0 for no entries up to 4 for the busiest days
+pub fn heat_level(count: usize, max: usize) -> usize {
+    if count == 0 || max == 0 {
+        0
+    } else {
+        (count * 4).div_ceil(max).clamp(1, 4)
+    }
+}
+
+pub fn word_count(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+// Outside resources per PERCENTAGE_BUCKETS
+pub fn percentage_distribution(outsides: &[Outside]) -> [usize; 6] {
+    let mut buckets = [0; 6];
+    for outside in outsides {
+        let bucket = match outside.percentage {
+            None => 0,
+            Some(p) if p >= 100 => 5,
+            Some(p) => 1 + (p.max(0) / 25) as usize,
+        };
+        buckets[bucket] += 1;
+    }
+    buckets
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TagCompletion {
+    pub tag: String,
+    pub total: usize,
+    pub done: usize,
+}
+
+fn is_complete(outside: &Outside) -> bool {
+    outside.status == Some(Status::Done) || outside.percentage.is_some_and(|p| p >= 100)
+}
+
+// Share of Outside resources finished per hashtag, most used tags first
+pub fn completion_by_tag(outsides: &[Outside]) -> Vec<TagCompletion> {
+    let mut by_tag: HashMap<String, TagCompletion> = HashMap::new();
+    for outside in outsides {
+        for tag in parse_tags(&outside.context) {
+            let entry = by_tag.entry(tag.clone()).or_insert(TagCompletion { tag, total: 0, done: 0 });
+            entry.total += 1;
+            entry.done += usize::from(is_complete(outside));
+        }
+    }
+    let mut tags: Vec<TagCompletion> = by_tag.into_values().collect();
+    tags.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.tag.cmp(&b.tag)));
+    tags
+}
+
+fn urls_in(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | '<' | '>' | '"' | '\'' | '[' | ']'))
+        .filter(|word| word.starts_with("http://") || word.starts_with("https://"))
+}
+
+// Domains of Outside URLs and of links written in any context, most linked first
+pub fn top_domains(outsides: &[Outside], insides: &[Inside], limit: usize) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let urls = outsides
+        .iter()
+        .flat_map(|o| std::iter::once(o.url.as_str()).chain(urls_in(&o.context)))
+        .chain(insides.iter().flat_map(|i| urls_in(&i.context)));
+    for url in urls {
+        let domain = domain_of(url);
+        if !domain.is_empty() {
+            *counts.entry(domain).or_insert(0) += 1;
+        }
+    }
+    let mut domains: Vec<(String, usize)> = counts.into_iter().collect();
+    domains.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    domains.truncate(limit);
+    domains
+}
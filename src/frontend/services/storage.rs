@@ -1,12 +1,23 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use gloo::storage::{LocalStorage, Storage};
 use serde::{Deserialize, Serialize};
-use crate::models::{Outside, Inside};
+use serde::de::DeserializeOwned;
+use crate::models::{Outside, Inside, ProgressEntry, Status, Timestamp};
+use crate::frontend::services::{crypto, file_sync, live, wiki, workspace};
+use crate::frontend::services::markdown::is_web_url;
+use crate::frontend::services::tab_sync::{self, Change, Collection};
 use web_sys::window;
 use wasm_bindgen::{JsValue, JsCast};
 use js_sys;
 
-const OUTSIDE_KEY: &str = "relf_outside_data";
-const INSIDE_KEY: &str = "relf_inside_data";
+const HISTORY_KEY: &str = "relf_history";
+const MAX_REVISIONS: usize = 20;
+
+thread_local! {
+    // Keys already reported as unreadable
+    static UNREADABLE: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct StorageData {
@@ -20,6 +31,26 @@ pub struct ExportData {
     pub inside: Vec<ExportInside>,
 }
 
+// One workspace in an export of every workspace
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WorkspaceExport {
+    pub name: String,
+    #[serde(flatten)]
+    pub data: ExportData,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AllWorkspacesData {
+    pub workspaces: Vec<WorkspaceExport>,
+}
+
+// Whether records are copied to another workspace or moved there
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Transfer {
+    Copy,
+    Move,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OutsideOnlyData {
     pub outside: Vec<ExportOutside>,
@@ -30,18 +61,48 @@ pub struct InsideOnlyData {
     pub inside: Vec<ExportInside>,
 }
 
+// A record as it was before an edit, kept for its history
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Revision {
+    pub uuid: String,
+    // When the edit replaced this version
+    pub replaced_at: String,
+    pub record: serde_json::Value,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ExportOutside {
+    // Kept so reloading an exported or synced file does not break links to the record
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
     pub name: String,
     pub context: String,
     pub url: String,
     pub percentage: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub progress: Vec<ProgressEntry>,
+    // Kept so sorting by date added or updated still works after an import or restore
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ExportInside {
-    pub date: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    pub date: Timestamp,
     pub context: String,
+    // Names of the Outside resources the entry is about
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unreadable_date: Option<String>,
 }
 
 impl Default for StorageData {
@@ -54,220 +115,816 @@ impl Default for StorageData {
                     context: "A systems programming language focused on safety, speed, and concurrency. Rust prevents common bugs like null pointer dereferences and buffer overflows through its ownership system, making it ideal for building reliable software without sacrificing performance.".to_string(),
                     url: "https://www.rust-lang.org/".to_string(),
                     percentage: Some(100),
+                    ..Default::default()
                 },
             ],
             inside: vec![
                 Inside {
                     uuid: uuid::Uuid::new_v4().to_string(),
                     context: "Finally learned how to use cargo! Running 'cargo new my_project' creates such a clean project structure. I love how it automatically sets up the Cargo.toml and src/main.rs. The fact that it initializes a git repo by default is really thoughtful. This feels so much more organized than other languages I've tried.".to_string(),
-                    date: "2025-01-01 00:00:00".to_string(),
+                    date: Timestamp::parse("2025-01-01 00:00:00").unwrap_or_default(),
+                    ..Default::default()
                 },
             ],
         }
     }
 }
 
+// Read a stored value, decrypting it when it was stored encrypted
+pub fn read_value<T: DeserializeOwned>(key: &str) -> Result<T, String> {
+    let raw = LocalStorage::raw()
+        .get_item(key)
+        .ok()
+        .flatten()
+        .ok_or_else(|| "No stored data".to_string())?;
+    let json = if crypto::is_envelope(&raw) { crypto::decrypt(&raw)? } else { raw };
+    serde_json::from_str(&json).map_err(|e| format!("Invalid stored data: {:?}", e))
+}
+
+// Write a value, encrypting it while encryption is enabled
+pub fn write_value<T: Serialize + ?Sized>(key: &str, value: &T) -> Result<(), String> {
+    let json = serde_json::to_string(value)
+        .map_err(|e| format!("Failed to serialize data: {:?}", e))?;
+    let value = if crypto::is_enabled() { crypto::encrypt(&json)? } else { json };
+    LocalStorage::raw()
+        .set_item(key, &value)
+        .map_err(|e| format!("{:?}", e))
+}
+
+fn read_collection<T: DeserializeOwned>(key: &str) -> Result<Vec<T>, String> {
+    read_value(key)
+}
+
+fn write_collection<T: Serialize>(key: &str, items: &[T]) -> Result<(), String> {
+    write_value(key, items)
+}
+
 pub fn get_outsides() -> Vec<Outside> {
-    LocalStorage::get(OUTSIDE_KEY).unwrap_or_else(|_| {
-        let default_data = StorageData::default();
-        let _ = LocalStorage::set(OUTSIDE_KEY, &default_data.outside);
-        default_data.outside
-    })
+    get_outsides_in(&workspace::active_id())
 }
 
 pub fn get_insides() -> Vec<Inside> {
-    LocalStorage::get(INSIDE_KEY).unwrap_or_else(|_| {
-        let default_data = StorageData::default();
-        let _ = LocalStorage::set(INSIDE_KEY, &default_data.inside);
-        default_data.inside
-    })
+    get_insides_in(&workspace::active_id())
+}
+
+// Stored data that cannot be read, e.g. after a failed decryption, is left untouched and
+// reported once per key; sample data only fills a default workspace that has none yet
+fn read_or_sample<T: Serialize + DeserializeOwned>(workspace_id: &str, key: &str, sample: impl FnOnce() -> Vec<T>) -> Vec<T> {
+    let error = match read_collection(key) {
+        Ok(items) => return items,
+        Err(e) => e,
+    };
+    let stored = LocalStorage::raw().get_item(key).ok().flatten().is_some();
+    if stored {
+        let first = UNREADABLE.with(|u| u.borrow_mut().insert(key.to_string()));
+        if first {
+            web_sys::console::log_1(&format!("Failed to read {}: {}", key, error).into());
+            if let Some(window) = window() {
+                let _ = window.alert_with_message(&format!(
+                    "Some stored data could not be read ({}). It was left as it is, but saving records in this workspace will replace it; restore a snapshot from the Data page to recover.",
+                    error
+                ));
+            }
+        }
+        return Vec::new();
+    }
+    if workspace_id != workspace::DEFAULT_ID {
+        return Vec::new();
+    }
+    let items = sample();
+    let _ = write_collection(key, &items);
+    items
+}
+
+// Only the default workspace starts out with sample data
+pub fn get_outsides_in(workspace_id: &str) -> Vec<Outside> {
+    if crypto::is_locked() {
+        return Vec::new();
+    }
+    read_or_sample(workspace_id, &workspace::outside_key(workspace_id), || StorageData::default().outside)
+}
+
+pub fn get_insides_in(workspace_id: &str) -> Vec<Inside> {
+    if crypto::is_locked() {
+        return Vec::new();
+    }
+    read_or_sample(workspace_id, &workspace::inside_key(workspace_id), || StorageData::default().inside)
+}
+
+// Uuids of records that were added, changed or removed between two versions of a collection
+pub fn changed_uuids<T: Serialize>(previous: &[T], current: &[T], uuid: impl Fn(&T) -> &str) -> Vec<String> {
+    let before: HashMap<&str, String> = previous
+        .iter()
+        .map(|item| (uuid(item), serde_json::to_string(item).unwrap_or_default()))
+        .collect();
+    let after: HashMap<&str, String> = current
+        .iter()
+        .map(|item| (uuid(item), serde_json::to_string(item).unwrap_or_default()))
+        .collect();
+
+    let mut changed: Vec<String> = after
+        .iter()
+        .filter(|(id, json)| before.get(*id) != Some(*json))
+        .map(|(id, _)| id.to_string())
+        .collect();
+    changed.extend(before.keys().filter(|id| !after.contains_key(*id)).map(|id| id.to_string()));
+    changed
+}
+
+pub fn save_outsides(outsides: &[Outside]) -> Result<(), String> {
+    save_outsides_in(&workspace::active_id(), outsides)
+}
+
+pub fn save_insides(insides: &[Inside]) -> Result<(), String> {
+    save_insides_in(&workspace::active_id(), insides)
+}
+
+pub fn save_outsides_in(workspace_id: &str, outsides: &[Outside]) -> Result<(), String> {
+    let key = workspace::outside_key(workspace_id);
+    let previous: Vec<Outside> = read_collection(&key).unwrap_or_default();
+    write_collection(&key, outsides)
+        .map_err(|e| format!("Failed to save outside data: {}", e))?;
+    file_sync::notify_change(workspace_id);
+    live::notify_change(workspace_id);
+    tab_sync::broadcast(workspace_id, Change {
+        collection: Collection::Outside,
+        uuids: changed_uuids(&previous, outsides, |o| &o.uuid),
+    });
+    Ok(())
+}
+
+pub fn save_insides_in(workspace_id: &str, insides: &[Inside]) -> Result<(), String> {
+    let key = workspace::inside_key(workspace_id);
+    let previous: Vec<Inside> = read_collection(&key).unwrap_or_default();
+    write_collection(&key, insides)
+        .map_err(|e| format!("Failed to save inside data: {}", e))?;
+    file_sync::notify_change(workspace_id);
+    live::notify_change(workspace_id);
+    tab_sync::broadcast(workspace_id, Change {
+        collection: Collection::Inside,
+        uuids: changed_uuids(&previous, insides, |i| &i.uuid),
+    });
+    Ok(())
+}
+
+// Earlier versions of a record, newest first
+pub fn get_history(uuid: &str) -> Vec<Revision> {
+    let mut revisions: Vec<Revision> = read_collection::<Revision>(HISTORY_KEY)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|r| r.uuid == uuid)
+        .collect();
+    revisions.reverse();
+    revisions
+}
+
+// Keep the version an edit is about to replace, dropping the oldest beyond MAX_REVISIONS
+fn record_revision<T: Serialize>(uuid: &str, previous: &T) -> Result<(), String> {
+    let record = serde_json::to_value(previous)
+        .map_err(|e| format!("Failed to serialize data: {:?}", e))?;
+    let mut revisions: Vec<Revision> = read_collection(HISTORY_KEY).unwrap_or_default();
+    revisions.push(Revision {
+        uuid: uuid.to_string(),
+        replaced_at: timestamp(),
+        record,
+    });
+    let count = revisions.iter().filter(|r| r.uuid == uuid).count();
+    if count > MAX_REVISIONS
+        && let Some(oldest) = revisions.iter().position(|r| r.uuid == uuid)
+    {
+        revisions.remove(oldest);
+    }
+    write_collection(HISTORY_KEY, &revisions)
+}
+
+// Collections of every workspace, read before the encryption key changes
+fn read_all_workspaces() -> Vec<(String, Vec<Outside>, Vec<Inside>)> {
+    workspace::list()
+        .into_iter()
+        .map(|w| {
+            let outsides = get_outsides_in(&w.id);
+            let insides = get_insides_in(&w.id);
+            (w.id, outsides, insides)
+        })
+        .collect()
+}
+
+fn write_all_workspaces(workspaces: Vec<(String, Vec<Outside>, Vec<Inside>)>) -> Result<(), String> {
+    for (id, outsides, insides) in workspaces {
+        save_outsides_in(&id, &outsides)?;
+        save_insides_in(&id, &insides)?;
+    }
+    Ok(())
+}
+
+// What the live link stores about the linked workspace, kept as JSON across an encryption change
+fn read_live_state() -> Vec<(String, serde_json::Value)> {
+    live::stored_keys()
+        .into_iter()
+        .filter_map(|key| read_value(&key).ok().map(|value| (key, value)))
+        .collect()
+}
+
+fn write_live_state(state: Vec<(String, serde_json::Value)>) -> Result<(), String> {
+    state.iter().try_for_each(|(key, value)| write_value(key, value))
 }
 
-pub fn save_outsides(outsides: &Vec<Outside>) -> Result<(), String> {
-    LocalStorage::set(OUTSIDE_KEY, outsides)
-        .map_err(|e| format!("Failed to save outside data: {:?}", e))
+// Turn on encryption and re-save every workspace with the new key
+pub fn enable_encryption(passphrase: &str) -> Result<(), String> {
+    let workspaces = read_all_workspaces();
+    let history: Vec<Revision> = read_collection(HISTORY_KEY).unwrap_or_default();
+    let live_state = read_live_state();
+    crypto::enable(passphrase)?;
+    write_all_workspaces(workspaces)?;
+    write_live_state(live_state)?;
+    write_collection(HISTORY_KEY, &history)
 }
 
-pub fn save_insides(insides: &Vec<Inside>) -> Result<(), String> {
-    LocalStorage::set(INSIDE_KEY, insides)
-        .map_err(|e| format!("Failed to save inside data: {:?}", e))
+// Turn off encryption and re-save every workspace in plaintext
+pub fn disable_encryption() -> Result<(), String> {
+    if crypto::is_locked() {
+        return Err("Unlock the data before disabling encryption".to_string());
+    }
+    let workspaces = read_all_workspaces();
+    let history: Vec<Revision> = read_collection(HISTORY_KEY).unwrap_or_default();
+    let live_state = read_live_state();
+    crypto::disable();
+    write_all_workspaces(workspaces)?;
+    write_live_state(live_state)?;
+    write_collection(HISTORY_KEY, &history)
+}
+
+fn timestamp() -> String {
+    chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+// Keep percentages within 0–100
+fn clamp_percentage(percentage: Option<i32>) -> Option<i32> {
+    percentage.map(|p| p.clamp(0, 100))
 }
 
-pub fn add_outside(outside: Outside) -> Result<(), String> {
-    let mut outsides = get_outsides();
-    outsides.push(outside);
-    save_outsides(&outsides)
+// Append to the progress history when the percentage or status moved
+fn track_progress(history: &mut Vec<ProgressEntry>, percentage: Option<i32>, status: Option<Status>, at: &str) {
+    let unchanged = history
+        .last()
+        .is_some_and(|last| last.percentage == percentage && last.status == status);
+    let empty = history.is_empty() && percentage.is_none() && status.is_none();
+    if !unchanged && !empty {
+        history.push(ProgressEntry {
+            at: at.to_string(),
+            percentage,
+            status,
+        });
+    }
+}
+
+pub fn add_outside(outsides: &mut Vec<Outside>, outside: Outside) {
+    let now = timestamp();
+    let percentage = clamp_percentage(outside.percentage);
+    let mut progress_history = Vec::new();
+    track_progress(&mut progress_history, percentage, outside.status, &now);
+    outsides.push(Outside {
+        percentage,
+        progress_history,
+        created_at: Some(now.clone()),
+        updated_at: Some(now),
+        ..outside
+    });
 }
 
-pub fn update_outside(uuid: &str, updated: Outside) -> Result<(), String> {
-    let mut outsides = get_outsides();
+// Apply edited records, e.g. after a batch edit, pointing links at the renamed ones
+pub fn update_outsides(outsides: &mut [Outside], insides: &mut [Inside], updated: Vec<Outside>) -> Result<(), String> {
+    for outside in updated {
+        replace_outside(outsides, insides, &outside.uuid.clone(), outside)?;
+    }
+    Ok(())
+}
+
+fn replace_outside(outsides: &mut [Outside], insides: &mut [Inside], uuid: &str, updated: Outside) -> Result<(), String> {
     if let Some(index) = outsides.iter().position(|o| o.uuid == uuid) {
-        outsides[index] = updated;
-        save_outsides(&outsides)
+        record_revision(uuid, &outsides[index])?;
+        let old_name = outsides[index].name.clone();
+        let new_name = updated.name.clone();
+        let now = timestamp();
+        let percentage = clamp_percentage(updated.percentage);
+        let mut progress_history = outsides[index].progress_history.clone();
+        // Records from before the history existed start from their previous progress
+        if progress_history.is_empty() {
+            let since = outsides[index].updated_at.clone().unwrap_or_default();
+            track_progress(&mut progress_history, outsides[index].percentage, outsides[index].status, &since);
+        }
+        track_progress(&mut progress_history, percentage, updated.status, &now);
+        // Preserve when the record was added
+        let created_at = outsides[index].created_at.clone();
+        outsides[index] = Outside {
+            percentage,
+            progress_history,
+            created_at,
+            updated_at: Some(now),
+            ..updated
+        };
+        if old_name.trim() != new_name.trim() {
+            rename_wiki_links(outsides, insides, &old_name, &new_name);
+        }
+        Ok(())
     } else {
         Err("Outside item not found".to_string())
     }
 }
 
-pub fn delete_outside(uuid: &str) -> Result<(), String> {
-    let mut outsides = get_outsides();
-    outsides.retain(|o| o.uuid != uuid);
-    save_outsides(&outsides)
+// Point `[[old name]]` links in every context at the renamed record
+fn rename_wiki_links(outsides: &mut [Outside], insides: &mut [Inside], old_name: &str, new_name: &str) {
+    for outside in outsides.iter_mut() {
+        outside.context = wiki::rename_links(&outside.context, old_name, new_name);
+    }
+    for inside in insides.iter_mut() {
+        inside.context = wiki::rename_links(&inside.context, old_name, new_name);
+    }
 }
 
-pub fn add_inside(inside: Inside) -> Result<(), String> {
-    let mut insides = get_insides();
-    insides.push(inside);
-    save_insides(&insides)
+// Remove records, and the relations entries have to them
+pub fn delete_outsides(outsides: &mut Vec<Outside>, insides: &mut [Inside], uuids: &[String]) {
+    outsides.retain(|o| !uuids.contains(&o.uuid));
+    for inside in insides.iter_mut() {
+        inside.outside_uuids.retain(|u| !uuids.contains(u));
+    }
 }
 
-pub fn update_inside(uuid: &str, updated: Inside) -> Result<(), String> {
-    let mut insides = get_insides();
-    if let Some(index) = insides.iter().position(|i| i.uuid == uuid) {
-        // Preserve the original date when updating
-        let original_date = insides[index].date.clone();
+pub fn add_inside(insides: &mut Vec<Inside>, inside: Inside) {
+    insides.push(Inside {
+        updated_at: Some(timestamp()),
+        ..inside
+    });
+}
+
+// Apply edited entries, e.g. after a batch edit
+pub fn update_insides(insides: &mut [Inside], updated: Vec<Inside>) -> Result<(), String> {
+    for inside in updated {
+        let Some(index) = insides.iter().position(|i| i.uuid == inside.uuid) else {
+            return Err("Inside item not found".to_string());
+        };
+        record_revision(&inside.uuid, &insides[index])?;
         insides[index] = Inside {
-            date: original_date,
-            ..updated
+            updated_at: Some(timestamp()),
+            ..inside
         };
-        save_insides(&insides)
-    } else {
-        Err("Inside item not found".to_string())
     }
+    Ok(())
 }
 
-pub fn delete_inside(uuid: &str) -> Result<(), String> {
-    let mut insides = get_insides();
-    insides.retain(|i| i.uuid != uuid);
-    save_insides(&insides)
+pub fn delete_insides(insides: &mut Vec<Inside>, uuids: &[String]) {
+    insides.retain(|i| !uuids.contains(&i.uuid));
 }
 
-pub fn export_to_json() -> String {
-    let mut outsides = get_outsides();
-    let mut insides = get_insides();
-    
-    // Sort outside by percentage (highest first, nulls last), then by name
+// Save the collections of a workspace that differ from `previous`. Entries go first and are put
+// back when the records cannot be saved, so no link points at a name that was never saved
+pub fn save_changes(workspace_id: &str, previous: (&[Outside], &[Inside]), outsides: &[Outside], insides: &[Inside]) -> Result<(), String> {
+    let (previous_outsides, previous_insides) = previous;
+    if previous_insides != insides {
+        save_insides_in(workspace_id, insides)?;
+    }
+    if previous_outsides != outsides {
+        save_outsides_in(workspace_id, outsides).inspect_err(|_| {
+            if previous_insides != insides
+                && let Err(e) = save_insides_in(workspace_id, previous_insides)
+            {
+                web_sys::console::log_1(&format!("Failed to restore entries: {}", e).into());
+            }
+        })?;
+    }
+    Ok(())
+}
+
+// Outside by percentage (highest first, nulls last), then by name
+pub fn sort_outsides(outsides: &mut [Outside]) {
     outsides.sort_by(|a, b| {
         match (a.percentage, b.percentage) {
-            (Some(ap), Some(bp)) => {
-                let cmp = bp.cmp(&ap); // Descending order for percentage
-                if cmp == std::cmp::Ordering::Equal {
-                    a.name.cmp(&b.name) // Ascending order for name
-                } else {
-                    cmp
-                }
-            }
+            (Some(ap), Some(bp)) => bp.cmp(&ap).then_with(|| a.name.cmp(&b.name)),
             (Some(_), None) => std::cmp::Ordering::Less, // Non-null comes first
             (None, Some(_)) => std::cmp::Ordering::Greater, // Null comes last
-            (None, None) => a.name.cmp(&b.name), // Ascending order for name
+            (None, None) => a.name.cmp(&b.name),
         }
     });
+}
+
+// Inside by date (newest first)
+pub fn sort_insides(insides: &mut [Inside]) {
+    insides.sort_by_key(|i| std::cmp::Reverse(i.date));
+}
+
+fn uuid_or_new(uuid: Option<String>) -> String {
+    uuid.filter(|u| !u.trim().is_empty())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
+
+// Records that repeat an earlier record's UUID, such as entries copied by hand in a file, get a new one
+fn dedupe_uuids<T>(records: &mut [T], uuid_of: fn(&mut T) -> &mut String) {
+    let mut seen = HashSet::new();
+    for record in records.iter_mut() {
+        let uuid = uuid_of(record);
+        if !seen.insert(uuid.clone()) {
+            *uuid = uuid::Uuid::new_v4().to_string();
+            seen.insert(uuid.clone());
+        }
+    }
+}
+
+// Appended records are copies, so they never take the UUIDs in the file
+fn without_uuids(data: ExportData) -> ExportData {
+    ExportData {
+        outside: data.outside.into_iter().map(|o| ExportOutside { uuid: None, ..o }).collect(),
+        inside: data.inside.into_iter().map(|i| ExportInside { uuid: None, ..i }).collect(),
+    }
+}
+
+fn from_export_outside(o: ExportOutside) -> Outside {
+    Outside {
+        uuid: uuid_or_new(o.uuid),
+        name: o.name,
+        context: o.context,
+        url: o.url,
+        percentage: clamp_percentage(o.percentage),
+        status: o.status,
+        progress_history: o.progress,
+        created_at: o.created_at,
+        updated_at: o.updated_at,
+        ..Default::default()
+    }
+}
+
+// Relations are exported by Outside name, so they also resolve in a workspace with other UUIDs
+fn to_export_inside(i: Inside, outsides: &[Outside]) -> ExportInside {
+    ExportInside {
+        uuid: Some(i.uuid),
+        date: i.date,
+        context: i.context,
+        related: i
+            .outside_uuids
+            .iter()
+            .filter_map(|uuid| outsides.iter().find(|o| &o.uuid == uuid))
+            .map(|o| o.name.clone())
+            .collect(),
+        updated_at: i.updated_at,
+        unreadable_date: i.unreadable_date,
+    }
+}
+
+fn from_export_inside(i: ExportInside, outsides: &[Outside]) -> Inside {
+    Inside {
+        uuid: uuid_or_new(i.uuid),
+        date: i.date,
+        context: i.context,
+        outside_uuids: i
+            .related
+            .iter()
+            .filter_map(|name| outsides.iter().find(|o| o.name.trim().eq_ignore_ascii_case(name.trim())))
+            .map(|o| o.uuid.clone())
+            .collect(),
+        updated_at: i.updated_at,
+        unreadable_date: i.unreadable_date,
+        ..Default::default()
+    }
+}
+
+pub fn export_to_json() -> String {
+    export_workspace(&workspace::active_id())
+}
+
+pub fn export_workspace(workspace_id: &str) -> String {
+    serde_json::to_string_pretty(&workspace_data(workspace_id)).unwrap_or_else(|_| "{}".to_string())
+}
+
+fn workspace_data(workspace_id: &str) -> ExportData {
+    let outsides = get_outsides_in(workspace_id);
+    let insides = get_insides_in(workspace_id);
+    export_data(outsides.clone(), insides, &outsides)
+}
+
+// Just the chosen records; relations still name any Outside resource
+pub fn selection_data(outside_uuids: &[String], inside_uuids: &[String]) -> ExportData {
+    let all_outsides = get_outsides();
+    let outsides = all_outsides.iter().filter(|o| outside_uuids.contains(&o.uuid)).cloned().collect();
+    let insides = get_insides().into_iter().filter(|i| inside_uuids.contains(&i.uuid)).collect();
+    // Links stay shorter without UUIDs, which an import would not keep anyway
+    without_uuids(export_data(outsides, insides, &all_outsides))
+}
+
+fn export_selection(outside_uuids: &[String], inside_uuids: &[String]) -> String {
+    serde_json::to_string_pretty(&selection_data(outside_uuids, inside_uuids)).unwrap_or_else(|_| "{}".to_string())
+}
+
+fn export_data(mut outsides: Vec<Outside>, mut insides: Vec<Inside>, all_outsides: &[Outside]) -> ExportData {
+    sort_outsides(&mut outsides);
+    sort_insides(&mut insides);
     
-    // Sort inside by date (newest first)
-    insides.sort_by(|a, b| b.date.cmp(&a.date));
+    let export_insides: Vec<ExportInside> = insides.into_iter().map(|i| to_export_inside(i, all_outsides)).collect();
     
     let export_outsides: Vec<ExportOutside> = outsides.into_iter().map(|o| ExportOutside {
+        uuid: Some(o.uuid),
         name: o.name,
         context: o.context,
         url: o.url,
         percentage: o.percentage,
+        status: o.status,
+        progress: o.progress_history,
+        created_at: o.created_at,
+        updated_at: o.updated_at,
     }).collect();
     
-    let export_insides: Vec<ExportInside> = insides.into_iter().map(|i| ExportInside {
-        date: i.date,
-        context: i.context,
-    }).collect();
-    
-    let data = ExportData {
+    ExportData {
         outside: export_outsides,
         inside: export_insides,
+    }
+}
+
+// Every workspace by name, for full backups and snapshots
+pub fn export_everything() -> String {
+    let data = AllWorkspacesData {
+        workspaces: workspace::list()
+            .into_iter()
+            .map(|w| WorkspaceExport {
+                data: workspace_data(&w.id),
+                name: w.name,
+            })
+            .collect(),
     };
-    
     serde_json::to_string_pretty(&data).unwrap_or_else(|_| "{}".to_string())
 }
 
+// Record counts across every workspace
+pub fn count_everything() -> (usize, usize) {
+    workspace::list().iter().fold((0, 0), |(outside, inside), w| {
+        (outside + get_outsides_in(&w.id).len(), inside + get_insides_in(&w.id).len())
+    })
+}
+
+fn for_file(json: String) -> Result<String, String> {
+    if crypto::is_enabled() { crypto::encrypt(&json) } else { Ok(json) }
+}
+
+// Export for files leaving the browser, encrypted while encryption is enabled
+pub fn export_for_file() -> Result<String, String> {
+    for_file(export_to_json())
+}
+
+pub fn export_workspace_for_file(workspace_id: &str) -> Result<String, String> {
+    for_file(export_workspace(workspace_id))
+}
+
+pub fn export_everything_for_file() -> Result<String, String> {
+    for_file(export_everything())
+}
+
 pub fn import_from_json(json_str: &str) -> Result<(), String> {
-    let data: ExportData = serde_json::from_str(json_str)
-        .map_err(|e| format!("Invalid JSON format: {:?}", e))?;
-    
-    // Convert ExportData to StorageData with new UUIDs
-    let outsides: Vec<Outside> = data.outside.into_iter().map(|o| Outside {
-        uuid: uuid::Uuid::new_v4().to_string(),
-        name: o.name,
-        context: o.context,
-        url: o.url,
-        percentage: o.percentage,
-    }).collect();
+    import_workspace_from_json(&workspace::active_id(), json_str)
+}
+
+pub fn import_workspace_from_json(workspace_id: &str, json_str: &str) -> Result<(), String> {
+    let json_str = &crypto::decrypt_import(json_str)?;
+    let data: ExportData = parse_import(json_str)?;
+    replace_workspace(workspace_id, data)
+}
+
+// Read an import. An entry whose date cannot be read fails it, named by how it starts,
+// rather than being given a made-up date
+fn parse_import<T: DeserializeOwned>(json_str: &str) -> Result<T, String> {
+    serde_json::from_str(json_str).map_err(|e| {
+        serde_json::from_str(json_str)
+            .ok()
+            .and_then(|value| unreadable_date(&value))
+            .unwrap_or_else(|| format!("Invalid JSON format: {:?}", e))
+    })
+}
+
+// The first entry, in one workspace or a backup of all of them, whose date cannot be read
+fn unreadable_date(value: &serde_json::Value) -> Option<String> {
+    let data: Vec<&serde_json::Value> = match value.get("workspaces").and_then(|w| w.as_array()) {
+        Some(workspaces) => workspaces.iter().filter_map(|w| w.get("data")).collect(),
+        None => vec![value],
+    };
+    data.iter()
+        .filter_map(|d| d.get("inside")?.as_array())
+        .flatten()
+        .find_map(|entry| {
+            let date = entry.get("date").unwrap_or(&serde_json::Value::Null);
+            if date.as_str().and_then(Timestamp::parse).is_some() {
+                return None;
+            }
+            let context = entry.get("context").and_then(|c| c.as_str()).unwrap_or_default();
+            let start: String = context.trim().chars().take(40).collect();
+            Some(format!("The entry \"{}\" has a date that could not be read: {}", start, date))
+        })
+}
+
+// Imported and shared records come from elsewhere; only web links are taken from them
+fn check_urls(outsides: &[ExportOutside]) -> Result<(), String> {
+    match outsides.iter().find(|o| !is_web_url(&o.url)) {
+        Some(o) => Err(format!("\"{}\" links to {}, which is not a web address", o.name, o.url.trim())),
+        None => Ok(()),
+    }
+}
+
+// Replace a workspace's records with imported ones, keeping the UUIDs the file has
+fn replace_workspace(workspace_id: &str, data: ExportData) -> Result<(), String> {
+    check_urls(&data.outside)?;
+    let mut outsides: Vec<Outside> = data.outside.into_iter().map(from_export_outside).collect();
+    dedupe_uuids(&mut outsides, |o| &mut o.uuid);
     
-    let insides: Vec<Inside> = data.inside.into_iter().map(|i| Inside {
-        uuid: uuid::Uuid::new_v4().to_string(),
-        date: i.date,
-        context: i.context,
-    }).collect();
+    let mut insides: Vec<Inside> = data.inside.into_iter().map(|i| from_export_inside(i, &outsides)).collect();
+    dedupe_uuids(&mut insides, |i| &mut i.uuid);
     
-    save_outsides(&outsides)?;
-    save_insides(&insides)?;
+    save_outsides_in(workspace_id, &outsides)?;
+    save_insides_in(workspace_id, &insides)?;
     
     Ok(())
 }
 
+// Restore workspaces by name, creating missing ones; workspaces absent from the file are kept.
+// A single-workspace export replaces the current workspace.
+pub fn import_everything_from_json(json_str: &str) -> Result<(), String> {
+    let json_str = &crypto::decrypt_import(json_str)?;
+    let Ok(data) = serde_json::from_str::<AllWorkspacesData>(json_str) else {
+        return import_from_json(json_str);
+    };
+    // Checked up front so a bad record does not leave a half-restored backup
+    for export in &data.workspaces {
+        check_urls(&export.data.outside)?;
+    }
+    for export in data.workspaces {
+        let id = match workspace::find_by_name(&export.name) {
+            Some(existing) => existing.id,
+            None => workspace::create(&export.name)?.id,
+        };
+        replace_workspace(&id, export.data)?;
+    }
+    Ok(())
+}
+
+// Copy or move the chosen records of a workspace into another one.
+// Moved records keep their UUIDs; relations follow resources that came along
+// or match one of the same name in the target. The target is saved here; moved records
+// are taken out of `outsides` and `insides` for the caller to save.
+pub fn transfer_records(
+    source: &str,
+    target: &str,
+    outsides: &mut Vec<Outside>,
+    insides: &mut Vec<Inside>,
+    outside_uuids: &[String],
+    inside_uuids: &[String],
+    transfer: Transfer,
+) -> Result<(), String> {
+    if workspace::find(target).is_none() {
+        return Err("Workspace not found".to_string());
+    }
+    if target == source {
+        return Err("The records are already in this workspace".to_string());
+    }
+    let mut target_outsides = get_outsides_in(target);
+    let mut target_insides = get_insides_in(target);
+
+    let new_uuid = |uuid: &str| match transfer {
+        Transfer::Copy => uuid::Uuid::new_v4().to_string(),
+        Transfer::Move => uuid.to_string(),
+    };
+    let mut uuids: HashMap<String, String> = HashMap::new();
+    for outside in outsides.iter().filter(|o| outside_uuids.contains(&o.uuid)) {
+        let uuid = new_uuid(&outside.uuid);
+        uuids.insert(outside.uuid.clone(), uuid.clone());
+        target_outsides.push(Outside { uuid, ..outside.clone() });
+    }
+    for inside in insides.iter().filter(|i| inside_uuids.contains(&i.uuid)) {
+        let related = inside
+            .outside_uuids
+            .iter()
+            .filter_map(|related| {
+                uuids.get(related).cloned().or_else(|| {
+                    let name = &outsides.iter().find(|o| &o.uuid == related)?.name;
+                    target_outsides
+                        .iter()
+                        .find(|o| o.name.trim().eq_ignore_ascii_case(name.trim()))
+                        .map(|o| o.uuid.clone())
+                })
+            })
+            .collect();
+        target_insides.push(Inside {
+            uuid: new_uuid(&inside.uuid),
+            outside_uuids: related,
+            ..inside.clone()
+        });
+    }
+    save_outsides_in(target, &target_outsides)?;
+    save_insides_in(target, &target_insides)?;
+
+    if transfer == Transfer::Move {
+        delete_insides(insides, inside_uuids);
+        delete_outsides(outsides, insides, outside_uuids);
+    }
+    Ok(())
+}
 
 pub fn download_json() {
-    if let Some(window) = window() {
-        let json_content = export_to_json();
+    match export_for_file() {
+        Ok(content) => download_file("relf_data.json", &content),
+        Err(e) => web_sys::console::log_1(&format!("Export failed: {}", e).into()),
+    }
+}
+
+// Download one workspace, named after it
+pub fn download_workspace(workspace_id: &str) {
+    let name = workspace::find(workspace_id).map(|w| w.name).unwrap_or_default();
+    let stem: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    match export_workspace_for_file(workspace_id) {
+        Ok(content) => download_file(&format!("relf_{}.json", stem), &content),
+        Err(e) => web_sys::console::log_1(&format!("Export failed: {}", e).into()),
+    }
+}
+
+pub fn download_everything() {
+    match export_everything_for_file() {
+        Ok(content) => download_file("relf_all_workspaces.json", &content),
+        Err(e) => web_sys::console::log_1(&format!("Export failed: {}", e).into()),
+    }
+}
+
+pub fn download_selection(outside_uuids: &[String], inside_uuids: &[String]) {
+    match for_file(export_selection(outside_uuids, inside_uuids)) {
+        Ok(content) => download_file("relf_selection.json", &content),
+        Err(e) => web_sys::console::log_1(&format!("Export failed: {}", e).into()),
+    }
+}
+
+pub fn download_file(filename: &str, json_content: &str) {
+    let Some(document) = window().and_then(|w| w.document()) else {
+        return;
+    };
+    let blob_parts = js_sys::Array::new();
+    blob_parts.push(&JsValue::from_str(json_content));
+    
+    let opts = web_sys::BlobPropertyBag::new();
+    opts.set_type("application/json");
+    
+    let Ok(blob) = web_sys::Blob::new_with_str_sequence_and_options(&blob_parts, &opts) else {
+        return;
+    };
+    let Ok(url) = web_sys::Url::create_object_url_with_blob(&blob) else {
+        return;
+    };
+    if let Ok(a) = document.create_element("a") {
+        let _ = a.set_attribute("href", &url);
+        let _ = a.set_attribute("download", filename);
+        let _ = a.set_attribute("style", "display: none");
         
-        if let Some(document) = window.document() {
-            let blob_parts = js_sys::Array::new();
-            blob_parts.push(&JsValue::from_str(&json_content));
-            
-            let opts = web_sys::BlobPropertyBag::new();
-            opts.set_type("application/json");
+        if let Some(body) = document.body() {
+            let _ = body.append_child(&a);
             
-            if let Ok(blob) = web_sys::Blob::new_with_str_sequence_and_options(&blob_parts, &opts) {
-                if let Ok(url) = web_sys::Url::create_object_url_with_blob(&blob) {
-                    if let Ok(a) = document.create_element("a") {
-                        let _ = a.set_attribute("href", &url);
-                        let _ = a.set_attribute("download", "relf_data.json");
-                        let _ = a.set_attribute("style", "display: none");
-                        
-                        if let Some(body) = document.body() {
-                            let _ = body.append_child(&a);
-                            
-                            if let Some(html_element) = a.dyn_ref::<web_sys::HtmlElement>() {
-                                html_element.click();
-                            }
-                            
-                            let _ = body.remove_child(&a);
-                        }
-                        
-                        let _ = web_sys::Url::revoke_object_url(&url);
-                        web_sys::console::log_1(&"File download initiated!".into());
-                    }
-                }
+            if let Some(html_element) = a.dyn_ref::<web_sys::HtmlElement>() {
+                html_element.click();
             }
+            
+            let _ = body.remove_child(&a);
         }
+        
+        let _ = web_sys::Url::revoke_object_url(&url);
+        web_sys::console::log_1(&"File download initiated!".into());
     }
 }
 
+// Read a picked file as text and hand it to `on_load`
+pub fn read_text_file(file: &web_sys::File, on_load: impl FnOnce(String) + 'static) {
+    let Ok(reader) = web_sys::FileReader::new() else {
+        return;
+    };
+    let reader_clone = reader.clone();
+    let closure = wasm_bindgen::closure::Closure::once(move || {
+        if let Some(text) = reader_clone.result().ok().and_then(|r| r.as_string()) {
+            on_load(text);
+        }
+    });
+    reader.set_onloadend(Some(closure.as_ref().unchecked_ref()));
+    closure.forget();
+    let _ = reader.read_as_text(file);
+}
+
 pub fn reset_to_defaults() -> Result<(), String> {
     let default_data = StorageData::default();
     save_outsides(&default_data.outside)?;
     save_insides(&default_data.inside)?;
+    LocalStorage::delete(HISTORY_KEY);
     Ok(())
 }
 
 pub fn import_outside_from_json(json_str: &str) -> Result<(), String> {
-    let data: OutsideOnlyData = serde_json::from_str(json_str)
-        .map_err(|e| format!("Invalid JSON format: {:?}", e))?;
+    let json_str = &crypto::decrypt_import(json_str)?;
+    let data: OutsideOnlyData = parse_import(json_str)?;
     
-    // Convert only outside data with new UUIDs
-    let outsides: Vec<Outside> = data.outside.into_iter().map(|o| Outside {
-        uuid: uuid::Uuid::new_v4().to_string(),
-        name: o.name,
-        context: o.context,
-        url: o.url,
-        percentage: o.percentage,
-    }).collect();
+    check_urls(&data.outside)?;
+    // Convert only outside data, keeping the UUIDs the file has
+    let mut outsides: Vec<Outside> = data.outside.into_iter().map(from_export_outside).collect();
+    dedupe_uuids(&mut outsides, |o| &mut o.uuid);
     
     save_outsides(&outsides)?;
     
@@ -275,15 +932,13 @@ pub fn import_outside_from_json(json_str: &str) -> Result<(), String> {
 }
 
 pub fn import_inside_from_json(json_str: &str) -> Result<(), String> {
-    let data: InsideOnlyData = serde_json::from_str(json_str)
-        .map_err(|e| format!("Invalid JSON format: {:?}", e))?;
+    let json_str = &crypto::decrypt_import(json_str)?;
+    let data: InsideOnlyData = parse_import(json_str)?;
     
-    // Convert only inside data with new UUIDs
-    let insides: Vec<Inside> = data.inside.into_iter().map(|i| Inside {
-        uuid: uuid::Uuid::new_v4().to_string(),
-        date: i.date,
-        context: i.context,
-    }).collect();
+    // Convert only inside data, keeping the UUIDs the file has
+    let outsides = get_outsides();
+    let mut insides: Vec<Inside> = data.inside.into_iter().map(|i| from_export_inside(i, &outsides)).collect();
+    dedupe_uuids(&mut insides, |i| &mut i.uuid);
     
     save_insides(&insides)?;
     
@@ -291,29 +946,25 @@ pub fn import_inside_from_json(json_str: &str) -> Result<(), String> {
 }
 
 pub fn append_from_json(json_str: &str) -> Result<(), String> {
-    let data: ExportData = serde_json::from_str(json_str)
-        .map_err(|e| format!("Invalid JSON format: {:?}", e))?;
-    
+    let json_str = &crypto::decrypt_import(json_str)?;
+    let data: ExportData = parse_import(json_str)?;
+    append_data(data)
+}
+
+// Add exported records to the current workspace under new UUIDs
+pub fn append_data(data: ExportData) -> Result<(), String> {
+    check_urls(&data.outside)?;
+    let data = without_uuids(data);
     // Get existing data
     let mut existing_outsides = get_outsides();
     let mut existing_insides = get_insides();
     
     // Convert and append new data with new UUIDs
-    let new_outsides: Vec<Outside> = data.outside.into_iter().map(|o| Outside {
-        uuid: uuid::Uuid::new_v4().to_string(),
-        name: o.name,
-        context: o.context,
-        url: o.url,
-        percentage: o.percentage,
-    }).collect();
-    
-    let new_insides: Vec<Inside> = data.inside.into_iter().map(|i| Inside {
-        uuid: uuid::Uuid::new_v4().to_string(),
-        date: i.date,
-        context: i.context,
-    }).collect();
-    
+    let new_outsides: Vec<Outside> = data.outside.into_iter().map(from_export_outside).collect();
     existing_outsides.extend(new_outsides);
+    
+    // Relations may point at existing or newly appended resources
+    let new_insides: Vec<Inside> = data.inside.into_iter().map(|i| from_export_inside(i, &existing_outsides)).collect();
     existing_insides.extend(new_insides);
     
     save_outsides(&existing_outsides)?;
@@ -323,20 +974,19 @@ pub fn append_from_json(json_str: &str) -> Result<(), String> {
 }
 
 pub fn append_outside_from_json(json_str: &str) -> Result<(), String> {
-    let data: OutsideOnlyData = serde_json::from_str(json_str)
-        .map_err(|e| format!("Invalid JSON format: {:?}", e))?;
+    let json_str = &crypto::decrypt_import(json_str)?;
+    let data: OutsideOnlyData = parse_import(json_str)?;
+    check_urls(&data.outside)?;
     
     // Get existing data
     let mut existing_outsides = get_outsides();
     
     // Convert and append new data with new UUIDs
-    let new_outsides: Vec<Outside> = data.outside.into_iter().map(|o| Outside {
-        uuid: uuid::Uuid::new_v4().to_string(),
-        name: o.name,
-        context: o.context,
-        url: o.url,
-        percentage: o.percentage,
-    }).collect();
+    let new_outsides: Vec<Outside> = data
+        .outside
+        .into_iter()
+        .map(|o| from_export_outside(ExportOutside { uuid: None, ..o }))
+        .collect();
     
     existing_outsides.extend(new_outsides);
     save_outsides(&existing_outsides)?;
@@ -345,18 +995,19 @@ pub fn append_outside_from_json(json_str: &str) -> Result<(), String> {
 }
 
 pub fn append_inside_from_json(json_str: &str) -> Result<(), String> {
-    let data: InsideOnlyData = serde_json::from_str(json_str)
-        .map_err(|e| format!("Invalid JSON format: {:?}", e))?;
+    let json_str = &crypto::decrypt_import(json_str)?;
+    let data: InsideOnlyData = parse_import(json_str)?;
     
     // Get existing data
     let mut existing_insides = get_insides();
+    let outsides = get_outsides();
     
     // Convert and append new data with new UUIDs
-    let new_insides: Vec<Inside> = data.inside.into_iter().map(|i| Inside {
-        uuid: uuid::Uuid::new_v4().to_string(),
-        date: i.date,
-        context: i.context,
-    }).collect();
+    let new_insides: Vec<Inside> = data
+        .inside
+        .into_iter()
+        .map(|i| from_export_inside(ExportInside { uuid: None, ..i }, &outsides))
+        .collect();
     
     existing_insides.extend(new_insides);
     save_insides(&existing_insides)?;
@@ -364,3 +1015,34 @@ pub fn append_inside_from_json(json_str: &str) -> Result<(), String> {
     Ok(())
 }
 
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    #[test]
+    fn unreadable_import_date_names_the_entry() {
+        let workspace = json!({"outside": [], "inside": [
+            {"date": "2025-01-01", "context": "Fine"},
+            {"date": "yesterday-ish", "context": "  Walked to the lake and back  "},
+        ]});
+        let error = parse_import::<ExportData>(&workspace.to_string()).unwrap_err();
+        assert_eq!(error, "The entry \"Walked to the lake and back\" has a date that could not be read: \"yesterday-ish\"");
+
+        let backup = json!({"workspaces": [{"name": "Home", "data": workspace}]});
+        assert!(parse_import::<ExportData>(&backup.to_string()).unwrap_err().contains("Walked to the lake"));
+    }
+
+    #[test]
+    fn unreadable_stored_date_is_kept() {
+        let stored = json!({"uuid": "a", "date": "sometime", "context": "", "updated_at": "2025-03-04 05:06:07"});
+        let inside: Inside = serde_json::from_value(stored).unwrap();
+        assert_eq!(inside.unreadable_date.as_deref(), Some("sometime"));
+        assert_eq!(inside.date, Timestamp::parse("2025-03-04 05:06:07").unwrap());
+        assert_eq!(inside.date_label(), "sometime");
+
+        let saved: Inside = serde_json::from_str(&serde_json::to_string(&inside).unwrap()).unwrap();
+        assert_eq!(saved, inside);
+    }
+}
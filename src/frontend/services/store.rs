@@ -0,0 +1,262 @@
+use std::cell::RefCell;
+use std::ops::Deref;
+use std::rc::Rc;
+use yew::prelude::*;
+use crate::models::{Outside, Inside};
+use crate::frontend::services::{file_sync, live, storage, workspace};
+use crate::frontend::services::storage::Transfer;
+use crate::frontend::services::tab_sync::{self, Change};
+
+// The whole document, shared with every page through context
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Store {
+    // Id of the workspace the collections belong to
+    pub workspace: String,
+    pub outside: Vec<Outside>,
+    pub inside: Vec<Inside>,
+    // Last save made in another tab or by a teammate, used to flag concurrent edits
+    pub remote_change: Option<Change>,
+    // Bumped on every action so subscribers can react to any change
+    pub revision: u64,
+}
+
+// What pages can do to the document, through `StoreContext::run`
+pub enum StoreAction {
+    // Re-read storage after it was written outside the store (imports, linked file)
+    Reload,
+    // Re-read storage after another tab or a teammate saved
+    Remote(Change),
+    AddOutside(Outside),
+    UpdateOutside(Outside),
+    DeleteOutside(String),
+    AddInside(Inside),
+    UpdateInside(Inside),
+    DeleteInside(String),
+    // Batch edits of selected records
+    UpdateOutsides(Vec<Outside>),
+    DeleteOutsides(Vec<String>),
+    UpdateInsides(Vec<Inside>),
+    DeleteInsides(Vec<String>),
+    // Show another workspace in this tab
+    SwitchWorkspace(String),
+    // Copy or move selected records into another workspace
+    TransferRecords {
+        workspace: String,
+        outsides: Vec<String>,
+        insides: Vec<String>,
+        transfer: Transfer,
+    },
+}
+
+// An action for the reducer, with where it reports whether the action could be saved
+pub struct StoreUpdate {
+    action: StoreAction,
+    outcome: Rc<RefCell<Result<(), String>>>,
+}
+
+// The store as pages see it; changes go through `run`
+#[derive(Clone, PartialEq)]
+pub struct StoreContext(UseReducerHandle<Store>);
+
+impl Deref for StoreContext {
+    type Target = Store;
+
+    fn deref(&self) -> &Store {
+        &self.0
+    }
+}
+
+impl StoreContext {
+    // Apply an action to the document and save it; on failure the document is left as it was
+    pub fn run(&self, action: StoreAction) -> Result<(), String> {
+        let outcome = Rc::new(RefCell::new(Ok(())));
+        // The reducer runs before `dispatch` returns
+        self.0.dispatch(StoreUpdate {
+            action,
+            outcome: outcome.clone(),
+        });
+        outcome.replace(Ok(()))
+    }
+
+    // Show storage as it is after it was written outside the store
+    pub fn reload(&self) {
+        let _ = self.run(StoreAction::Reload);
+    }
+}
+
+impl Store {
+    pub fn load() -> Self {
+        let mut outside = storage::get_outsides();
+        let mut inside = storage::get_insides();
+        storage::sort_outsides(&mut outside);
+        storage::sort_insides(&mut inside);
+        Self {
+            workspace: workspace::active_id(),
+            outside,
+            inside,
+            remote_change: None,
+            revision: 0,
+        }
+    }
+
+    pub fn find_outside(&self, uuid: &str) -> Option<&Outside> {
+        self.outside.iter().find(|o| o.uuid == uuid)
+    }
+
+    pub fn find_inside(&self, uuid: &str) -> Option<&Inside> {
+        self.inside.iter().find(|i| i.uuid == uuid)
+    }
+
+    // The document after an action, saved, with a message for the console
+    fn apply(&self, action: StoreAction) -> Result<(Store, &'static str), String> {
+        let mut next = Store {
+            remote_change: None,
+            ..self.clone()
+        };
+        let message = match action {
+            StoreAction::Reload => return Ok((Store::load(), "Data reloaded")),
+            StoreAction::Remote(change) => {
+                let store = Store {
+                    remote_change: Some(change),
+                    ..Store::load()
+                };
+                return Ok((store, "Data changed elsewhere"));
+            }
+            StoreAction::SwitchWorkspace(id) => {
+                workspace::set_active(&id)?;
+                return Ok((Store::load(), "Workspace switched"));
+            }
+            StoreAction::AddOutside(outside) => {
+                storage::add_outside(&mut next.outside, outside);
+                "Outside saved successfully!"
+            }
+            StoreAction::UpdateOutside(outside) => {
+                storage::update_outsides(&mut next.outside, &mut next.inside, vec![outside])?;
+                "Outside saved successfully!"
+            }
+            StoreAction::DeleteOutside(uuid) => {
+                storage::delete_outsides(&mut next.outside, &mut next.inside, &[uuid]);
+                "Outside deleted successfully!"
+            }
+            StoreAction::AddInside(inside) => {
+                storage::add_inside(&mut next.inside, inside);
+                "Inside saved successfully!"
+            }
+            StoreAction::UpdateInside(inside) => {
+                storage::update_insides(&mut next.inside, vec![inside])?;
+                "Inside saved successfully!"
+            }
+            StoreAction::DeleteInside(uuid) => {
+                storage::delete_insides(&mut next.inside, &[uuid]);
+                "Inside deleted successfully!"
+            }
+            StoreAction::UpdateOutsides(outsides) => {
+                storage::update_outsides(&mut next.outside, &mut next.inside, outsides)?;
+                "Outsides saved successfully!"
+            }
+            StoreAction::DeleteOutsides(uuids) => {
+                storage::delete_outsides(&mut next.outside, &mut next.inside, &uuids);
+                "Outsides deleted successfully!"
+            }
+            StoreAction::UpdateInsides(insides) => {
+                storage::update_insides(&mut next.inside, insides)?;
+                "Insides saved successfully!"
+            }
+            StoreAction::DeleteInsides(uuids) => {
+                storage::delete_insides(&mut next.inside, &uuids);
+                "Insides deleted successfully!"
+            }
+            StoreAction::TransferRecords { workspace, outsides, insides, transfer } => {
+                storage::transfer_records(&self.workspace, &workspace, &mut next.outside, &mut next.inside, &outsides, &insides, transfer)?;
+                match transfer {
+                    Transfer::Copy => "Records copied successfully!",
+                    Transfer::Move => "Records moved successfully!",
+                }
+            }
+        };
+        storage::save_changes(&self.workspace, (&self.outside, &self.inside), &next.outside, &next.inside)?;
+        storage::sort_outsides(&mut next.outside);
+        storage::sort_insides(&mut next.inside);
+        Ok((next, message))
+    }
+}
+
+impl Reducible for Store {
+    type Action = StoreUpdate;
+
+    fn reduce(self: Rc<Self>, update: Self::Action) -> Rc<Self> {
+        match self.apply(update.action) {
+            Ok((store, message)) => {
+                web_sys::console::log_1(&message.into());
+                Rc::new(Store {
+                    revision: self.revision + 1,
+                    ..store
+                })
+            }
+            Err(e) => {
+                web_sys::console::log_1(&format!("Failed to save: {}", e).into());
+                *update.outcome.borrow_mut() = Err(e);
+                self
+            }
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct StoreProviderProps {
+    pub children: Children,
+}
+
+#[function_component(StoreProvider)]
+pub fn store_provider(props: &StoreProviderProps) -> Html {
+    let store = StoreContext(use_reducer(Store::load));
+
+    // Reload when another tab saves
+    {
+        let store = store.clone();
+        use_effect_with((), move |_| {
+            let subscription = tab_sync::subscribe(Callback::from(move |change: Change| {
+                let _ = store.run(StoreAction::Remote(change));
+            }));
+            move || drop(subscription)
+        });
+    }
+
+    // Keep a linked file in sync, reloading when it is modified outside relf
+    {
+        let store = store.clone();
+        use_effect_with((), move |_| {
+            let watcher = file_sync::watch(Callback::from(move |_| {
+                store.reload();
+            }));
+            wasm_bindgen_futures::spawn_local(async {
+                if let Err(e) = file_sync::restore_link().await {
+                    web_sys::console::log_1(&format!("Failed to restore linked file: {}", e).into());
+                }
+            });
+            move || drop(watcher)
+        });
+    }
+
+    // Follow teammates' changes to a workspace linked to the server
+    {
+        let store = store.clone();
+        use_effect_with((), move |_| {
+            live::start(Callback::from(move |change: Change| {
+                let _ = store.run(StoreAction::Remote(change));
+            }));
+            live::stop
+        });
+    }
+
+    html! {
+        <ContextProvider<StoreContext> context={store}>
+            {props.children.clone()}
+        </ContextProvider<StoreContext>>
+    }
+}
+
+#[hook]
+pub fn use_store() -> StoreContext {
+    use_context::<StoreContext>().expect("StoreProvider is missing")
+}
@@ -0,0 +1,154 @@
+use std::cell::RefCell;
+use gloo::events::EventListener;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::{window, BroadcastChannel, MessageEvent, StorageEvent};
+use yew::Callback;
+use crate::frontend::services::workspace;
+
+const CHANNEL_NAME: &str = "relf";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum Collection {
+    Outside,
+    Inside,
+}
+
+// A save in another tab or by a teammate; `uuids` lists the records that were added, changed or removed,
+// and is empty when the browser cannot tell which ones
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Change {
+    pub collection: Collection,
+    pub uuids: Vec<String>,
+}
+
+impl Change {
+    pub fn touches(&self, uuid: &str) -> bool {
+        self.uuids.is_empty() || self.uuids.iter().any(|u| u == uuid)
+    }
+}
+
+// What a message tells other tabs about a workspace
+#[derive(Serialize, Deserialize)]
+enum Notice {
+    Saved(Change),
+    Deleted,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Message {
+    tab: String,
+    workspace: String,
+    notice: Notice,
+}
+
+thread_local! {
+    static TAB_ID: String = uuid::Uuid::new_v4().to_string();
+    static CHANNEL: RefCell<Option<BroadcastChannel>> = const { RefCell::new(None) };
+}
+
+fn channel() -> Option<BroadcastChannel> {
+    CHANNEL.with(|c| {
+        let mut channel = c.borrow_mut();
+        if channel.is_none() {
+            *channel = BroadcastChannel::new(CHANNEL_NAME).ok();
+        }
+        channel.clone()
+    })
+}
+
+// Tell other open tabs which records a save to a workspace changed
+pub fn broadcast(workspace_id: &str, change: Change) {
+    if change.uuids.is_empty() {
+        return;
+    }
+    // Without a channel other tabs still see the write through the storage event
+    post(workspace_id, Notice::Saved(change));
+}
+
+// Tell other open tabs a workspace was deleted, so those showing it move to the default one
+pub fn broadcast_deleted(workspace_id: &str) {
+    post(workspace_id, Notice::Deleted);
+}
+
+fn post(workspace_id: &str, notice: Notice) {
+    let Some(channel) = channel() else {
+        return;
+    };
+    let message = Message {
+        tab: TAB_ID.with(|id| id.clone()),
+        workspace: workspace_id.to_string(),
+        notice,
+    };
+    if let Ok(json) = serde_json::to_string(&message) {
+        let _ = channel.post_message(&json.into());
+    }
+}
+
+// Listens for changes made in other tabs until dropped
+pub struct Subscription {
+    _listener: EventListener,
+    _channel: Option<BroadcastChannel>,
+}
+
+pub fn subscribe(callback: Callback<Change>) -> Option<Subscription> {
+    if let Ok(channel) = BroadcastChannel::new(CHANNEL_NAME) {
+        let listener = EventListener::new(&channel, "message", move |event| {
+            let Some(data) = event
+                .dyn_ref::<MessageEvent>()
+                .and_then(|e| e.data().as_string())
+            else {
+                return;
+            };
+            let Ok(message) = serde_json::from_str::<Message>(&data) else {
+                return;
+            };
+            if TAB_ID.with(|id| *id == message.tab) {
+                return;
+            }
+            match message.notice {
+                // Tabs showing another workspace are not affected
+                Notice::Saved(change) if message.workspace == workspace::active_id() => callback.emit(change),
+                Notice::Saved(_) => {}
+                Notice::Deleted => {
+                    if workspace::deleted_elsewhere(&message.workspace) {
+                        callback.emit(Change {
+                            collection: Collection::Outside,
+                            uuids: Vec::new(),
+                        });
+                    }
+                }
+            }
+        });
+        return Some(Subscription {
+            _listener: listener,
+            _channel: Some(channel),
+        });
+    }
+
+    // Without BroadcastChannel fall back to storage events, which do not say what changed
+    let window = window()?;
+    let listener = EventListener::new(&window, "storage", move |event| {
+        let active = workspace::active_id();
+        if workspace::find(&active).is_none() && workspace::deleted_elsewhere(&active) {
+            callback.emit(Change {
+                collection: Collection::Outside,
+                uuids: Vec::new(),
+            });
+            return;
+        }
+        let collection = match event.dyn_ref::<StorageEvent>().and_then(|e| e.key()) {
+            Some(key) if key == workspace::outside_key(&active) => Collection::Outside,
+            Some(key) if key == workspace::inside_key(&active) => Collection::Inside,
+            _ => return,
+        };
+        callback.emit(Change {
+            collection,
+            uuids: Vec::new(),
+        });
+    });
+    Some(Subscription {
+        _listener: listener,
+        _channel: None,
+    })
+}
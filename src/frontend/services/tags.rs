@@ -0,0 +1,53 @@
+// Hashtags in a context, e.g. "#rust" or "#to-review", lowercased and without duplicates
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    let mut previous = ' ';
+    for (index, c) in text.char_indices() {
+        if c == '#' && (previous.is_whitespace() || previous == '(') {
+            let rest = &text[index + 1..];
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_' || c == '/'))
+                .unwrap_or(rest.len());
+            let tag = rest[..end].trim_end_matches(['-', '_', '/']).to_lowercase();
+            if tag.chars().any(|c| !c.is_ascii_digit()) && !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        previous = c;
+    }
+    tags
+}
+
+// A tag as typed by the user ("#Work log" → "work-log"), or None if nothing usable is left
+pub fn normalize_tag(input: &str) -> Option<String> {
+    let tag: String = input
+        .trim()
+        .trim_start_matches('#')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '/'))
+        .collect::<String>()
+        .to_lowercase();
+    let tag = tag.trim_matches(['-', '_', '/']).to_string();
+    tag.chars().any(|c| !c.is_ascii_digit()).then_some(tag)
+}
+
+// Append `#tag` to a context unless it already has it, joining a trailing line of tags
+pub fn add_tag(text: &str, tag: &str) -> String {
+    if parse_tags(text).iter().any(|t| t == tag) {
+        return text.to_string();
+    }
+    let trimmed = text.trim_end();
+    if trimmed.is_empty() {
+        return format!("#{}", tag);
+    }
+    let last_line = trimmed.lines().last().unwrap_or_default();
+    let only_tags = last_line.split_whitespace().all(|word| word.starts_with('#') && word.len() > 1 && !word.starts_with("##"));
+    if only_tags {
+        format!("{} #{}", trimmed, tag)
+    } else {
+        format!("{}\n\n#{}", trimmed, tag)
+    }
+}
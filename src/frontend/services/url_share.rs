@@ -0,0 +1,114 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as BASE64_URL;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::window;
+use crate::frontend::services::storage::ExportData;
+
+// Records travel in the fragment of a /view link, which browsers never send to a server:
+// "v1." followed by the export JSON, deflated and base64url encoded
+const PREFIX: &str = "v1.";
+// Guards against links that inflate to something huge
+const MAX_INFLATED_BYTES: usize = 8 * 1024 * 1024;
+// Some chat apps and browsers cut off longer links
+const LONG_LINK: usize = 8000;
+
+pub fn encode(data: &ExportData) -> Result<String, String> {
+    let json = serde_json::to_vec(data).map_err(|e| format!("Failed to serialize records: {}", e))?;
+    let compressed = miniz_oxide::deflate::compress_to_vec(&json, 9);
+    Ok(format!("{}{}", PREFIX, BASE64_URL.encode(compressed)))
+}
+
+pub fn decode(fragment: &str) -> Result<ExportData, String> {
+    let encoded = fragment
+        .trim_start_matches('#')
+        .strip_prefix(PREFIX)
+        .ok_or_else(|| "This link does not contain shared records".to_string())?;
+    let compressed = BASE64_URL
+        .decode(encoded.trim())
+        .map_err(|_| "The link is damaged or incomplete".to_string())?;
+    let json = miniz_oxide::inflate::decompress_to_vec_with_limit(&compressed, MAX_INFLATED_BYTES)
+        .map_err(|_| "The link is damaged or incomplete".to_string())?;
+    serde_json::from_slice(&json).map_err(|e| format!("Invalid shared records: {}", e))
+}
+
+// Full /view link for records, on the origin relf is served from
+pub fn link_for(data: &ExportData) -> Result<String, String> {
+    let origin = window()
+        .and_then(|w| w.location().origin().ok())
+        .ok_or_else(|| "No browser window".to_string())?;
+    Ok(format!("{}/view#{}", origin, encode(data)?))
+}
+
+// Copy a link, falling back to a prompt the user can copy from
+pub fn copy_link(link: String) {
+    let Some(window) = window() else {
+        return;
+    };
+    let note = if link.len() > LONG_LINK {
+        format!(" It is {} characters long, so some apps may cut it off.", link.len())
+    } else {
+        String::new()
+    };
+    wasm_bindgen_futures::spawn_local(async move {
+        let copied = JsFuture::from(window.navigator().clipboard().write_text(&link)).await;
+        match copied {
+            Ok(_) => {
+                let _ = window.alert_with_message(&format!("Share link copied to the clipboard.{}", note));
+            }
+            Err(_) => {
+                let _ = window.prompt_with_message_and_default(&format!("Copy this share link:{}", note), &link);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::Engine;
+    use serde_json::json;
+    use super::*;
+
+    fn records(count: usize) -> ExportData {
+        let outside: Vec<_> = (0..count)
+            .map(|n| json!({"name": format!("Book {}", n), "context": "Notes ".repeat(n % 50), "url": "https://example.com", "percentage": n % 100}))
+            .collect();
+        let inside: Vec<_> = (0..count)
+            .map(|n| json!({"date": "2025-01-01 00:00:00", "context": format!("Entry {} ✓", n), "related": [format!("Book {}", n)]}))
+            .collect();
+        serde_json::from_value(json!({"outside": outside, "inside": inside})).unwrap()
+    }
+
+    #[test]
+    fn large_payload_round_trips() {
+        let data = records(2000);
+        let encoded = encode(&data).unwrap();
+        assert!(encoded.starts_with(PREFIX));
+        let decoded = decode(&format!("#{}", encoded)).unwrap();
+        assert_eq!(serde_json::to_value(decoded).unwrap(), serde_json::to_value(data).unwrap());
+    }
+
+    #[test]
+    fn garbage_is_an_error() {
+        let not_json = format!("{}{}", PREFIX, BASE64_URL.encode(miniz_oxide::deflate::compress_to_vec(b"not json", 9)));
+        let fragments = [
+            "",
+            "#",
+            "v2.abc",
+            "v1.",
+            "v1.!!!***",
+            "v1.AAAA",
+            "v1.eJzLSM3JyVcozy_KSQEAGgQEXQ",
+            &not_json,
+        ];
+        for fragment in fragments {
+            assert!(decode(fragment).is_err(), "{}", fragment);
+        }
+    }
+
+    #[test]
+    fn oversized_payload_is_refused() {
+        let zeros = vec![b' '; MAX_INFLATED_BYTES + 1];
+        let fragment = format!("{}{}", PREFIX, BASE64_URL.encode(miniz_oxide::deflate::compress_to_vec(&zeros, 9)));
+        assert!(decode(&fragment).is_err());
+    }
+}
@@ -0,0 +1,354 @@
+use std::cmp::Ordering;
+use chrono::NaiveDate;
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use crate::models::{Outside, Inside};
+
+const OUTSIDE_VIEW_KEY: &str = "relf_outside_view";
+const INSIDE_VIEW_KEY: &str = "relf_inside_view";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortKey {
+    Name,
+    Percentage,
+    Date,
+    Added,
+    Updated,
+    Domain,
+    Random,
+}
+
+impl SortKey {
+    pub const OUTSIDE: [SortKey; 6] = [
+        SortKey::Percentage,
+        SortKey::Name,
+        SortKey::Added,
+        SortKey::Updated,
+        SortKey::Domain,
+        SortKey::Random,
+    ];
+    pub const INSIDE: [SortKey; 3] = [SortKey::Date, SortKey::Updated, SortKey::Random];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortKey::Name => "Name",
+            SortKey::Percentage => "Percentage",
+            SortKey::Date => "Date",
+            SortKey::Added => "Date added",
+            SortKey::Updated => "Date updated",
+            SortKey::Domain => "Domain",
+            SortKey::Random => "Random",
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortKey::Name => "name",
+            SortKey::Percentage => "percentage",
+            SortKey::Date => "date",
+            SortKey::Added => "added",
+            SortKey::Updated => "updated",
+            SortKey::Domain => "domain",
+            SortKey::Random => "random",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<SortKey> {
+        [
+            SortKey::Name,
+            SortKey::Percentage,
+            SortKey::Date,
+            SortKey::Added,
+            SortKey::Updated,
+            SortKey::Domain,
+            SortKey::Random,
+        ]
+        .into_iter()
+        .find(|key| key.as_str() == value)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    pub fn toggled(&self) -> SortOrder {
+        match self {
+            SortOrder::Asc => SortOrder::Desc,
+            SortOrder::Desc => SortOrder::Asc,
+        }
+    }
+
+    fn apply(&self, ordering: Ordering) -> Ordering {
+        match self {
+            SortOrder::Asc => ordering,
+            SortOrder::Desc => ordering.reverse(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Density {
+    Grid,
+    List,
+    Compact,
+}
+
+impl Density {
+    pub const ALL: [Density; 3] = [Density::Grid, Density::List, Density::Compact];
+
+    pub fn class(&self) -> &'static str {
+        match self {
+            Density::Grid => "density-grid",
+            Density::List => "density-list",
+            Density::Compact => "density-compact",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Density::Grid => "▦",
+            Density::List => "☰",
+            Density::Compact => "▤",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Density::Grid => "Grid",
+            Density::List => "List",
+            Density::Compact => "Compact",
+        }
+    }
+}
+
+// How Inside entries are laid out
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Layout {
+    #[default]
+    Cards,
+    Calendar,
+    Timeline,
+}
+
+impl Layout {
+    pub const INSIDE: [Layout; 3] = [Layout::Cards, Layout::Calendar, Layout::Timeline];
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Layout::Cards => "🗂️",
+            Layout::Calendar => "📅",
+            Layout::Timeline => "🕒",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Layout::Cards => "Cards",
+            Layout::Calendar => "Calendar",
+            Layout::Timeline => "Timeline",
+        }
+    }
+}
+
+const DAY_FORMAT: &str = "%Y-%m-%d";
+
+// How a page lists its records; persisted per page and mirrored in the query string
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ViewOptions {
+    pub sort: SortKey,
+    pub order: SortOrder,
+    pub density: Density,
+    // Seed for the random order, so it stays stable until reshuffled
+    #[serde(default)]
+    pub seed: u32,
+    #[serde(default)]
+    pub layout: Layout,
+    // Only show entries from this day; kept in the URL but not remembered
+    #[serde(skip)]
+    pub day: Option<NaiveDate>,
+}
+
+// Query string form of ViewOptions, e.g. `?sort=name&order=asc&view=list`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct ViewQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<SortOrder>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub view: Option<Density>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout: Option<Layout>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub day: Option<String>,
+    // Search box text
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub q: Option<String>,
+}
+
+impl ViewQuery {
+    pub fn with_search(self, search: &str) -> Self {
+        let search = search.trim();
+        Self {
+            q: (!search.is_empty()).then(|| search.to_string()),
+            ..self
+        }
+    }
+}
+
+impl ViewOptions {
+    pub fn default_outside() -> Self {
+        Self {
+            sort: SortKey::Percentage,
+            order: SortOrder::Desc,
+            density: Density::Grid,
+            seed: 0,
+            layout: Layout::Cards,
+            day: None,
+        }
+    }
+
+    pub fn default_inside() -> Self {
+        Self {
+            sort: SortKey::Date,
+            order: SortOrder::Desc,
+            density: Density::Grid,
+            seed: 0,
+            layout: Layout::Cards,
+            day: None,
+        }
+    }
+
+    // Query string values take precedence over the stored choice
+    pub fn with_query(self, query: &ViewQuery, allowed: &[SortKey]) -> Self {
+        Self {
+            sort: query
+                .sort
+                .as_deref()
+                .and_then(SortKey::parse)
+                .filter(|key| allowed.contains(key))
+                .unwrap_or(self.sort),
+            order: query.order.unwrap_or(self.order),
+            density: query.view.unwrap_or(self.density),
+            seed: query.seed.unwrap_or(self.seed),
+            layout: query.layout.unwrap_or(self.layout),
+            day: query
+                .day
+                .as_deref()
+                .and_then(|day| NaiveDate::parse_from_str(day, DAY_FORMAT).ok()),
+        }
+    }
+
+    pub fn to_query(self) -> ViewQuery {
+        ViewQuery {
+            sort: Some(self.sort.as_str().to_string()),
+            order: Some(self.order),
+            view: Some(self.density),
+            seed: (self.sort == SortKey::Random).then_some(self.seed),
+            layout: (self.layout != Layout::Cards).then_some(self.layout),
+            day: self.day.map(|day| day.format(DAY_FORMAT).to_string()),
+            q: None,
+        }
+    }
+}
+
+pub fn load_outside_view() -> ViewOptions {
+    LocalStorage::get(OUTSIDE_VIEW_KEY).unwrap_or_else(|_| ViewOptions::default_outside())
+}
+
+pub fn load_inside_view() -> ViewOptions {
+    LocalStorage::get(INSIDE_VIEW_KEY).unwrap_or_else(|_| ViewOptions::default_inside())
+}
+
+pub fn save_outside_view(options: &ViewOptions) {
+    let _ = LocalStorage::set(OUTSIDE_VIEW_KEY, options);
+}
+
+pub fn save_inside_view(options: &ViewOptions) {
+    let _ = LocalStorage::set(INSIDE_VIEW_KEY, options);
+}
+
+// Host of a URL without scheme, port or leading "www."
+pub fn domain_of(url: &str) -> String {
+    let rest = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let host = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let host = host.rsplit_once('@').map(|(_, host)| host).unwrap_or(host);
+    let host = host.split(':').next().unwrap_or("");
+    host.trim_start_matches("www.").to_lowercase()
+}
+
+// " by <username>" for records edited in a shared workspace
+pub fn by(username: &Option<String>) -> String {
+    username.as_ref().map(|name| format!(" by {}", name)).unwrap_or_default()
+}
+
+// Stable pseudo-random rank of a uuid for a given seed (FNV-1a)
+fn random_rank(uuid: &str, seed: u32) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325 ^ u64::from(seed);
+    for byte in uuid.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+fn contains_all(haystacks: &[&str], search: &str) -> bool {
+    search.split_whitespace().all(|term| {
+        let term = term.to_lowercase();
+        haystacks.iter().any(|text| text.to_lowercase().contains(&term))
+    })
+}
+
+// Every word of the search appears in the name, context or URL
+pub fn outside_matches(outside: &Outside, search: &str) -> bool {
+    contains_all(&[&outside.name, &outside.context, &outside.url], search)
+}
+
+// Every word of the search appears in the context or the date
+pub fn inside_matches(inside: &Inside, search: &str) -> bool {
+    contains_all(&[&inside.context, &inside.date.to_string()], search)
+}
+
+pub fn sort_outsides(outsides: &mut [Outside], options: &ViewOptions) {
+    outsides.sort_by(|a, b| {
+        let primary = match options.sort {
+            // Records without a percentage stay last in either direction
+            SortKey::Percentage => match (a.percentage, b.percentage) {
+                (Some(ap), Some(bp)) => options.order.apply(ap.cmp(&bp)),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+            SortKey::Name => options.order.apply(a.name.to_lowercase().cmp(&b.name.to_lowercase())),
+            SortKey::Added | SortKey::Date => options.order.apply(a.created_at.cmp(&b.created_at)),
+            SortKey::Updated => options.order.apply(a.updated_at.cmp(&b.updated_at)),
+            SortKey::Domain => options.order.apply(domain_of(&a.url).cmp(&domain_of(&b.url))),
+            SortKey::Random => random_rank(&a.uuid, options.seed).cmp(&random_rank(&b.uuid, options.seed)),
+        };
+        primary.then_with(|| a.name.cmp(&b.name))
+    });
+}
+
+pub fn sort_insides(insides: &mut [Inside], options: &ViewOptions) {
+    insides.sort_by(|a, b| {
+        let primary = match options.sort {
+            SortKey::Updated => options.order.apply(
+                a.updated_at.clone().unwrap_or_else(|| a.date.to_string())
+                    .cmp(&b.updated_at.clone().unwrap_or_else(|| b.date.to_string())),
+            ),
+            SortKey::Random => random_rank(&a.uuid, options.seed).cmp(&random_rank(&b.uuid, options.seed)),
+            _ => options.order.apply(a.date.cmp(&b.date)),
+        };
+        primary.then_with(|| b.date.cmp(&a.date))
+    });
+}
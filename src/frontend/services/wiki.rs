@@ -0,0 +1,93 @@
+use std::ops::Range;
+use crate::models::{Outside, Inside};
+use crate::frontend::services::router::Route;
+
+// A `[[target]]` or `[[target|label]]` link in a context
+#[derive(Clone, Debug, PartialEq)]
+pub struct WikiLink {
+    pub range: Range<usize>,
+    pub target: String,
+    pub label: String,
+}
+
+pub fn parse_links(text: &str) -> Vec<WikiLink> {
+    let mut links = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find("[[").map(|i| offset + i) {
+        let Some(end) = text[start + 2..].find("]]").map(|i| start + 2 + i) else {
+            break;
+        };
+        let inner = &text[start + 2..end];
+        if inner.contains('\n') || inner.contains("[[") {
+            offset = start + 1;
+            continue;
+        }
+        let (target, label) = inner.split_once('|').unwrap_or((inner, inner));
+        if !target.trim().is_empty() {
+            links.push(WikiLink {
+                range: start..end + 2,
+                target: target.trim().to_string(),
+                label: label.trim().to_string(),
+            });
+        }
+        offset = end + 2;
+    }
+    links
+}
+
+// Outside records resolve by name, Inside entries by uuid, date or day
+pub fn resolve(target: &str, outsides: &[Outside], insides: &[Inside]) -> Option<Route> {
+    let target = target.trim();
+    if let Some(outside) = outsides.iter().find(|o| o.name.trim().eq_ignore_ascii_case(target)) {
+        return Some(Route::OutsideDetail { uuid: outside.uuid.clone() });
+    }
+    insides
+        .iter()
+        .find(|i| i.uuid == target || i.date.to_string() == target)
+        .or_else(|| insides.iter().find(|i| target.len() >= 10 && i.date.to_string().starts_with(target)))
+        .map(|i| Route::InsideDetail { uuid: i.uuid.clone() })
+}
+
+fn links_to(context: &str, route: &Route, outsides: &[Outside], insides: &[Inside]) -> bool {
+    parse_links(context)
+        .iter()
+        .any(|link| resolve(&link.target, outsides, insides).as_ref() == Some(route))
+}
+
+// Records whose context links to `route`
+pub fn backlinks<'a>(
+    route: &Route,
+    outsides: &'a [Outside],
+    insides: &'a [Inside],
+) -> (Vec<&'a Outside>, Vec<&'a Inside>) {
+    (
+        outsides
+            .iter()
+            .filter(|o| links_to(&o.context, route, outsides, insides))
+            .collect(),
+        insides
+            .iter()
+            .filter(|i| links_to(&i.context, route, outsides, insides))
+            .collect(),
+    )
+}
+
+// Point links at `old_name` to `new_name`, keeping any label
+pub fn rename_links(text: &str, old_name: &str, new_name: &str) -> String {
+    let mut renamed = String::with_capacity(text.len());
+    let mut last = 0;
+    for link in parse_links(text) {
+        if !link.target.eq_ignore_ascii_case(old_name.trim()) {
+            continue;
+        }
+        renamed.push_str(&text[last..link.range.start]);
+        let inner = &text[link.range.start + 2..link.range.end - 2];
+        match inner.split_once('|') {
+            Some((_, label)) => renamed.push_str(&format!("[[{}|{}]]", new_name, label)),
+            None => renamed.push_str(&format!("[[{}]]", new_name)),
+        }
+        last = link.range.end;
+    }
+    renamed.push_str(&text[last..]);
+    renamed
+}
@@ -0,0 +1,177 @@
+use std::cell::RefCell;
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use crate::frontend::services::{file_sync, live, snapshot, tab_sync};
+
+const WORKSPACES_KEY: &str = "relf_workspaces";
+const ACTIVE_KEY: &str = "relf_workspace";
+// The first workspace keeps the keys data was stored under before workspaces existed
+pub const DEFAULT_ID: &str = "default";
+
+// A named set of Outside and Inside collections
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Workspace {
+    pub id: String,
+    pub name: String,
+}
+
+thread_local! {
+    // Each tab keeps the workspace it switched to, even when another tab switches
+    static ACTIVE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+pub fn outside_key(id: &str) -> String {
+    if id == DEFAULT_ID {
+        "relf_outside_data".to_string()
+    } else {
+        format!("relf_ws_{}_outside_data", id)
+    }
+}
+
+pub fn inside_key(id: &str) -> String {
+    if id == DEFAULT_ID {
+        "relf_inside_data".to_string()
+    } else {
+        format!("relf_ws_{}_inside_data", id)
+    }
+}
+
+// All workspaces, the default one first
+pub fn list() -> Vec<Workspace> {
+    let mut workspaces: Vec<Workspace> = LocalStorage::get(WORKSPACES_KEY).unwrap_or_default();
+    if !workspaces.iter().any(|w| w.id == DEFAULT_ID) {
+        workspaces.insert(0, Workspace {
+            id: DEFAULT_ID.to_string(),
+            name: "Default".to_string(),
+        });
+    }
+    workspaces
+}
+
+fn save(workspaces: &[Workspace]) -> Result<(), String> {
+    LocalStorage::set(WORKSPACES_KEY, workspaces).map_err(|e| format!("{:?}", e))
+}
+
+pub fn find(id: &str) -> Option<Workspace> {
+    list().into_iter().find(|w| w.id == id)
+}
+
+pub fn find_by_name(name: &str) -> Option<Workspace> {
+    list().into_iter().find(|w| w.name.trim().eq_ignore_ascii_case(name.trim()))
+}
+
+pub fn active_id() -> String {
+    ACTIVE.with(|active| {
+        let mut active = active.borrow_mut();
+        if active.is_none() {
+            let stored: String = LocalStorage::get(ACTIVE_KEY).unwrap_or_else(|_| DEFAULT_ID.to_string());
+            *active = Some(if find(&stored).is_some() { stored } else { DEFAULT_ID.to_string() });
+        }
+        active.clone().unwrap_or_default()
+    })
+}
+
+// Switch this tab to a workspace; new tabs open the last one chosen
+pub fn set_active(id: &str) -> Result<(), String> {
+    if find(id).is_none() {
+        return Err("Workspace not found".to_string());
+    }
+    ACTIVE.with(|active| *active.borrow_mut() = Some(id.to_string()));
+    LocalStorage::set(ACTIVE_KEY, id).map_err(|e| format!("{:?}", e))
+}
+
+fn validate_name(name: &str, except: Option<&str>) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Workspace name must not be empty".to_string());
+    }
+    if find_by_name(name).is_some_and(|w| Some(w.id.as_str()) != except) {
+        return Err(format!("A workspace named \"{}\" already exists", name));
+    }
+    Ok(name.to_string())
+}
+
+// Register an empty workspace; storage creates its collections on first save
+pub fn create(name: &str) -> Result<Workspace, String> {
+    let name = validate_name(name, None)?;
+    let mut workspaces = list();
+    let workspace = Workspace {
+        id: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+        name,
+    };
+    workspaces.push(workspace.clone());
+    save(&workspaces)?;
+    Ok(workspace)
+}
+
+pub fn rename(id: &str, name: &str) -> Result<(), String> {
+    let name = validate_name(name, Some(id))?;
+    let mut workspaces = list();
+    let workspace = workspaces
+        .iter_mut()
+        .find(|w| w.id == id)
+        .ok_or_else(|| "Workspace not found".to_string())?;
+    workspace.name = name;
+    save(&workspaces)
+}
+
+// Forget a workspace with its data, snapshots, linked file and live state; the default
+// workspace cannot be removed. Other tabs showing it move to the default workspace
+pub fn delete(id: &str) -> Result<(), String> {
+    if id == DEFAULT_ID {
+        return Err("The default workspace cannot be deleted".to_string());
+    }
+    let mut workspaces = list();
+    let name = workspaces
+        .iter()
+        .find(|w| w.id == id)
+        .map(|w| w.name.clone())
+        .ok_or_else(|| "Workspace not found".to_string())?;
+    workspaces.retain(|w| w.id != id);
+    save(&workspaces)?;
+    LocalStorage::delete(outside_key(id));
+    LocalStorage::delete(inside_key(id));
+    if release(id) {
+        set_active(DEFAULT_ID)?;
+    }
+    let id = id.to_string();
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(e) = file_sync::forget(&id).await {
+            log(&format!("Failed to unlink the file of a deleted workspace: {}", e));
+        }
+        // Sent once the file is unlinked, so other tabs find it gone
+        tab_sync::broadcast_deleted(&id);
+        if let Err(e) = snapshot::forget_workspace(&name).await {
+            log(&format!("Failed to remove a deleted workspace from snapshots: {}", e));
+        }
+    });
+    Ok(())
+}
+
+// Another tab deleted a workspace; returns whether this tab was showing it
+pub fn deleted_elsewhere(id: &str) -> bool {
+    let owned = id.to_string();
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(e) = file_sync::forget(&owned).await {
+            log(&format!("Failed to unlink the file of a deleted workspace: {}", e));
+        }
+    });
+    release(id)
+}
+
+// Stop following a deleted workspace live, and move off it if this tab shows it
+fn release(id: &str) -> bool {
+    live::forget(id);
+    ACTIVE.with(|active| {
+        let mut active = active.borrow_mut();
+        let showing = active.as_deref() == Some(id);
+        if showing {
+            *active = Some(DEFAULT_ID.to_string());
+        }
+        showing
+    })
+}
+
+fn log(message: &str) {
+    web_sys::console::log_1(&message.into());
+}
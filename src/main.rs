@@ -5,10 +5,15 @@ use std::env;
 use std::io::Result;
 use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
+use std::sync::Arc;
 use warp::Filter;
 
 mod models;
 mod frontend;
+mod server;
+
+// Where accounts and their records are stored, overridable with RELF_DATA
+const DEFAULT_DATA_PATH: &str = "data/relf.json";
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -57,6 +62,20 @@ async fn main() -> Result<()> {
     // Create socket address from parsed IP and port
     let socket_addr = SocketAddr::new(ip_addr, port);
 
+    // Load accounts and records for the API
+    let data_path = env::var("RELF_DATA").unwrap_or_else(|_| DEFAULT_DATA_PATH.to_string());
+    let db = match server::Db::open(&data_path) {
+        Ok(db) => Arc::new(db),
+        Err(e) => {
+            eprintln!("[{}] {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), e);
+            std::process::exit(1);
+        }
+    };
+    eprintln!("[{}] Storing accounts and records in {}", 
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), data_path);
+    let api = server::api(db.clone());
+    let pages = server::pages(db);
+
     // Serve all static files from the dist directory (Trunk output)
     let static_files = warp::fs::dir("dist");
     
@@ -76,7 +95,9 @@ async fn main() -> Result<()> {
         .and(warp::fs::file("dist/index.html"));
 
     // Combine all routes
-    let routes = robots_txt
+    let routes = api
+        .or(pages)
+        .or(robots_txt)
         .or(index_route)
         .or(static_files)
         .or(spa_fallback)
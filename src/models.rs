@@ -1,17 +1,226 @@
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct Outside {
     pub uuid: String,
     pub name: String,
     pub context: String,
     pub url: String,
     pub percentage: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    // Usernames of who added and last edited the record in a shared workspace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+    // Every change of percentage or status, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub progress_history: Vec<ProgressEntry>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Status {
+    ToRead,
+    InProgress,
+    Done,
+    Abandoned,
+}
+
+impl Status {
+    pub const ALL: [Status; 4] = [Status::ToRead, Status::InProgress, Status::Done, Status::Abandoned];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Status::ToRead => "To read",
+            Status::InProgress => "In progress",
+            Status::Done => "Done",
+            Status::Abandoned => "Abandoned",
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::ToRead => "to-read",
+            Status::InProgress => "in-progress",
+            Status::Done => "done",
+            Status::Abandoned => "abandoned",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Status> {
+        Status::ALL.into_iter().find(|s| s.as_str() == value)
+    }
+}
+
+// Progress or status of an Outside resource as of a point in time
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ProgressEntry {
+    pub at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub percentage: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+}
+
+// A point in time with its UTC offset, stored as RFC 3339.
+// Reading is tolerant: RFC 3339 / ISO 8601, "YYYY-MM-DD HH:MM[:SS]" in local time,
+// and date-only values (local midnight) are all accepted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(pub DateTime<FixedOffset>);
+
+impl Timestamp {
+    const DATE_TIME_FORMATS: [&'static str; 8] = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+        "%Y/%m/%d %H:%M:%S",
+        "%Y/%m/%d %H:%M",
+    ];
+    const OFFSET_FORMATS: [&'static str; 3] = ["%Y-%m-%d %H:%M:%S%z", "%Y-%m-%dT%H:%M:%S%z", "%Y-%m-%dT%H:%M%z"];
+    const DATE_FORMATS: [&'static str; 3] = ["%Y-%m-%d", "%Y/%m/%d", "%Y%m%d"];
+
+    pub fn now() -> Self {
+        Timestamp(Local::now().fixed_offset())
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if let Ok(date_time) = DateTime::parse_from_rfc3339(value) {
+            return Some(Timestamp(date_time));
+        }
+        if let Some(date_time) = Self::OFFSET_FORMATS
+            .iter()
+            .find_map(|format| DateTime::parse_from_str(value, format).ok())
+        {
+            return Some(Timestamp(date_time));
+        }
+        let naive = Self::DATE_TIME_FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+            .or_else(|| {
+                Self::DATE_FORMATS
+                    .iter()
+                    .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+            })?;
+        Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|date_time| Timestamp(date_time.fixed_offset()))
+    }
+
+    // Calendar day in local time
+    pub fn day(&self) -> NaiveDate {
+        self.0.with_timezone(&Local).date_naive()
+    }
+
+    // Value for an `<input type="datetime-local">`
+    pub fn to_input_value(self) -> String {
+        self.0.with_timezone(&Local).format("%Y-%m-%dT%H:%M:%S").to_string()
+    }
+}
+
+impl Default for Timestamp {
+    fn default() -> Self {
+        Timestamp(DateTime::UNIX_EPOCH.fixed_offset())
+    }
+}
+
+// Shown in local time, e.g. "2025-01-01 09:30:00"
+impl std::fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S"))
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0.to_rfc3339())
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Timestamp::parse(&value)
+            .ok_or_else(|| serde::de::Error::custom(format!("unrecognized date: {}", value)))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(from = "StoredInside")]
 pub struct Inside {
     pub uuid: String,
-    pub date: String,
+    pub date: Timestamp,
     pub context: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_by: Option<String>,
+    // Outside resources this entry is about
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outside_uuids: Vec<String>,
+    // A stored date that could not be read, kept as written so saving does not lose it;
+    // `date` then orders the entry by when it was last saved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unreadable_date: Option<String>,
+}
+
+impl Inside {
+    // The date as shown, or as stored when it could not be read
+    pub fn date_label(&self) -> String {
+        self.unreadable_date.clone().unwrap_or_else(|| self.date.to_string())
+    }
+}
+
+// An Inside as stored, before its date is read
+#[derive(Deserialize)]
+struct StoredInside {
+    uuid: String,
+    date: serde_json::Value,
+    context: String,
+    #[serde(default)]
+    updated_at: Option<String>,
+    #[serde(default)]
+    created_by: Option<String>,
+    #[serde(default)]
+    updated_by: Option<String>,
+    #[serde(default)]
+    outside_uuids: Vec<String>,
+    #[serde(default)]
+    unreadable_date: Option<String>,
+}
+
+impl From<StoredInside> for Inside {
+    fn from(stored: StoredInside) -> Self {
+        let date = stored.date.as_str().and_then(Timestamp::parse);
+        let unreadable_date = match date {
+            Some(_) => stored.unreadable_date,
+            None => Some(stored.date.as_str().map_or_else(|| stored.date.to_string(), str::to_string)),
+        };
+        Inside {
+            uuid: stored.uuid,
+            date: date
+                .or_else(|| stored.updated_at.as_deref().and_then(Timestamp::parse))
+                .unwrap_or_default(),
+            context: stored.context,
+            updated_at: stored.updated_at,
+            created_by: stored.created_by,
+            updated_by: stored.updated_by,
+            outside_uuids: stored.outside_uuids,
+            unreadable_date,
+        }
+    }
 }
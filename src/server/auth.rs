@@ -0,0 +1,681 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use warp::http::StatusCode;
+use warp::{Filter, Rejection, Reply};
+use crate::server::db::{timestamp, ApiToken, Data, Session, SharedDb, User};
+use crate::server::{json_body, json_reply, ApiError};
+
+const KDF: &str = "pbkdf2-sha256";
+const ITERATIONS: u32 = 200_000;
+const SESSION_DAYS: i64 = 30;
+const MIN_PASSWORD_LEN: usize = 8;
+// Personal API tokens are recognizable in scripts and logs
+const API_TOKEN_PREFIX: &str = "relf_pat_";
+// When a token was last used is saved at most this often
+const LAST_USED_INTERVAL_SECS: i64 = 10 * 60;
+// Failed logins allowed in a row before the username is locked for a while
+const MAX_FAILED_LOGINS: u32 = 5;
+const LOCKOUT: Duration = Duration::from_secs(30);
+const MAX_LOCKOUT: Duration = Duration::from_secs(15 * 60);
+// A username's failures are forgotten after this long without another one
+const FORGET_FAILURES: Duration = Duration::from_secs(60 * 60);
+
+// The account a request acts for
+#[derive(Clone, Debug)]
+pub struct Auth {
+    pub user_id: String,
+    pub username: String,
+    token_hash: String,
+}
+
+#[cfg(test)]
+impl Auth {
+    // Act as a user without a session
+    pub fn of(user_id: &str) -> Self {
+        Self {
+            user_id: user_id.to_string(),
+            username: user_id.to_string(),
+            token_hash: String::new(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Credentials {
+    username: String,
+    password: String,
+}
+
+#[derive(Deserialize)]
+struct NewToken {
+    name: String,
+}
+
+#[derive(Serialize)]
+struct Account {
+    id: String,
+    username: String,
+}
+
+#[derive(Serialize)]
+struct SessionReply {
+    token: String,
+    expires_at: i64,
+    user: Account,
+}
+
+#[derive(Serialize)]
+struct TokenInfo {
+    id: String,
+    name: String,
+    created_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_used_at: Option<String>,
+    // Only returned once, when the token is created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token: Option<String>,
+}
+
+impl TokenInfo {
+    fn from(token: &ApiToken) -> Self {
+        Self {
+            id: token.id.clone(),
+            name: token.name.clone(),
+            created_at: token.created_at.clone(),
+            last_used_at: token.last_used_at.clone(),
+            token: None,
+        }
+    }
+}
+
+struct Failures {
+    count: u32,
+    last: Instant,
+    locked_until: Option<Instant>,
+}
+
+// Failed logins by username, kept in memory. After a few in a row the username is locked,
+// for twice as long with each further failure, up to a limit; a successful login clears them
+#[derive(Default)]
+pub struct LoginAttempts(Mutex<HashMap<String, Failures>>);
+
+impl LoginAttempts {
+    // How much longer the username is locked for
+    fn locked(&self, username: &str, now: Instant) -> Option<Duration> {
+        let failures = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let until = failures.get(&username.to_lowercase())?.locked_until?;
+        until.checked_duration_since(now).filter(|wait| !wait.is_zero())
+    }
+
+    fn failed(&self, username: &str, now: Instant) {
+        let mut failures = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        failures.retain(|_, f| now.duration_since(f.last) < FORGET_FAILURES);
+        let entry = failures.entry(username.to_lowercase()).or_insert(Failures {
+            count: 0,
+            last: now,
+            locked_until: None,
+        });
+        entry.count += 1;
+        entry.last = now;
+        if let Some(extra) = entry.count.checked_sub(MAX_FAILED_LOGINS) {
+            entry.locked_until = Some(now + LOCKOUT.saturating_mul(1 << extra.min(16)).min(MAX_LOCKOUT));
+        }
+    }
+
+    fn succeeded(&self, username: &str) {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).remove(&username.to_lowercase());
+    }
+}
+
+// Whether a token's last use is old enough to be saved again
+fn last_used_is_stale(last_used_at: Option<&str>) -> bool {
+    let now = chrono::Local::now().naive_local();
+    last_used_at
+        .and_then(|at| chrono::NaiveDateTime::parse_from_str(at, "%Y-%m-%d %H:%M:%S").ok())
+        .is_none_or(|at| (now - at).num_seconds() >= LAST_USED_INTERVAL_SECS)
+}
+
+// 244 random bits from two v4 UUIDs
+pub fn random_token() -> String {
+    format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple())
+}
+
+fn hash_token(token: &str) -> String {
+    BASE64.encode(Sha256::digest(token.as_bytes()))
+}
+
+fn derive(password: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, iterations, &mut hash);
+    hash
+}
+
+fn hash_password(password: &str) -> String {
+    let salt = uuid::Uuid::new_v4().into_bytes();
+    let hash = derive(password, &salt, ITERATIONS);
+    format!("{}${}${}${}", KDF, ITERATIONS, BASE64.encode(salt), BASE64.encode(hash))
+}
+
+// Checked when no user has the name, so a login takes as long whether or not the user exists
+fn dummy_hash() -> &'static str {
+    static DUMMY: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+    DUMMY.get_or_init(|| hash_password("relf-dummy-password"))
+}
+
+// Compare without returning early, so timing does not reveal how much matched
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn verify_password(password: &str, stored: &str) -> bool {
+    let parts: Vec<&str> = stored.split('$').collect();
+    let [kdf, iterations, salt, hash] = parts[..] else {
+        return false;
+    };
+    let (Ok(iterations), Ok(salt), Ok(hash)) = (iterations.parse(), BASE64.decode(salt), BASE64.decode(hash)) else {
+        return false;
+    };
+    kdf == KDF && constant_time_eq(&derive(password, &salt, iterations), &hash)
+}
+
+fn validate_username(username: &str) -> Result<String, ApiError> {
+    let username = username.trim();
+    let valid_chars = username.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.');
+    if !(3..=32).contains(&username.len()) || !valid_chars {
+        return Err(ApiError::bad_request(
+            "Username must be 3–32 characters of letters, digits, '-', '_' or '.'",
+        ));
+    }
+    Ok(username.to_string())
+}
+
+pub fn now_secs() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+// Start a session, dropping expired ones on the way
+fn open_session(data: &mut Data, user: &User) -> SessionReply {
+    let now = now_secs();
+    data.sessions.retain(|s| s.expires_at > now);
+    let token = random_token();
+    let expires_at = now + SESSION_DAYS * 24 * 60 * 60;
+    data.sessions.push(Session {
+        token_hash: hash_token(&token),
+        user_id: user.id.clone(),
+        expires_at,
+    });
+    SessionReply {
+        token,
+        expires_at,
+        user: Account {
+            id: user.id.clone(),
+            username: user.username.clone(),
+        },
+    }
+}
+
+// Look up the session or API token a bearer token belongs to
+pub async fn authenticate(db: SharedDb, header: Option<String>) -> Result<Auth, Rejection> {
+    let token = header
+        .as_deref()
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| ApiError::unauthorized("Missing bearer token"))?;
+    let token_hash = hash_token(token);
+
+    let (auth, used_token) = {
+        let data = db.read().await;
+        let (user_id, used_token) = if token.starts_with(API_TOKEN_PREFIX) {
+            data.tokens
+                .iter()
+                .find(|t| t.token_hash == token_hash)
+                .map(|t| (Some(t.user_id.clone()), last_used_is_stale(t.last_used_at.as_deref()).then(|| t.id.clone())))
+                .unwrap_or_default()
+        } else {
+            let now = now_secs();
+            let user_id = data
+                .sessions
+                .iter()
+                .find(|s| s.token_hash == token_hash && s.expires_at > now)
+                .map(|s| s.user_id.clone());
+            (user_id, None)
+        };
+        let user = user_id
+            .and_then(|id| data.users.iter().find(|u| u.id == id))
+            .ok_or_else(|| ApiError::unauthorized("Invalid or expired token"))?;
+        let auth = Auth {
+            user_id: user.id.clone(),
+            username: user.username.clone(),
+            token_hash,
+        };
+        (auth, used_token)
+    };
+    // The request goes ahead even when its use of the token cannot be saved
+    if let Some(id) = used_token {
+        let saved = db
+            .write(|data| {
+                if let Some(token) = data.tokens.iter_mut().find(|t| t.id == id) {
+                    token.last_used_at = Some(timestamp());
+                }
+                Ok(())
+            })
+            .await;
+        if let Err(e) = saved {
+            eprintln!("Failed to save when a token was last used: {}", e.message);
+        }
+    }
+    Ok(auth)
+}
+
+// Requires `Authorization: Bearer <session or API token>`
+pub fn authenticated(db: SharedDb) -> impl Filter<Extract = (Auth,), Error = Rejection> + Clone {
+    warp::any()
+        .map(move || db.clone())
+        .and(warp::header::optional::<String>("authorization"))
+        .and_then(authenticate)
+}
+
+pub fn with_db(db: SharedDb) -> impl Filter<Extract = (SharedDb,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || db.clone())
+}
+
+async fn register(db: SharedDb, credentials: Credentials) -> Result<warp::reply::Response, Rejection> {
+    let username = validate_username(&credentials.username)?;
+    if credentials.password.chars().count() < MIN_PASSWORD_LEN {
+        return Err(ApiError::bad_request(format!("Password must be at least {} characters", MIN_PASSWORD_LEN)).into());
+    }
+    // Hashing is slow on purpose, so it runs off the async workers
+    let password_hash = tokio::task::spawn_blocking(move || hash_password(&credentials.password))
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to hash password: {}", e)))?;
+    let reply = db
+        .write(|data| {
+            if data.users.iter().any(|u| u.username.eq_ignore_ascii_case(&username)) {
+                return Err(ApiError::conflict("Username is already taken"));
+            }
+            let user = User {
+                id: uuid::Uuid::new_v4().to_string(),
+                username,
+                password_hash,
+                created_at: timestamp(),
+            };
+            data.records.entry(user.id.clone()).or_default();
+            let reply = open_session(data, &user);
+            data.users.push(user);
+            Ok(reply)
+        })
+        .await?;
+    Ok(json_reply(&reply, StatusCode::CREATED))
+}
+
+async fn login(db: SharedDb, credentials: Credentials) -> Result<warp::reply::Response, Rejection> {
+    let username = credentials.username.trim().to_string();
+    if let Some(wait) = db.logins.locked(&username, Instant::now()) {
+        return Err(ApiError::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            format!("Too many failed logins, try again in {} seconds", wait.as_secs() + 1),
+        )
+        .into());
+    }
+    let user = db
+        .read()
+        .await
+        .users
+        .iter()
+        .find(|u| u.username.eq_ignore_ascii_case(&username))
+        .cloned();
+    // Same answer, in the same time, for unknown users and wrong passwords
+    let user = tokio::task::spawn_blocking(move || match user {
+        Some(user) => verify_password(&credentials.password, &user.password_hash).then_some(user),
+        None => {
+            verify_password(&credentials.password, dummy_hash());
+            None
+        }
+    })
+    .await
+    .map_err(|e| ApiError::internal(format!("Failed to verify password: {}", e)))?;
+    let Some(user) = user else {
+        db.logins.failed(&username, Instant::now());
+        return Err(ApiError::unauthorized("Invalid username or password").into());
+    };
+    db.logins.succeeded(&username);
+    let reply = db.write(|data| Ok(open_session(data, &user))).await?;
+    Ok(json_reply(&reply, StatusCode::OK))
+}
+
+async fn logout(auth: Auth, db: SharedDb) -> Result<warp::reply::Response, Rejection> {
+    db.write(|data| {
+        data.sessions.retain(|s| s.token_hash != auth.token_hash);
+        Ok(())
+    })
+    .await?;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+async fn me(auth: Auth) -> Result<warp::reply::Response, Rejection> {
+    let account = Account {
+        id: auth.user_id,
+        username: auth.username,
+    };
+    Ok(json_reply(&account, StatusCode::OK))
+}
+
+async fn list_tokens(auth: Auth, db: SharedDb) -> Result<warp::reply::Response, Rejection> {
+    let tokens: Vec<TokenInfo> = db
+        .read()
+        .await
+        .tokens
+        .iter()
+        .filter(|t| t.user_id == auth.user_id)
+        .map(TokenInfo::from)
+        .collect();
+    Ok(json_reply(&tokens, StatusCode::OK))
+}
+
+async fn create_token(auth: Auth, db: SharedDb, new_token: NewToken) -> Result<warp::reply::Response, Rejection> {
+    let name = new_token.name.trim().to_string();
+    if name.is_empty() {
+        return Err(ApiError::bad_request("Token name must not be empty").into());
+    }
+    let token = format!("{}{}", API_TOKEN_PREFIX, random_token());
+    let api_token = ApiToken {
+        id: uuid::Uuid::new_v4().to_string(),
+        user_id: auth.user_id,
+        name,
+        token_hash: hash_token(&token),
+        created_at: timestamp(),
+        last_used_at: None,
+    };
+    let info = TokenInfo {
+        token: Some(token),
+        ..TokenInfo::from(&api_token)
+    };
+    db.write(|data| {
+        data.tokens.push(api_token);
+        Ok(())
+    })
+    .await?;
+    Ok(json_reply(&info, StatusCode::CREATED))
+}
+
+async fn revoke_token(id: String, auth: Auth, db: SharedDb) -> Result<warp::reply::Response, Rejection> {
+    db.write(|data| {
+        let count = data.tokens.len();
+        data.tokens.retain(|t| !(t.id == id && t.user_id == auth.user_id));
+        if data.tokens.len() == count {
+            return Err(ApiError::not_found("Token not found"));
+        }
+        Ok(())
+    })
+    .await?;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+// /api/auth/* and /api/tokens
+pub fn routes(db: SharedDb) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let auth = warp::path("api").and(warp::path("auth"));
+    let register = auth
+        .and(warp::path("register"))
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(with_db(db.clone()))
+        .and(json_body())
+        .and_then(register);
+    let login = auth
+        .and(warp::path("login"))
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(with_db(db.clone()))
+        .and(json_body())
+        .and_then(login);
+    let logout = auth
+        .and(warp::path("logout"))
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(authenticated(db.clone()))
+        .and(with_db(db.clone()))
+        .and_then(logout);
+    let me = auth
+        .and(warp::path("me"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(authenticated(db.clone()))
+        .and_then(me);
+
+    let tokens = warp::path("api").and(warp::path("tokens"));
+    let list_tokens = tokens
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(authenticated(db.clone()))
+        .and(with_db(db.clone()))
+        .and_then(list_tokens);
+    let create_token = tokens
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(authenticated(db.clone()))
+        .and(with_db(db.clone()))
+        .and(json_body())
+        .and_then(create_token);
+    let revoke_token = tokens
+        .and(warp::path::param())
+        .and(warp::path::end())
+        .and(warp::delete())
+        .and(authenticated(db.clone()))
+        .and(with_db(db))
+        .and_then(revoke_token);
+
+    register
+        .or(login)
+        .or(logout)
+        .or(me)
+        .or(list_tokens)
+        .or(create_token)
+        .or(revoke_token)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::server::db::scratch;
+    use crate::server::status_of;
+    use super::*;
+
+    fn user(id: &str) -> User {
+        User {
+            id: id.to_string(),
+            username: id.to_string(),
+            password_hash: String::new(),
+            created_at: timestamp(),
+        }
+    }
+
+    fn bearer(token: &str) -> Option<String> {
+        Some(format!("Bearer {}", token))
+    }
+
+    async fn user_of(db: &SharedDb, header: Option<String>) -> Result<String, StatusCode> {
+        authenticate(db.clone(), header)
+            .await
+            .map(|auth| auth.user_id)
+            .map_err(|rejection| status_of(Err(rejection)))
+    }
+
+    #[test]
+    fn password_verifies_only_itself() {
+        let stored = hash_password("correct horse");
+        assert!(stored.starts_with(&format!("{}${}$", KDF, ITERATIONS)));
+        assert!(verify_password("correct horse", &stored));
+        assert!(!verify_password("correct horsf", &stored));
+        assert!(!verify_password("", &stored));
+        // Each hash has its own salt
+        assert_ne!(stored, hash_password("correct horse"));
+    }
+
+    #[test]
+    fn malformed_or_foreign_hashes_never_verify() {
+        let salt = b"salt";
+        let hash = BASE64.encode(derive("secret", salt, 10));
+        assert!(verify_password("secret", &format!("{}$10${}${}", KDF, BASE64.encode(salt), hash)));
+        assert!(!verify_password("secret", &format!("md5$10${}${}", BASE64.encode(salt), hash)));
+        assert!(!verify_password("secret", &format!("{}$ten${}${}", KDF, BASE64.encode(salt), hash)));
+        assert!(!verify_password("secret", &format!("{}$10$%%%${}", KDF, hash)));
+        assert!(!verify_password("secret", &format!("{}$10${}", KDF, hash)));
+        assert!(!verify_password("secret", ""));
+    }
+
+    #[test]
+    fn dummy_hash_costs_as_much_and_matches_no_guess() {
+        assert!(dummy_hash().starts_with(&format!("{}${}$", KDF, ITERATIONS)));
+        assert!(!verify_password("password", dummy_hash()));
+        assert_eq!(dummy_hash(), dummy_hash());
+    }
+
+    #[test]
+    fn constant_time_eq_compares_whole_values() {
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abd"));
+        assert!(!constant_time_eq(b"abc", b"ab"));
+        assert!(constant_time_eq(b"", b""));
+    }
+
+    #[test]
+    fn repeated_failures_lock_a_username_for_longer_each_time() {
+        let logins = LoginAttempts::default();
+        let start = Instant::now();
+        for _ in 1..MAX_FAILED_LOGINS {
+            logins.failed("Ann", start);
+        }
+        assert_eq!(logins.locked("ann", start), None);
+
+        logins.failed("ann", start);
+        assert_eq!(logins.locked("ANN", start), Some(LOCKOUT));
+        assert_eq!(logins.locked("bob", start), None);
+        assert_eq!(logins.locked("ann", start + LOCKOUT), None);
+
+        logins.failed("ann", start + LOCKOUT);
+        assert_eq!(logins.locked("ann", start + LOCKOUT), Some(LOCKOUT * 2));
+        for _ in 0..20 {
+            logins.failed("ann", start + LOCKOUT);
+        }
+        assert_eq!(logins.locked("ann", start + LOCKOUT), Some(MAX_LOCKOUT));
+
+        logins.succeeded("Ann");
+        assert_eq!(logins.locked("ann", start + LOCKOUT), None);
+    }
+
+    #[test]
+    fn old_failures_are_forgotten() {
+        let logins = LoginAttempts::default();
+        let start = Instant::now();
+        for _ in 1..MAX_FAILED_LOGINS {
+            logins.failed("ann", start);
+        }
+        logins.failed("ann", start + FORGET_FAILURES);
+        assert_eq!(logins.locked("ann", start + FORGET_FAILURES), None);
+    }
+
+    #[test]
+    fn token_use_is_saved_at_most_every_interval() {
+        let at = |secs_ago: i64| {
+            (chrono::Local::now() - chrono::Duration::seconds(secs_ago))
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        };
+        assert!(last_used_is_stale(None));
+        assert!(last_used_is_stale(Some("not a date")));
+        assert!(last_used_is_stale(Some(&at(LAST_USED_INTERVAL_SECS + 5))));
+        assert!(!last_used_is_stale(Some(&at(5))));
+    }
+
+    #[tokio::test]
+    async fn sessions_last_until_they_expire_or_log_out() {
+        let db = scratch();
+        let session = db
+            .write(|data| {
+                data.users.push(user("ann"));
+                let session = open_session(data, &user("ann"));
+                data.sessions.push(Session {
+                    token_hash: hash_token("expired"),
+                    user_id: "ann".to_string(),
+                    expires_at: now_secs() - 1,
+                });
+                Ok(session)
+            })
+            .await
+            .unwrap();
+        assert!(session.expires_at > now_secs() + (SESSION_DAYS - 1) * 24 * 60 * 60);
+
+        assert_eq!(user_of(&db, bearer(&session.token)).await, Ok("ann".to_string()));
+        assert_eq!(user_of(&db, bearer("expired")).await, Err(StatusCode::UNAUTHORIZED));
+        assert_eq!(user_of(&db, bearer("unknown")).await, Err(StatusCode::UNAUTHORIZED));
+        assert_eq!(user_of(&db, None).await, Err(StatusCode::UNAUTHORIZED));
+        assert_eq!(user_of(&db, Some(session.token.clone())).await, Err(StatusCode::UNAUTHORIZED));
+
+        let auth = authenticate(db.clone(), bearer(&session.token)).await.unwrap();
+        assert_eq!(status_of(logout(auth, db.clone()).await), StatusCode::NO_CONTENT);
+        assert_eq!(user_of(&db, bearer(&session.token)).await, Err(StatusCode::UNAUTHORIZED));
+    }
+
+    #[tokio::test]
+    async fn api_tokens_work_until_revoked() {
+        let db = scratch();
+        db.write(|data| {
+            data.users.push(user("ann"));
+            Ok(())
+        })
+        .await
+        .unwrap();
+        let new_token = NewToken { name: "script".to_string() };
+        assert_eq!(status_of(create_token(Auth::of("ann"), db.clone(), new_token).await), StatusCode::CREATED);
+        // Only the hash is kept, so hand out a known token the same way
+        let token = format!("{}{}", API_TOKEN_PREFIX, random_token());
+        let id = db
+            .write(|data| {
+                let api_token = &mut data.tokens[0];
+                api_token.token_hash = hash_token(&token);
+                Ok(api_token.id.clone())
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(user_of(&db, bearer(&token)).await, Ok("ann".to_string()));
+        assert!(db.read().await.tokens[0].last_used_at.is_some());
+
+        assert_eq!(status_of(revoke_token(id.clone(), Auth::of("bob"), db.clone()).await), StatusCode::NOT_FOUND);
+        assert_eq!(status_of(revoke_token(id, Auth::of("ann"), db.clone()).await), StatusCode::NO_CONTENT);
+        assert_eq!(user_of(&db, bearer(&token)).await, Err(StatusCode::UNAUTHORIZED));
+    }
+
+    #[tokio::test]
+    async fn unknown_users_and_wrong_passwords_fail_alike_and_count_toward_a_lockout() {
+        let db = scratch();
+        let password_hash = hash_password("correct horse");
+        db.write(|data| {
+            data.users.push(User { password_hash, ..user("ann") });
+            Ok(())
+        })
+        .await
+        .unwrap();
+        let credentials = |username: &str, password: &str| Credentials {
+            username: username.to_string(),
+            password: password.to_string(),
+        };
+
+        assert_eq!(status_of(login(db.clone(), credentials("nobody", "guess")).await), StatusCode::UNAUTHORIZED);
+        assert_eq!(status_of(login(db.clone(), credentials("ann", "guess")).await), StatusCode::UNAUTHORIZED);
+        assert_eq!(status_of(login(db.clone(), credentials("ann", "correct horse")).await), StatusCode::OK);
+
+        for _ in 0..MAX_FAILED_LOGINS {
+            db.logins.failed("ann", Instant::now());
+        }
+        assert_eq!(
+            status_of(login(db.clone(), credentials("ann", "correct horse")).await),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+    }
+}
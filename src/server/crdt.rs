@@ -0,0 +1,200 @@
+use serde::{Deserialize, Serialize};
+use warp::http::StatusCode;
+use warp::{Filter, Rejection, Reply};
+use crate::frontend::services::crdt::{Doc, Op};
+use crate::frontend::services::markdown::is_web_url;
+use crate::frontend::services::storage::changed_uuids;
+use crate::frontend::services::tab_sync::Collection;
+use crate::models::{Inside, Outside};
+use crate::server::auth::{authenticated, with_db, Auth};
+use crate::server::db::{timestamp, Collections, Data, Journal, Role, SharedDb};
+use crate::server::live::{origin, Action};
+use crate::server::records::{access, publish, scope, scope_key, Record};
+use crate::server::{json_body, json_reply, ApiError};
+
+// A point in the journal: `since` operations into `epoch`
+#[derive(Deserialize)]
+struct Since {
+    #[serde(default)]
+    epoch: u64,
+    #[serde(default)]
+    since: usize,
+}
+
+#[derive(Deserialize)]
+struct NewOps {
+    ops: Vec<Op>,
+}
+
+// Operations after `since`, and how many there are in total. A client from another epoch gets
+// them all, with the document they continue from; epoch 0 means no journal was started yet
+#[derive(Serialize)]
+struct OpsReply {
+    epoch: u64,
+    seq: usize,
+    ops: Vec<Op>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base: Option<Doc>,
+}
+
+// A record change to tell live clients about
+type Event<R> = (Action, String, Option<R>);
+
+// The journal of a scope, started from its current records the first time it is needed
+fn journal<'a>(data: &'a mut Data, key: &str) -> &'a mut Journal {
+    if !data.journals.contains_key(key) {
+        let records = data.records.get(key).cloned().unwrap_or_default();
+        let mut journal = Journal::default();
+        let doc = journal.doc();
+        let mut ops = doc.update(Collection::Outside, &records.outside);
+        ops.extend(doc.update(Collection::Inside, &records.inside));
+        journal.ops = ops;
+        data.journals.insert(key.to_string(), journal);
+    }
+    data.journals.entry(key.to_string()).or_default()
+}
+
+// Record edits made through the record endpoints in the scope's journal, if it has one;
+// the journal's epoch and new length when operations were added
+pub fn follow(data: &mut Data, key: &str) -> Option<(u64, usize)> {
+    let records = data.records.get(key)?.clone();
+    let journal = data.journals.get_mut(key)?;
+    let doc = journal.doc();
+    let mut ops = doc.update(Collection::Outside, &records.outside);
+    ops.extend(doc.update(Collection::Inside, &records.inside));
+    if ops.is_empty() {
+        return None;
+    }
+    journal.extend(ops);
+    Some((journal.epoch, journal.ops.len()))
+}
+
+// Tell live clients that merge edits to fetch the new operations
+pub fn announce(db: &SharedDb, key: &str, position: Option<(u64, usize)>) {
+    if let Some((epoch, seq)) = position {
+        db.live.announce(key, epoch, seq);
+    }
+}
+
+// Take the merged records of a collection, stamping the changed ones with who changed them
+// and when; the stamps go into the journal too so every replica sees them
+fn settle<R: Record>(journal: &mut Journal, collections: &mut Collections, kind: Collection, auth: &Auth, now: &str) -> Vec<Event<R>> {
+    let previous = R::collection(collections).clone();
+    let doc = journal.doc();
+    let mut merged: Vec<R> = doc.records(kind);
+    let changed = changed_uuids(&previous, &merged, |r| r.uuid());
+    for record in merged.iter_mut().filter(|r| changed.iter().any(|u| u == r.uuid())) {
+        let before = previous.iter().find(|p| p.uuid() == record.uuid());
+        record.stamp(before, now, &auth.username);
+    }
+    let ops = doc.update(kind, &merged);
+    journal.extend(ops);
+
+    let events = changed
+        .into_iter()
+        .map(|uuid| {
+            let after = merged.iter().find(|r| r.uuid() == uuid).cloned();
+            let action = match (&after, previous.iter().any(|p| p.uuid() == uuid)) {
+                (None, _) => Action::Deleted,
+                (Some(_), true) => Action::Updated,
+                (Some(_), false) => Action::Created,
+            };
+            (action, uuid, after)
+        })
+        .collect();
+    *R::collection(collections) = merged;
+    events
+}
+
+// Only reads: a journal is started by the first operations sent, even an empty list
+async fn list_ops(workspace: Option<String>, auth: Auth, db: SharedDb, since: Since) -> Result<warp::reply::Response, Rejection> {
+    let data = db.read().await;
+    let (key, _) = access(&data, &auth, workspace.as_deref())?;
+    let reply = match data.journals.get(&key) {
+        None => OpsReply {
+            epoch: 0,
+            seq: 0,
+            ops: Vec::new(),
+            base: None,
+        },
+        Some(journal) if journal.epoch == since.epoch => OpsReply {
+            epoch: journal.epoch,
+            seq: journal.ops.len(),
+            ops: journal.ops.get(since.since..).unwrap_or_default().to_vec(),
+            base: None,
+        },
+        Some(journal) => OpsReply {
+            epoch: journal.epoch,
+            seq: journal.ops.len(),
+            ops: journal.ops.clone(),
+            base: journal.base.clone(),
+        },
+    };
+    Ok(json_reply(&reply, StatusCode::OK))
+}
+
+// Merge a client's operations and bring the records up to date with the result
+async fn add_ops(
+    workspace: Option<String>,
+    auth: Auth,
+    db: SharedDb,
+    origin: Option<String>,
+    new_ops: NewOps,
+) -> Result<warp::reply::Response, Rejection> {
+    // Same rule as the record endpoints
+    let bad_url = new_ops.ops.iter().any(|op| {
+        matches!(op, Op::Set { collection: Collection::Outside, field, value, .. }
+            if field == "url" && !value.as_str().is_none_or(is_web_url))
+    });
+    if bad_url {
+        return Err(ApiError::bad_request("The URL must start with http:// or https://").into());
+    }
+    let ((epoch, seq), outside_events, inside_events) = db
+        .write(|data| {
+            let (key, role) = access(data, &auth, workspace.as_deref())?;
+            if role < Role::Editor && !new_ops.ops.is_empty() {
+                return Err(ApiError::forbidden("Viewers cannot change records in this workspace"));
+            }
+            // Started before the records are taken out, so it starts from them
+            journal(data, &key);
+            let mut collections = data.records.remove(&key).unwrap_or_default();
+            let journal = journal(data, &key);
+            journal.add(new_ops.ops);
+            let now = timestamp();
+            let outside_events = settle::<Outside>(journal, &mut collections, Collection::Outside, &auth, &now);
+            let inside_events = settle::<Inside>(journal, &mut collections, Collection::Inside, &auth, &now);
+            let position = (journal.epoch, journal.ops.len());
+            data.records.insert(key, collections);
+            Ok((position, outside_events, inside_events))
+        })
+        .await?;
+
+    for (action, uuid, record) in outside_events {
+        publish(&db, &auth, workspace.as_deref(), action, &uuid, record.as_ref(), origin.clone());
+    }
+    for (action, uuid, record) in inside_events {
+        publish(&db, &auth, workspace.as_deref(), action, &uuid, record.as_ref(), origin.clone());
+    }
+    announce(&db, scope_key(&auth, workspace.as_deref()), Some((epoch, seq)));
+    Ok(json_reply(&serde_json::json!({ "epoch": epoch, "seq": seq }), StatusCode::OK))
+}
+
+// GET/POST <scope>/ops: the merge journal of personal or team workspace records
+pub fn routes(db: SharedDb) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let ops = scope().and(warp::path("ops")).and(warp::path::end());
+    let list_ops = ops
+        .clone()
+        .and(warp::get())
+        .and(authenticated(db.clone()))
+        .and(with_db(db.clone()))
+        .and(warp::query::<Since>())
+        .and_then(list_ops);
+    let add_ops = ops
+        .and(warp::post())
+        .and(authenticated(db.clone()))
+        .and(with_db(db))
+        .and(origin())
+        .and(json_body())
+        .and_then(add_ops);
+    list_ops.or(add_ops)
+}
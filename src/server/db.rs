@@ -0,0 +1,322 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, MutexGuard};
+use crate::frontend::services::crdt::{Doc, Op, Stamp};
+use crate::models::{Inside, Outside};
+use crate::server::ApiError;
+use crate::server::auth::LoginAttempts;
+use crate::server::live::Hub;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct User {
+    pub id: String,
+    pub username: String,
+    // "pbkdf2-sha256$<iterations>$<salt>$<hash>", base64 encoded
+    pub password_hash: String,
+    pub created_at: String,
+}
+
+// A login from the web app; only a hash of the token is kept
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Session {
+    pub token_hash: String,
+    pub user_id: String,
+    // Unix seconds
+    pub expires_at: i64,
+}
+
+// A long-lived token for scripts, named by its owner
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ApiToken {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub token_hash: String,
+    pub created_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<String>,
+}
+
+// Ordered from least to most access
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Viewer,
+    Editor,
+    Owner,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Member {
+    pub user_id: String,
+    pub role: Role,
+}
+
+// A workspace several accounts share, such as a team reading list
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TeamWorkspace {
+    pub id: String,
+    pub name: String,
+    pub created_at: String,
+    pub members: Vec<Member>,
+}
+
+impl TeamWorkspace {
+    pub fn role_of(&self, user_id: &str) -> Option<Role> {
+        self.members.iter().find(|m| m.user_id == user_id).map(|m| m.role)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ShareKind {
+    Outside,
+    Inside,
+}
+
+// A public read-only link to the records of one collection that match a filter
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Share {
+    // Unguessable, and the only thing needed to read the share
+    pub token: String,
+    pub user_id: String,
+    // A team workspace, or the creator's own records when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+    pub kind: ShareKind,
+    pub title: String,
+    // Records must have all of these tags, contain the query and be listed, when set
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub query: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub uuids: Vec<String>,
+    pub created_at: String,
+    // Unix seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Collections {
+    pub outside: Vec<Outside>,
+    pub inside: Vec<Inside>,
+}
+
+// Replica id the server stamps its own merge operations with
+const SERVER_REPLICA: &str = "server";
+
+// Tells operations apart: the stamp of a write, or the characters a removal covers
+fn op_key(op: &Op) -> (Stamp, usize) {
+    match op {
+        Op::Set { stamp, .. } | Op::Delete { stamp, .. } => (stamp.clone(), 0),
+        Op::Insert { id, .. } => (id.clone(), 0),
+        Op::Remove { id, len, .. } => (id.clone(), *len),
+    }
+}
+
+// Operations a journal keeps before they are folded into its base document
+const COMPACT_AFTER: usize = 10_000;
+
+// Every merge operation made to the records of a scope since the last compaction, for clients
+// that merge concurrent edits instead of overwriting them; started the first time one asks.
+//
+// Clients know the journal by its epoch and length. Once it grows long the operations are folded
+// into `base` and a new epoch starts: a client from an earlier epoch gets `base` and the
+// operations after it, and replaces its replica after sending its outbox
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Journal {
+    #[serde(default = "first_epoch")]
+    pub epoch: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<Doc>,
+    pub ops: Vec<Op>,
+    // Rebuilt from `base` and the operations after loading
+    #[serde(skip)]
+    doc: Option<Doc>,
+    #[serde(skip)]
+    keys: Option<HashSet<(Stamp, usize)>>,
+}
+
+// Clients that have not seen a journal yet are at epoch 0
+fn first_epoch() -> u64 {
+    1
+}
+
+impl Default for Journal {
+    fn default() -> Self {
+        Self {
+            epoch: first_epoch(),
+            base: None,
+            ops: Vec::new(),
+            doc: None,
+            keys: None,
+        }
+    }
+}
+
+impl Journal {
+    pub fn doc(&mut self) -> &mut Doc {
+        let (base, ops) = (&self.base, &self.ops);
+        self.doc.get_or_insert_with(|| {
+            let mut doc = base.clone().unwrap_or_else(|| Doc::new(SERVER_REPLICA));
+            doc.apply(ops.iter().cloned());
+            doc
+        })
+    }
+
+    fn keys(&mut self) -> &mut HashSet<(Stamp, usize)> {
+        let ops = &self.ops;
+        self.keys.get_or_insert_with(|| ops.iter().map(op_key).collect())
+    }
+
+    // Keep operations the server made, already applied to `doc`
+    pub fn extend(&mut self, ops: Vec<Op>) {
+        self.keys().extend(ops.iter().map(op_key));
+        self.ops.extend(ops);
+        self.compact();
+    }
+
+    // Merge a client's operations, skipping those already kept, e.g. sent again after a lost reply.
+    // One sent again after a compaction is kept twice, which changes nothing when applied
+    pub fn add(&mut self, ops: Vec<Op>) {
+        let keys = self.keys();
+        let ops: Vec<Op> = ops.into_iter().filter(|op| keys.insert(op_key(op))).collect();
+        self.doc().apply(ops.iter().cloned());
+        self.ops.extend(ops);
+        self.compact();
+    }
+
+    fn compact(&mut self) {
+        if self.ops.len() < COMPACT_AFTER {
+            return;
+        }
+        self.base = Some(self.doc().clone());
+        self.ops.clear();
+        self.keys = Some(HashSet::new());
+        self.epoch += 1;
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Data {
+    pub users: Vec<User>,
+    pub sessions: Vec<Session>,
+    pub tokens: Vec<ApiToken>,
+    #[serde(default)]
+    pub workspaces: Vec<TeamWorkspace>,
+    #[serde(default)]
+    pub shares: Vec<Share>,
+    // Records of each user and each team workspace, keyed by user or workspace id
+    pub records: HashMap<String, Collections>,
+    // Merge journals, keyed like the records
+    #[serde(default)]
+    pub journals: HashMap<String, Journal>,
+}
+
+// Everything the server stores, kept in memory and written to one JSON file
+pub struct Db {
+    path: PathBuf,
+    data: Mutex<Data>,
+    // Connected live clients, told about every record change
+    pub live: Hub,
+    pub logins: LoginAttempts,
+}
+
+pub type SharedDb = Arc<Db>;
+
+impl Db {
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, String> {
+        let path = path.into();
+        let data = load(&path, std::fs::read_to_string(&path))?;
+        Ok(Self { path, data: Mutex::new(data), live: Hub::default(), logins: LoginAttempts::default() })
+    }
+
+    pub async fn read(&self) -> MutexGuard<'_, Data> {
+        self.data.lock().await
+    }
+
+    // Apply a change in place and persist it. A change that fails halfway or cannot be written
+    // is undone by reading back the file, which still holds everything from before it
+    pub async fn write<T>(&self, change: impl FnOnce(&mut Data) -> Result<T, ApiError>) -> Result<T, ApiError> {
+        let mut data = self.data.lock().await;
+        let result = match change(&mut data) {
+            Ok(result) => self.persist(&data).await.map(|()| result),
+            Err(e) => Err(e),
+        };
+        if result.is_err() {
+            match load(&self.path, tokio::fs::read_to_string(&self.path).await) {
+                Ok(saved) => *data = saved,
+                Err(e) => eprintln!("Failed to undo a change: {}", e),
+            }
+        }
+        result
+    }
+
+    // Write to a temporary file first so a crash never leaves a half-written file
+    async fn persist(&self, data: &Data) -> Result<(), ApiError> {
+        let json = serde_json::to_string(data)
+            .map_err(|e| ApiError::internal(format!("Failed to serialize data: {}", e)))?;
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir)
+                .await
+                .map_err(|e| ApiError::internal(format!("Failed to create {}: {}", dir.display(), e)))?;
+        }
+        let temp = self.path.with_extension("json.tmp");
+        tokio::fs::write(&temp, json)
+            .await
+            .map_err(|e| ApiError::internal(format!("Failed to write data: {}", e)))?;
+        tokio::fs::rename(&temp, &self.path)
+            .await
+            .map_err(|e| ApiError::internal(format!("Failed to write data: {}", e)))
+    }
+}
+
+// The stored data, or none yet when the file does not exist
+fn load(path: &Path, json: std::io::Result<String>) -> Result<Data, String> {
+    match json {
+        Ok(json) => serde_json::from_str(&json).map_err(|e| format!("Invalid data file {}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Data::default()),
+        Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+    }
+}
+
+pub fn timestamp() -> String {
+    chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+// An empty database in a file of its own
+#[cfg(test)]
+pub fn scratch() -> SharedDb {
+    let path = std::env::temp_dir().join(format!("relf-test-{}.json", uuid::Uuid::new_v4()));
+    Arc::new(Db::open(path).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use crate::frontend::services::tab_sync::Collection;
+    use super::*;
+
+    #[test]
+    fn long_journal_is_compacted_into_its_base() {
+        let mut journal = Journal::default();
+        let mut client = Doc::new("client");
+        for n in 0..COMPACT_AFTER + 5 {
+            let ops = client.update(Collection::Outside, &[json!({"uuid": "1", "name": "Book", "percentage": n})]);
+            journal.add(ops);
+        }
+        assert_eq!(journal.epoch, 2);
+        assert!(journal.base.is_some());
+        assert!(journal.ops.len() < 10);
+
+        // A restarted server rebuilds the same document from the base and the operations after it
+        let mut loaded: Journal = serde_json::from_str(&serde_json::to_string(&journal).unwrap()).unwrap();
+        assert_eq!(loaded.epoch, 2);
+        let expected: Vec<Value> = client.records(Collection::Outside);
+        assert_eq!(loaded.doc().records::<Value>(Collection::Outside), expected);
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use warp::ws::{Message, WebSocket};
+use warp::{Filter, Rejection, Reply};
+use crate::server::auth::{authenticate, with_db, Auth};
+use crate::server::db::{Role, SharedDb};
+
+// Sent with record changes, and echoed in their events, so clients can skip their own
+pub const ORIGIN_HEADER: &str = "x-relf-client";
+const CHANNEL_CAPACITY: usize = 256;
+const HELLO_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Created,
+    Updated,
+    Deleted,
+}
+
+// A change to a record, pushed to every client watching the records it belongs to
+#[derive(Serialize, Clone, Debug)]
+pub struct RecordEvent {
+    pub action: Action,
+    pub collection: &'static str,
+    pub uuid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub record: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+}
+
+// Who is looking at which record; `record` is empty while on a list
+#[derive(Serialize, Clone, Debug)]
+struct Viewer {
+    username: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    record: Option<String>,
+    editing: bool,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum ServerMessage {
+    Ready { role: Role },
+    Change(RecordEvent),
+    Presence { viewers: Vec<Viewer> },
+    // The merge journal is at `epoch` and has `seq` operations
+    Ops { epoch: u64, seq: usize },
+    // Events were dropped, so the client should fetch the records again
+    Resync,
+    Error { message: String },
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum ClientMessage {
+    // First message of a connection: a session or API token and the records to watch
+    Hello {
+        token: String,
+        #[serde(default)]
+        workspace: Option<String>,
+    },
+    Presence {
+        #[serde(default)]
+        record: Option<String>,
+        #[serde(default)]
+        editing: bool,
+    },
+}
+
+// A message for the clients of one scope: a user id for personal records, or a workspace id
+#[derive(Clone)]
+struct Broadcast {
+    scope: String,
+    json: String,
+}
+
+// Connected live clients and what they are looking at
+pub struct Hub {
+    sender: broadcast::Sender<Broadcast>,
+    presence: Mutex<HashMap<u64, (String, Viewer)>>,
+    next_id: AtomicU64,
+}
+
+impl Default for Hub {
+    fn default() -> Self {
+        Self {
+            sender: broadcast::channel(CHANNEL_CAPACITY).0,
+            presence: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(0),
+        }
+    }
+}
+
+impl Hub {
+    fn send(&self, scope: &str, message: &ServerMessage) {
+        if let Ok(json) = serde_json::to_string(message) {
+            // Fails only when nobody is connected
+            let _ = self.sender.send(Broadcast { scope: scope.to_string(), json });
+        }
+    }
+
+    pub fn publish(&self, scope: &str, event: RecordEvent) {
+        self.send(scope, &ServerMessage::Change(event));
+    }
+
+    pub fn announce(&self, scope: &str, epoch: u64, seq: usize) {
+        self.send(scope, &ServerMessage::Ops { epoch, seq });
+    }
+
+    // Update or clear a connection's presence and tell its scope who is there now
+    fn set_presence(&self, id: u64, scope: &str, viewer: Option<Viewer>) {
+        let viewers = {
+            let mut presence = self.presence.lock().unwrap_or_else(|e| e.into_inner());
+            match viewer {
+                Some(viewer) => presence.insert(id, (scope.to_string(), viewer)),
+                None => presence.remove(&id),
+            };
+            presence.values().filter(|(s, _)| s == scope).map(|(_, v)| v.clone()).collect()
+        };
+        self.send(scope, &ServerMessage::Presence { viewers });
+    }
+}
+
+fn text(message: &ServerMessage) -> Message {
+    Message::text(serde_json::to_string(message).unwrap_or_default())
+}
+
+// The role a user has on the records of a scope, if any
+async fn role_in(db: &SharedDb, auth: &Auth, workspace: Option<&str>) -> Option<Role> {
+    match workspace {
+        None => Some(Role::Owner),
+        Some(id) => db.read().await.workspaces.iter().find(|w| w.id == id)?.role_of(&auth.user_id),
+    }
+}
+
+async fn hello(socket: &mut WebSocket, db: &SharedDb) -> Result<(Auth, Option<String>, Role), String> {
+    let message = tokio::time::timeout(HELLO_TIMEOUT, socket.next())
+        .await
+        .ok()
+        .flatten()
+        .and_then(|m| m.ok())
+        .ok_or_else(|| "Expected a hello message".to_string())?;
+    let Ok(ClientMessage::Hello { token, workspace }) = serde_json::from_str(message.to_str().unwrap_or_default()) else {
+        return Err("Expected a hello message".to_string());
+    };
+    let auth = authenticate(db.clone(), Some(format!("Bearer {}", token)))
+        .await
+        .map_err(|_| "Invalid or expired token".to_string())?;
+    let role = role_in(db, &auth, workspace.as_deref())
+        .await
+        .ok_or_else(|| "Workspace not found".to_string())?;
+    Ok((auth, workspace, role))
+}
+
+async fn connect(mut socket: WebSocket, db: SharedDb) {
+    let (auth, workspace, role) = match hello(&mut socket, &db).await {
+        Ok(joined) => joined,
+        Err(message) => {
+            let _ = socket.send(text(&ServerMessage::Error { message })).await;
+            let _ = socket.close().await;
+            return;
+        }
+    };
+    let scope = workspace.clone().unwrap_or_else(|| auth.user_id.clone());
+    let id = db.live.next_id.fetch_add(1, Ordering::Relaxed);
+    let mut events = db.live.sender.subscribe();
+    let (mut tx, mut rx) = socket.split();
+    if tx.send(text(&ServerMessage::Ready { role })).await.is_err() {
+        return;
+    }
+    let viewer = |record: Option<String>, editing: bool| Viewer {
+        username: auth.username.clone(),
+        record,
+        editing,
+    };
+    db.live.set_presence(id, &scope, Some(viewer(None, false)));
+
+    loop {
+        tokio::select! {
+            message = rx.next() => match message {
+                Some(Ok(message)) if message.is_close() => break,
+                Some(Ok(message)) => {
+                    if let Ok(ClientMessage::Presence { record, editing }) = serde_json::from_str(message.to_str().unwrap_or_default()) {
+                        db.live.set_presence(id, &scope, Some(viewer(record, editing)));
+                    }
+                }
+                _ => break,
+            },
+            event = events.recv() => {
+                let message = match event {
+                    Ok(broadcast) if broadcast.scope == scope => {
+                        // Members removed from a workspace stop hearing about it
+                        if role_in(&db, &auth, workspace.as_deref()).await.is_none() {
+                            let _ = tx.send(text(&ServerMessage::Error { message: "Workspace not found".to_string() })).await;
+                            break;
+                        }
+                        Message::text(broadcast.json)
+                    }
+                    Ok(_) => continue,
+                    Err(broadcast::error::RecvError::Lagged(_)) => text(&ServerMessage::Resync),
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                if tx.send(message).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    db.live.set_presence(id, &scope, None);
+}
+
+// GET /api/live, upgraded to a WebSocket
+pub fn routes(db: SharedDb) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    warp::path("api")
+        .and(warp::path("live"))
+        .and(warp::path::end())
+        .and(warp::ws())
+        .and(with_db(db))
+        .map(|ws: warp::ws::Ws, db: SharedDb| ws.on_upgrade(move |socket| connect(socket, db)))
+}
+
+pub fn origin() -> impl Filter<Extract = (Option<String>,), Error = Rejection> + Clone {
+    warp::header::optional::<String>(ORIGIN_HEADER)
+}
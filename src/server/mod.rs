@@ -0,0 +1,114 @@
+// JSON API for accounts, shared workspaces and their Outside and Inside records,
+// and the public pages of share links
+mod auth;
+mod crdt;
+mod db;
+mod live;
+mod records;
+mod shares;
+mod workspaces;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use warp::http::StatusCode;
+use warp::{Filter, Rejection, Reply};
+
+pub use db::{Db, SharedDb};
+
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self { status, message: message.into() }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, message)
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, message)
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::CONFLICT, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+}
+
+// Warp turns any `Reject` into a `Rejection`, so handlers can use `?`
+impl warp::reject::Reject for ApiError {}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+// Request bodies are capped so one request cannot exhaust memory
+const MAX_BODY_BYTES: u64 = 4 * 1024 * 1024;
+
+pub fn json_body<T: DeserializeOwned + Send>() -> impl Filter<Extract = (T,), Error = Rejection> + Clone {
+    warp::body::content_length_limit(MAX_BODY_BYTES).and(warp::body::json())
+}
+
+pub fn json_reply<T: Serialize>(value: &T, status: StatusCode) -> warp::reply::Response {
+    warp::reply::with_status(warp::reply::json(value), status).into_response()
+}
+
+// The status a handler answered with, whether it replied or was rejected
+#[cfg(test)]
+fn status_of(result: Result<warp::reply::Response, Rejection>) -> StatusCode {
+    match result {
+        Ok(response) => response.status(),
+        Err(rejection) => rejection.find::<ApiError>().map_or(StatusCode::INTERNAL_SERVER_ERROR, |e| e.status),
+    }
+}
+
+// Every /api route; anything else under /api is a JSON 404
+pub fn api(db: SharedDb) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let unknown = warp::path("api").map(|| {
+        json_reply(&ErrorBody { error: "Unknown API route".to_string() }, StatusCode::NOT_FOUND)
+    });
+    auth::routes(db.clone())
+        .or(workspaces::routes(db.clone()))
+        .or(shares::routes(db.clone()))
+        .or(live::routes(db.clone()))
+        .or(crdt::routes(db.clone()))
+        .or(records::routes(db))
+        .recover(handle_rejection)
+        .or(unknown)
+}
+
+// Pages the server renders itself, outside the SPA
+pub fn pages(db: SharedDb) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    shares::page_routes(db)
+}
+
+// Turn API errors into JSON; other rejections fall through to the static routes
+async fn handle_rejection(err: Rejection) -> Result<warp::reply::Response, Rejection> {
+    let error = if let Some(error) = err.find::<ApiError>() {
+        ApiError::new(error.status, error.message.clone())
+    } else if let Some(error) = err.find::<warp::filters::body::BodyDeserializeError>() {
+        ApiError::bad_request(format!("Invalid request body: {}", error))
+    } else if err.find::<warp::reject::PayloadTooLarge>().is_some() {
+        ApiError::new(StatusCode::PAYLOAD_TOO_LARGE, "Request body is too large")
+    } else {
+        return Err(err);
+    };
+    Ok(json_reply(&ErrorBody { error: error.message }, error.status))
+}
@@ -0,0 +1,294 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use warp::http::StatusCode;
+use warp::{Filter, Rejection, Reply};
+use crate::frontend::services::markdown::is_web_url;
+use crate::models::{Inside, Outside};
+use crate::server::auth::{authenticated, with_db, Auth};
+use crate::server::db::{timestamp, Collections, Data, Role, SharedDb};
+use crate::server::crdt::{announce, follow};
+use crate::server::live::{origin, Action, RecordEvent};
+use crate::server::{json_body, json_reply, ApiError};
+
+// An Outside or Inside record as the API stores it
+pub trait Record: Serialize + DeserializeOwned + Clone + Send + Sync + 'static {
+    // Path segment under /api and name in error messages
+    const PATH: &'static str;
+    const NAME: &'static str;
+
+    fn uuid(&self) -> &str;
+    fn set_uuid(&mut self, uuid: String);
+    fn collection(collections: &mut Collections) -> &mut Vec<Self>;
+    // Fill in server-managed fields from the version being replaced, if any
+    fn stamp(&mut self, previous: Option<&Self>, now: &str, username: &str);
+    // Clean up other records after this one was deleted
+    fn deleted(_collections: &mut Collections, _uuid: &str) {}
+    // Reject what clients must not store
+    fn validate(&self) -> Result<(), ApiError> {
+        Ok(())
+    }
+}
+
+impl Record for Outside {
+    const PATH: &'static str = "outside";
+    const NAME: &'static str = "Outside";
+
+    fn uuid(&self) -> &str {
+        &self.uuid
+    }
+
+    fn set_uuid(&mut self, uuid: String) {
+        self.uuid = uuid;
+    }
+
+    fn collection(collections: &mut Collections) -> &mut Vec<Self> {
+        &mut collections.outside
+    }
+
+    fn stamp(&mut self, previous: Option<&Self>, now: &str, username: &str) {
+        self.percentage = self.percentage.map(|p| p.clamp(0, 100));
+        self.created_at = previous.and_then(|p| p.created_at.clone()).or_else(|| Some(now.to_string()));
+        self.updated_at = Some(now.to_string());
+        self.created_by = previous.and_then(|p| p.created_by.clone()).or_else(|| Some(username.to_string()));
+        self.updated_by = Some(username.to_string());
+    }
+
+    // Drop relations to the deleted resource
+    fn deleted(collections: &mut Collections, uuid: &str) {
+        for inside in collections.inside.iter_mut() {
+            inside.outside_uuids.retain(|u| u != uuid);
+        }
+    }
+
+    // Links are opened by everyone who can see the record
+    fn validate(&self) -> Result<(), ApiError> {
+        if is_web_url(&self.url) {
+            Ok(())
+        } else {
+            Err(ApiError::bad_request("The URL must start with http:// or https://"))
+        }
+    }
+}
+
+impl Record for Inside {
+    const PATH: &'static str = "inside";
+    const NAME: &'static str = "Inside";
+
+    fn uuid(&self) -> &str {
+        &self.uuid
+    }
+
+    fn set_uuid(&mut self, uuid: String) {
+        self.uuid = uuid;
+    }
+
+    fn collection(collections: &mut Collections) -> &mut Vec<Self> {
+        &mut collections.inside
+    }
+
+    fn stamp(&mut self, previous: Option<&Self>, now: &str, username: &str) {
+        self.updated_at = Some(now.to_string());
+        self.created_by = previous.and_then(|p| p.created_by.clone()).or_else(|| Some(username.to_string()));
+        self.updated_by = Some(username.to_string());
+    }
+}
+
+// Key of the user's own records, or of a team workspace's when the user is a member of it
+pub fn access(data: &Data, auth: &Auth, workspace: Option<&str>) -> Result<(String, Role), ApiError> {
+    match workspace {
+        None => Ok((auth.user_id.clone(), Role::Owner)),
+        Some(id) => {
+            // Outsiders cannot tell a workspace they are not in from one that does not exist
+            let role = data
+                .workspaces
+                .iter()
+                .find(|w| w.id == id)
+                .and_then(|w| w.role_of(&auth.user_id))
+                .ok_or_else(|| ApiError::not_found("Workspace not found"))?;
+            Ok((id.to_string(), role))
+        }
+    }
+}
+
+fn collections<'a>(data: &'a mut Data, auth: &Auth, workspace: Option<&str>) -> Result<(&'a mut Collections, Role), ApiError> {
+    let (key, role) = access(data, auth, workspace)?;
+    Ok((data.records.entry(key).or_default(), role))
+}
+
+// Adding, changing and deleting records needs at least the editor role
+fn editable<'a>(data: &'a mut Data, auth: &Auth, workspace: Option<&str>) -> Result<&'a mut Collections, ApiError> {
+    let (collections, role) = collections(data, auth, workspace)?;
+    if role < Role::Editor {
+        return Err(ApiError::forbidden("Viewers cannot change records in this workspace"));
+    }
+    Ok(collections)
+}
+
+// Records of a team workspace are kept under its id, personal ones under the user's
+pub fn scope_key<'a>(auth: &'a Auth, workspace: Option<&'a str>) -> &'a str {
+    workspace.unwrap_or(&auth.user_id)
+}
+
+// Tell live clients of the same records about a change
+pub fn publish<R: Record>(db: &SharedDb, auth: &Auth, workspace: Option<&str>, action: Action, uuid: &str, record: Option<&R>, origin: Option<String>) {
+    db.live.publish(scope_key(auth, workspace), RecordEvent {
+        action,
+        collection: R::PATH,
+        uuid: uuid.to_string(),
+        record: record.and_then(|r| serde_json::to_value(r).ok()),
+        origin,
+    });
+}
+
+fn not_found<R: Record>() -> ApiError {
+    ApiError::not_found(format!("{} item not found", R::NAME))
+}
+
+async fn list<R: Record>(workspace: Option<String>, auth: Auth, db: SharedDb) -> Result<warp::reply::Response, Rejection> {
+    let mut data = db.read().await;
+    let (collections, _) = collections(&mut data, &auth, workspace.as_deref())?;
+    let records = R::collection(collections).clone();
+    Ok(json_reply(&records, StatusCode::OK))
+}
+
+async fn get<R: Record>(workspace: Option<String>, uuid: String, auth: Auth, db: SharedDb) -> Result<warp::reply::Response, Rejection> {
+    let mut data = db.read().await;
+    let (collections, _) = collections(&mut data, &auth, workspace.as_deref())?;
+    let record = R::collection(collections)
+        .iter()
+        .find(|r| r.uuid() == uuid)
+        .cloned()
+        .ok_or_else(not_found::<R>)?;
+    Ok(json_reply(&record, StatusCode::OK))
+}
+
+// New records get a fresh UUID unless the client chose an unused one
+async fn create<R: Record>(
+    workspace: Option<String>,
+    auth: Auth,
+    db: SharedDb,
+    origin: Option<String>,
+    mut record: R,
+) -> Result<warp::reply::Response, Rejection> {
+    record.validate()?;
+    let (record, merged) = db
+        .write(|data| {
+            let records = R::collection(editable(data, &auth, workspace.as_deref())?);
+            if record.uuid().trim().is_empty() || records.iter().any(|r| r.uuid() == record.uuid()) {
+                record.set_uuid(uuid::Uuid::new_v4().to_string());
+            }
+            record.stamp(None, &timestamp(), &auth.username);
+            records.push(record.clone());
+            Ok((record, follow(data, scope_key(&auth, workspace.as_deref()))))
+        })
+        .await?;
+    publish(&db, &auth, workspace.as_deref(), Action::Created, record.uuid(), Some(&record), origin);
+    announce(&db, scope_key(&auth, workspace.as_deref()), merged);
+    Ok(json_reply(&record, StatusCode::CREATED))
+}
+
+async fn update<R: Record>(
+    workspace: Option<String>,
+    uuid: String,
+    auth: Auth,
+    db: SharedDb,
+    origin: Option<String>,
+    mut record: R,
+) -> Result<warp::reply::Response, Rejection> {
+    record.validate()?;
+    let (record, merged) = db
+        .write(|data| {
+            let records = R::collection(editable(data, &auth, workspace.as_deref())?);
+            let index = records.iter().position(|r| r.uuid() == uuid).ok_or_else(not_found::<R>)?;
+            record.set_uuid(uuid);
+            record.stamp(Some(&records[index]), &timestamp(), &auth.username);
+            records[index] = record.clone();
+            Ok((record, follow(data, scope_key(&auth, workspace.as_deref()))))
+        })
+        .await?;
+    publish(&db, &auth, workspace.as_deref(), Action::Updated, record.uuid(), Some(&record), origin);
+    announce(&db, scope_key(&auth, workspace.as_deref()), merged);
+    Ok(json_reply(&record, StatusCode::OK))
+}
+
+async fn delete<R: Record>(
+    workspace: Option<String>,
+    uuid: String,
+    auth: Auth,
+    db: SharedDb,
+    origin: Option<String>,
+) -> Result<warp::reply::Response, Rejection> {
+    let merged = db
+        .write(|data| {
+            let collections = editable(data, &auth, workspace.as_deref())?;
+            let records = R::collection(collections);
+            let count = records.len();
+            records.retain(|r| r.uuid() != uuid);
+            if records.len() == count {
+                return Err(not_found::<R>());
+            }
+            R::deleted(collections, &uuid);
+            Ok(follow(data, scope_key(&auth, workspace.as_deref())))
+        })
+        .await?;
+    publish::<R>(&db, &auth, workspace.as_deref(), Action::Deleted, &uuid, None, origin);
+    announce(&db, scope_key(&auth, workspace.as_deref()), merged);
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+// Personal records live under /api, a team workspace's under /api/workspaces/<id>
+pub fn scope() -> impl Filter<Extract = (Option<String>,), Error = Rejection> + Clone {
+    let team = warp::path("api")
+        .and(warp::path("workspaces"))
+        .and(warp::path::param::<String>())
+        .map(Some);
+    let personal = warp::path("api").map(|| None);
+    team.or(personal).unify()
+}
+
+// GET/POST <scope>/<path> and GET/PUT/DELETE <scope>/<path>/<uuid>
+fn record_routes<R: Record>(db: SharedDb) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let collection = scope().and(warp::path(R::PATH));
+    let list = collection
+        .clone()
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(authenticated(db.clone()))
+        .and(with_db(db.clone()))
+        .and_then(list::<R>);
+    let create = collection
+        .clone()
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(authenticated(db.clone()))
+        .and(with_db(db.clone()))
+        .and(origin())
+        .and(json_body())
+        .and_then(create::<R>);
+    let item = collection.and(warp::path::param::<String>()).and(warp::path::end());
+    let get = item
+        .clone()
+        .and(warp::get())
+        .and(authenticated(db.clone()))
+        .and(with_db(db.clone()))
+        .and_then(get::<R>);
+    let update = item
+        .clone()
+        .and(warp::put())
+        .and(authenticated(db.clone()))
+        .and(with_db(db.clone()))
+        .and(origin())
+        .and(json_body())
+        .and_then(update::<R>);
+    let delete = item
+        .and(warp::delete())
+        .and(authenticated(db.clone()))
+        .and(with_db(db))
+        .and(origin())
+        .and_then(delete::<R>);
+    list.or(create).or(get).or(update).or(delete)
+}
+
+pub fn routes(db: SharedDb) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    record_routes::<Outside>(db.clone()).or(record_routes::<Inside>(db))
+}
@@ -0,0 +1,520 @@
+use serde::{Deserialize, Serialize};
+use warp::http::StatusCode;
+use warp::{Filter, Rejection, Reply};
+use crate::frontend::services::highlight::escape_html;
+use crate::frontend::services::markdown::{render, safe_url};
+use crate::frontend::services::tags::{normalize_tag, parse_tags};
+use crate::frontend::services::view::domain_of;
+use crate::models::{Inside, Outside};
+use crate::server::auth::{authenticated, now_secs, random_token, with_db, Auth};
+use crate::server::db::{timestamp, Collections, Data, Role, Share, ShareKind, SharedDb};
+use crate::server::{json_body, json_reply, ApiError};
+
+const MAX_EXPIRY_DAYS: i64 = 3650;
+
+#[derive(Deserialize)]
+struct NewShare {
+    kind: ShareKind,
+    title: String,
+    #[serde(default)]
+    workspace: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    query: String,
+    #[serde(default)]
+    uuids: Vec<String>,
+    // Never expires when absent
+    #[serde(default)]
+    expires_in_days: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct ShareInfo {
+    // Path of the read-only page
+    url: String,
+    #[serde(flatten)]
+    share: Share,
+}
+
+impl ShareInfo {
+    fn from(share: &Share) -> Self {
+        Self {
+            url: format!("/share/{}", share.token),
+            share: share.clone(),
+        }
+    }
+}
+
+// What the public sees: records without account names or links to unshared records
+#[derive(Serialize)]
+struct Published {
+    title: String,
+    kind: ShareKind,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    outside: Vec<Outside>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    inside: Vec<Inside>,
+}
+
+fn matches(share: &Share, uuid: &str, text: &str, context: &str) -> bool {
+    let tags = parse_tags(context);
+    (share.uuids.is_empty() || share.uuids.iter().any(|u| u == uuid))
+        && share.tags.iter().all(|t| tags.contains(t))
+        && (share.query.is_empty() || text.to_lowercase().contains(&share.query.to_lowercase()))
+}
+
+// The records a share reads, as long as its creator can still read them
+fn source<'a>(data: &'a Data, share: &Share) -> Option<&'a Collections> {
+    let key = match &share.workspace {
+        None => &share.user_id,
+        Some(id) => {
+            data.workspaces.iter().find(|w| &w.id == id)?.role_of(&share.user_id)?;
+            id
+        }
+    };
+    data.records.get(key)
+}
+
+// The records behind a live share; unknown, expired and orphaned shares look the same
+fn publish(data: &Data, token: &str) -> Result<Published, ApiError> {
+    let now = now_secs();
+    let share = data
+        .shares
+        .iter()
+        .find(|s| s.token == token && s.expires_at.is_none_or(|at| at > now))
+        .ok_or_else(|| ApiError::not_found("Share link not found or expired"))?;
+    let collections = source(data, share).cloned().unwrap_or_default();
+    let mut published = Published {
+        title: share.title.clone(),
+        kind: share.kind,
+        outside: Vec::new(),
+        inside: Vec::new(),
+    };
+    match share.kind {
+        ShareKind::Outside => {
+            published.outside = collections
+                .outside
+                .into_iter()
+                .filter(|o| matches(share, &o.uuid, &format!("{} {} {}", o.name, o.context, o.url), &o.context))
+                .map(|o| Outside { created_by: None, updated_by: None, ..o })
+                .collect();
+        }
+        ShareKind::Inside => {
+            published.inside = collections
+                .inside
+                .into_iter()
+                .filter(|i| matches(share, &i.uuid, &i.context, &i.context))
+                .map(|i| Inside { created_by: None, updated_by: None, outside_uuids: Vec::new(), ..i })
+                .collect();
+        }
+    }
+    Ok(published)
+}
+
+async fn list_shares(auth: Auth, db: SharedDb) -> Result<warp::reply::Response, Rejection> {
+    let shares: Vec<ShareInfo> = db
+        .read()
+        .await
+        .shares
+        .iter()
+        .filter(|s| s.user_id == auth.user_id)
+        .map(ShareInfo::from)
+        .collect();
+    Ok(json_reply(&shares, StatusCode::OK))
+}
+
+// Publishing a team workspace's records needs at least the editor role
+async fn create_share(auth: Auth, db: SharedDb, new_share: NewShare) -> Result<warp::reply::Response, Rejection> {
+    let title = new_share.title.trim().to_string();
+    if title.is_empty() {
+        return Err(ApiError::bad_request("Share title must not be empty").into());
+    }
+    if new_share.expires_in_days.is_some_and(|days| !(1..=MAX_EXPIRY_DAYS).contains(&days)) {
+        return Err(ApiError::bad_request(format!("Expiry must be between 1 and {} days", MAX_EXPIRY_DAYS)).into());
+    }
+    let now = now_secs();
+    let share = Share {
+        token: random_token(),
+        user_id: auth.user_id.clone(),
+        workspace: new_share.workspace,
+        kind: new_share.kind,
+        title,
+        tags: new_share.tags.iter().filter_map(|t| normalize_tag(t)).collect(),
+        query: new_share.query.trim().to_string(),
+        uuids: new_share.uuids,
+        created_at: timestamp(),
+        expires_at: new_share.expires_in_days.map(|days| now + days * 24 * 60 * 60),
+    };
+    let info = ShareInfo::from(&share);
+    db.write(|data| {
+        if let Some(id) = &share.workspace {
+            let role = data
+                .workspaces
+                .iter()
+                .find(|w| &w.id == id)
+                .and_then(|w| w.role_of(&auth.user_id))
+                .ok_or_else(|| ApiError::not_found("Workspace not found"))?;
+            if role < Role::Editor {
+                return Err(ApiError::forbidden("Viewers cannot share records of this workspace"));
+            }
+        }
+        data.shares.retain(|s| s.expires_at.is_none_or(|at| at > now));
+        data.shares.push(share);
+        Ok(())
+    })
+    .await?;
+    Ok(json_reply(&info, StatusCode::CREATED))
+}
+
+// The creator and owners of the shared workspace may revoke a link
+async fn revoke_share(token: String, auth: Auth, db: SharedDb) -> Result<warp::reply::Response, Rejection> {
+    db.write(|data| {
+        let index = data
+            .shares
+            .iter()
+            .position(|s| {
+                s.token == token
+                    && (s.user_id == auth.user_id
+                        || s.workspace.as_ref().is_some_and(|id| {
+                            data.workspaces
+                                .iter()
+                                .any(|w| &w.id == id && w.role_of(&auth.user_id) == Some(Role::Owner))
+                        }))
+            })
+            .ok_or_else(|| ApiError::not_found("Share link not found"))?;
+        data.shares.remove(index);
+        Ok(())
+    })
+    .await?;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+async fn public_share(token: String, db: SharedDb) -> Result<warp::reply::Response, Rejection> {
+    let published = publish(&*db.read().await, &token)?;
+    Ok(json_reply(&published, StatusCode::OK))
+}
+
+fn outside_html(outside: &Outside) -> String {
+    let mut meta: Vec<String> = Vec::new();
+    let domain = domain_of(&outside.url);
+    if !domain.is_empty() {
+        meta.push(escape_html(&domain));
+    }
+    if let Some(p) = outside.percentage {
+        meta.push(format!("{}%", p));
+    }
+    if let Some(status) = outside.status {
+        meta.push(status.label().to_string());
+    }
+    let name = escape_html(&outside.name);
+    let heading = if outside.url.trim().is_empty() {
+        name
+    } else {
+        format!(
+            "<a href=\"{}\" target=\"_blank\" rel=\"noopener noreferrer\">{}</a>",
+            escape_html(&safe_url(&outside.url)),
+            name
+        )
+    };
+    format!(
+        "<article><h2>{}</h2><p class=\"meta\">{}</p><div class=\"markdown\">{}</div></article>",
+        heading,
+        meta.join(" · "),
+        render(&outside.context, &|_| None)
+    )
+}
+
+fn inside_html(inside: &Inside) -> String {
+    format!(
+        "<article><p class=\"meta\">{}</p><div class=\"markdown\">{}</div></article>",
+        escape_html(&inside.date_label()),
+        render(&inside.context, &|_| None)
+    )
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<meta name="robots" content="noindex">
+<title>{title} · relf</title>
+<style>
+body {{ margin: 0; font-family: system-ui, sans-serif; line-height: 1.5; color: #222; background: #f6f6f4; }}
+main {{ max-width: 760px; margin: 0 auto; padding: 24px 16px; }}
+article {{ background: #fff; border-radius: 8px; padding: 12px 16px; margin: 12px 0; box-shadow: 0 1px 3px rgba(0, 0, 0, 0.08); }}
+h2 {{ font-size: 1.1em; margin: 0; }}
+a {{ color: #2a6fdb; }}
+.meta {{ color: #777; font-size: 0.85em; margin: 4px 0; }}
+.markdown pre {{ overflow-x: auto; background: #f3f3f3; padding: 8px; border-radius: 4px; }}
+footer {{ color: #999; font-size: 0.8em; text-align: center; margin-top: 24px; }}
+</style>
+</head>
+<body>
+<main>
+{body}
+<footer>Shared read-only from relf</footer>
+</main>
+</body>
+</html>
+"#,
+        title = escape_html(title),
+        body = body
+    )
+}
+
+// The read-only page; never cached so revoked links disappear at once
+async fn share_page(token: String, db: SharedDb) -> Result<warp::reply::Response, Rejection> {
+    let (html, status) = match publish(&*db.read().await, &token) {
+        Ok(published) => {
+            let items: Vec<String> = match published.kind {
+                ShareKind::Outside => published.outside.iter().map(outside_html).collect(),
+                ShareKind::Inside => published.inside.iter().map(inside_html).collect(),
+            };
+            let body = format!(
+                "<h1>{}</h1><p class=\"meta\">{} items</p>{}",
+                escape_html(&published.title),
+                items.len(),
+                items.join("\n")
+            );
+            (page(&published.title, &body), StatusCode::OK)
+        }
+        Err(error) => (page("Not found", &format!("<h1>{}</h1>", escape_html(&error.message))), error.status),
+    };
+    let reply = warp::reply::with_status(warp::reply::html(html), status);
+    Ok(warp::reply::with_header(reply, "cache-control", "no-store").into_response())
+}
+
+// /api/shares for their creators and /api/public/<token> for everyone
+pub fn routes(db: SharedDb) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let shares = warp::path("api").and(warp::path("shares"));
+    let list_shares = shares
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(authenticated(db.clone()))
+        .and(with_db(db.clone()))
+        .and_then(list_shares);
+    let create_share = shares
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(authenticated(db.clone()))
+        .and(with_db(db.clone()))
+        .and(json_body())
+        .and_then(create_share);
+    let revoke_share = shares
+        .and(warp::path::param())
+        .and(warp::path::end())
+        .and(warp::delete())
+        .and(authenticated(db.clone()))
+        .and(with_db(db.clone()))
+        .and_then(revoke_share);
+    let public_share = warp::path("api")
+        .and(warp::path("public"))
+        .and(warp::path::param())
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(with_db(db))
+        .and_then(public_share);
+    list_shares.or(create_share).or(revoke_share).or(public_share)
+}
+
+// GET /share/<token>, the page a share link opens
+pub fn page_routes(db: SharedDb) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    warp::path("share")
+        .and(warp::path::param())
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(with_db(db))
+        .and_then(share_page)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::server::db::{scratch, Member, TeamWorkspace};
+    use crate::server::status_of;
+    use super::*;
+
+    fn outside(uuid: &str, name: &str, context: &str, url: &str) -> Outside {
+        Outside {
+            uuid: uuid.to_string(),
+            name: name.to_string(),
+            context: context.to_string(),
+            url: url.to_string(),
+            created_by: Some("ann".to_string()),
+            ..Outside::default()
+        }
+    }
+
+    fn share(token: &str, workspace: Option<&str>) -> Share {
+        Share {
+            token: token.to_string(),
+            user_id: "ed".to_string(),
+            workspace: workspace.map(str::to_string),
+            kind: ShareKind::Outside,
+            title: "Reading".to_string(),
+            tags: Vec::new(),
+            query: String::new(),
+            uuids: Vec::new(),
+            created_at: timestamp(),
+            expires_at: None,
+        }
+    }
+
+    // Ed's own records and a team workspace owned by ann that ed edits and vic views
+    async fn shared() -> SharedDb {
+        let db = scratch();
+        db.write(|data| {
+            data.workspaces.push(TeamWorkspace {
+                id: "team".to_string(),
+                name: "Team".to_string(),
+                created_at: timestamp(),
+                members: [("ann", Role::Owner), ("ed", Role::Editor), ("vic", Role::Viewer)]
+                    .into_iter()
+                    .map(|(user_id, role)| Member { user_id: user_id.to_string(), role })
+                    .collect(),
+            });
+            data.records.entry("ed".to_string()).or_default().outside = vec![
+                outside("1", "Rust book", "A #rust classic", "https://example.com/rust"),
+                outside("2", "Go book", "About #go", ""),
+            ];
+            data.records.entry("team".to_string()).or_default().outside =
+                vec![outside("3", "Team book", "", "")];
+            Ok(())
+        })
+        .await
+        .unwrap();
+        db
+    }
+
+    async fn add(db: &SharedDb, share: Share) {
+        db.write(|data| {
+            data.shares.push(share);
+            Ok(())
+        })
+        .await
+        .unwrap();
+    }
+
+    async fn published(db: &SharedDb, token: &str) -> Result<Vec<String>, StatusCode> {
+        publish(&*db.read().await, token)
+            .map(|p| p.outside.into_iter().map(|o| o.uuid).collect())
+            .map_err(|e| e.status)
+    }
+
+    #[test]
+    fn filters_must_all_match() {
+        let filtered = Share {
+            tags: vec!["rust".to_string()],
+            query: "BOOK".to_string(),
+            uuids: vec!["1".to_string()],
+            ..share("t", None)
+        };
+        assert!(matches(&filtered, "1", "Rust book", "A #rust classic"));
+        assert!(!matches(&filtered, "2", "Rust book", "A #rust classic"));
+        assert!(!matches(&filtered, "1", "Rust guide", "A #rust classic"));
+        assert!(!matches(&filtered, "1", "Rust book", "A rust classic"));
+        assert!(matches(&share("t", None), "9", "", ""));
+    }
+
+    #[tokio::test]
+    async fn shares_publish_matching_records_without_account_names() {
+        let db = shared().await;
+        add(&db, Share { tags: vec!["rust".to_string()], ..share("rust", None) }).await;
+        assert_eq!(published(&db, "rust").await, Ok(vec!["1".to_string()]));
+        let data = db.read().await;
+        assert!(publish(&data, "rust").unwrap().outside.iter().all(|o| o.created_by.is_none()));
+    }
+
+    #[tokio::test]
+    async fn expired_and_unknown_shares_are_not_found() {
+        let db = shared().await;
+        add(&db, Share { expires_at: Some(now_secs() - 1), ..share("old", None) }).await;
+        add(&db, Share { expires_at: Some(now_secs() + 60), ..share("new", None) }).await;
+        assert_eq!(published(&db, "old").await, Err(StatusCode::NOT_FOUND));
+        assert_eq!(published(&db, "missing").await, Err(StatusCode::NOT_FOUND));
+        assert_eq!(published(&db, "new").await.map(|uuids| uuids.len()), Ok(2));
+    }
+
+    #[tokio::test]
+    async fn leaving_a_workspace_empties_its_shares() {
+        let db = shared().await;
+        add(&db, share("team", Some("team"))).await;
+        assert_eq!(published(&db, "team").await, Ok(vec!["3".to_string()]));
+        db.write(|data| {
+            data.workspaces[0].members.retain(|m| m.user_id != "ed");
+            Ok(())
+        })
+        .await
+        .unwrap();
+        assert_eq!(published(&db, "team").await, Ok(Vec::new()));
+    }
+
+    #[tokio::test]
+    async fn only_creators_and_workspace_owners_revoke() {
+        let db = shared().await;
+        add(&db, share("mine", None)).await;
+        add(&db, share("team", Some("team"))).await;
+        for (token, user_id) in [("mine", "ann"), ("team", "vic"), ("mine", "eve")] {
+            assert_eq!(status_of(revoke_share(token.into(), Auth::of(user_id), db.clone()).await), StatusCode::NOT_FOUND);
+        }
+        assert_eq!(status_of(revoke_share("team".into(), Auth::of("ann"), db.clone()).await), StatusCode::NO_CONTENT);
+        assert_eq!(status_of(revoke_share("mine".into(), Auth::of("ed"), db.clone()).await), StatusCode::NO_CONTENT);
+        assert_eq!(published(&db, "mine").await, Err(StatusCode::NOT_FOUND));
+        assert_eq!(status_of(public_share("team".into(), db.clone()).await), StatusCode::NOT_FOUND);
+
+        let response = share_page("mine".into(), db.clone()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()["cache-control"], "no-store");
+    }
+
+    #[tokio::test]
+    async fn viewers_cannot_share_and_expiry_is_bounded() {
+        let db = shared().await;
+        let new_share = |workspace: Option<&str>, expires_in_days: Option<i64>| NewShare {
+            kind: ShareKind::Outside,
+            title: "Reading".to_string(),
+            workspace: workspace.map(str::to_string),
+            tags: Vec::new(),
+            query: String::new(),
+            uuids: Vec::new(),
+            expires_in_days,
+        };
+        assert_eq!(status_of(create_share(Auth::of("vic"), db.clone(), new_share(Some("team"), None)).await), StatusCode::FORBIDDEN);
+        assert_eq!(status_of(create_share(Auth::of("eve"), db.clone(), new_share(Some("team"), None)).await), StatusCode::NOT_FOUND);
+        assert_eq!(status_of(create_share(Auth::of("ed"), db.clone(), new_share(None, Some(0))).await), StatusCode::BAD_REQUEST);
+        assert_eq!(status_of(create_share(Auth::of("ed"), db.clone(), new_share(None, Some(MAX_EXPIRY_DAYS + 1))).await), StatusCode::BAD_REQUEST);
+        assert_eq!(status_of(create_share(Auth::of("ed"), db.clone(), new_share(Some("team"), Some(7))).await), StatusCode::CREATED);
+        let expires_at = db.read().await.shares[0].expires_at.unwrap();
+        assert!((expires_at - now_secs() - 7 * 24 * 60 * 60).abs() < 60);
+    }
+
+    #[test]
+    fn record_text_is_escaped_and_script_links_dropped() {
+        let html = outside_html(&outside("1", "<script>alert(1)</script>", "<img src=x onerror=alert(1)>", "javascript:alert(1)"));
+        assert!(!html.contains("<script>"));
+        assert!(!html.contains("<img"));
+        assert!(!html.contains("javascript:"));
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+
+        let html = outside_html(&outside("1", "Book", "", "https://example.com/?a=1&b=\"2\""));
+        assert!(html.contains("href=\"https://example.com/?a=1&amp;b=&quot;2&quot;\""));
+
+        let inside = Inside { unreadable_date: Some("<b>someday</b>".to_string()), ..Inside::default() };
+        assert!(inside_html(&inside).contains("&lt;b&gt;someday&lt;/b&gt;"));
+    }
+
+    #[tokio::test]
+    async fn share_title_is_escaped_on_the_page() {
+        let db = shared().await;
+        add(&db, Share { title: "</title><script>alert(1)</script>".to_string(), ..share("page", None) }).await;
+        let response = share_page("page".into(), db.clone()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = warp::hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(!body.contains("<script>"));
+        assert!(body.contains("<title>&lt;/title&gt;&lt;script&gt;"));
+    }
+}
@@ -0,0 +1,412 @@
+use serde::{Deserialize, Serialize};
+use warp::http::StatusCode;
+use warp::{Filter, Rejection, Reply};
+use crate::server::auth::{authenticated, with_db, Auth};
+use crate::server::db::{timestamp, Data, Member, Role, SharedDb, TeamWorkspace};
+use crate::server::{json_body, json_reply, ApiError};
+
+#[derive(Deserialize)]
+struct NewWorkspace {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct NewMember {
+    username: String,
+    role: Role,
+}
+
+#[derive(Deserialize)]
+struct RoleChange {
+    role: Role,
+}
+
+#[derive(Serialize)]
+struct MemberInfo {
+    user_id: String,
+    username: String,
+    role: Role,
+}
+
+#[derive(Serialize)]
+struct WorkspaceInfo {
+    id: String,
+    name: String,
+    created_at: String,
+    // The role of the requesting user
+    role: Role,
+    members: Vec<MemberInfo>,
+}
+
+impl WorkspaceInfo {
+    fn from(data: &Data, workspace: &TeamWorkspace, user_id: &str) -> Self {
+        let members = workspace
+            .members
+            .iter()
+            .map(|m| MemberInfo {
+                user_id: m.user_id.clone(),
+                username: data
+                    .users
+                    .iter()
+                    .find(|u| u.id == m.user_id)
+                    .map(|u| u.username.clone())
+                    .unwrap_or_default(),
+                role: m.role,
+            })
+            .collect();
+        Self {
+            id: workspace.id.clone(),
+            name: workspace.name.clone(),
+            created_at: workspace.created_at.clone(),
+            role: workspace.role_of(user_id).unwrap_or(Role::Viewer),
+            members,
+        }
+    }
+}
+
+fn validate_name(name: &str) -> Result<String, ApiError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ApiError::bad_request("Workspace name must not be empty"));
+    }
+    Ok(name.to_string())
+}
+
+// The workspace if the user is a member, with the user's role
+fn membership<'a>(data: &'a mut Data, id: &str, auth: &Auth) -> Result<(&'a mut TeamWorkspace, Role), ApiError> {
+    data.workspaces
+        .iter_mut()
+        .find(|w| w.id == id)
+        .and_then(|w| w.role_of(&auth.user_id).map(|role| (w, role)))
+        .ok_or_else(|| ApiError::not_found("Workspace not found"))
+}
+
+fn owned<'a>(data: &'a mut Data, id: &str, auth: &Auth) -> Result<&'a mut TeamWorkspace, ApiError> {
+    let (workspace, role) = membership(data, id, auth)?;
+    if role != Role::Owner {
+        return Err(ApiError::forbidden("Only owners can manage this workspace"));
+    }
+    Ok(workspace)
+}
+
+// Every workspace needs someone left to manage it
+fn keeps_an_owner(workspace: &TeamWorkspace, user_id: &str) -> Result<(), ApiError> {
+    if !workspace.members.iter().any(|m| m.role == Role::Owner && m.user_id != user_id) {
+        return Err(ApiError::conflict("A workspace must keep at least one owner"));
+    }
+    Ok(())
+}
+
+async fn list(auth: Auth, db: SharedDb) -> Result<warp::reply::Response, Rejection> {
+    let data = db.read().await;
+    let workspaces: Vec<WorkspaceInfo> = data
+        .workspaces
+        .iter()
+        .filter(|w| w.role_of(&auth.user_id).is_some())
+        .map(|w| WorkspaceInfo::from(&data, w, &auth.user_id))
+        .collect();
+    Ok(json_reply(&workspaces, StatusCode::OK))
+}
+
+async fn create(auth: Auth, db: SharedDb, new_workspace: NewWorkspace) -> Result<warp::reply::Response, Rejection> {
+    let name = validate_name(&new_workspace.name)?;
+    let info = db
+        .write(|data| {
+            let workspace = TeamWorkspace {
+                id: uuid::Uuid::new_v4().to_string(),
+                name,
+                created_at: timestamp(),
+                members: vec![Member {
+                    user_id: auth.user_id.clone(),
+                    role: Role::Owner,
+                }],
+            };
+            data.records.entry(workspace.id.clone()).or_default();
+            let info = WorkspaceInfo::from(data, &workspace, &auth.user_id);
+            data.workspaces.push(workspace);
+            Ok(info)
+        })
+        .await?;
+    Ok(json_reply(&info, StatusCode::CREATED))
+}
+
+async fn get(id: String, auth: Auth, db: SharedDb) -> Result<warp::reply::Response, Rejection> {
+    let mut data = db.read().await;
+    let workspace = membership(&mut data, &id, &auth)?.0.clone();
+    Ok(json_reply(&WorkspaceInfo::from(&data, &workspace, &auth.user_id), StatusCode::OK))
+}
+
+async fn rename(id: String, auth: Auth, db: SharedDb, renamed: NewWorkspace) -> Result<warp::reply::Response, Rejection> {
+    let name = validate_name(&renamed.name)?;
+    let info = db
+        .write(|data| {
+            let workspace = owned(data, &id, &auth)?;
+            workspace.name = name;
+            let workspace = workspace.clone();
+            Ok(WorkspaceInfo::from(data, &workspace, &auth.user_id))
+        })
+        .await?;
+    Ok(json_reply(&info, StatusCode::OK))
+}
+
+// Deleting a workspace deletes its records for every member
+async fn delete(id: String, auth: Auth, db: SharedDb) -> Result<warp::reply::Response, Rejection> {
+    db.write(|data| {
+        owned(data, &id, &auth)?;
+        data.workspaces.retain(|w| w.id != id);
+        data.records.remove(&id);
+        data.journals.remove(&id);
+        Ok(())
+    })
+    .await?;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+async fn add_member(id: String, auth: Auth, db: SharedDb, new_member: NewMember) -> Result<warp::reply::Response, Rejection> {
+    let info = db
+        .write(|data| {
+            let user_id = data
+                .users
+                .iter()
+                .find(|u| u.username.eq_ignore_ascii_case(new_member.username.trim()))
+                .map(|u| u.id.clone())
+                .ok_or_else(|| ApiError::not_found("User not found"))?;
+            let workspace = owned(data, &id, &auth)?;
+            if workspace.role_of(&user_id).is_some() {
+                return Err(ApiError::conflict("User is already a member"));
+            }
+            workspace.members.push(Member {
+                user_id,
+                role: new_member.role,
+            });
+            let workspace = workspace.clone();
+            Ok(WorkspaceInfo::from(data, &workspace, &auth.user_id))
+        })
+        .await?;
+    Ok(json_reply(&info, StatusCode::CREATED))
+}
+
+async fn change_role(
+    id: String,
+    user_id: String,
+    auth: Auth,
+    db: SharedDb,
+    change: RoleChange,
+) -> Result<warp::reply::Response, Rejection> {
+    let info = db
+        .write(|data| {
+            let workspace = owned(data, &id, &auth)?;
+            if change.role != Role::Owner {
+                keeps_an_owner(workspace, &user_id)?;
+            }
+            let member = workspace
+                .members
+                .iter_mut()
+                .find(|m| m.user_id == user_id)
+                .ok_or_else(|| ApiError::not_found("Member not found"))?;
+            member.role = change.role;
+            let workspace = workspace.clone();
+            Ok(WorkspaceInfo::from(data, &workspace, &auth.user_id))
+        })
+        .await?;
+    Ok(json_reply(&info, StatusCode::OK))
+}
+
+// Owners remove anyone; every member may leave on their own
+async fn remove_member(id: String, user_id: String, auth: Auth, db: SharedDb) -> Result<warp::reply::Response, Rejection> {
+    db.write(|data| {
+        let workspace = if user_id == auth.user_id {
+            membership(data, &id, &auth)?.0
+        } else {
+            owned(data, &id, &auth)?
+        };
+        if workspace.role_of(&user_id).is_none() {
+            return Err(ApiError::not_found("Member not found"));
+        }
+        keeps_an_owner(workspace, &user_id)?;
+        workspace.members.retain(|m| m.user_id != user_id);
+        Ok(())
+    })
+    .await?;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+// /api/workspaces and their members; their records are served by the records routes
+pub fn routes(db: SharedDb) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let workspaces = warp::path("api").and(warp::path("workspaces"));
+    let list = workspaces
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(authenticated(db.clone()))
+        .and(with_db(db.clone()))
+        .and_then(list);
+    let create = workspaces
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(authenticated(db.clone()))
+        .and(with_db(db.clone()))
+        .and(json_body())
+        .and_then(create);
+    let item = workspaces.and(warp::path::param::<String>());
+    let get = item
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(authenticated(db.clone()))
+        .and(with_db(db.clone()))
+        .and_then(get);
+    let rename = item
+        .and(warp::path::end())
+        .and(warp::put())
+        .and(authenticated(db.clone()))
+        .and(with_db(db.clone()))
+        .and(json_body())
+        .and_then(rename);
+    let delete = item
+        .and(warp::path::end())
+        .and(warp::delete())
+        .and(authenticated(db.clone()))
+        .and(with_db(db.clone()))
+        .and_then(delete);
+
+    let members = item.and(warp::path("members"));
+    let add_member = members
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(authenticated(db.clone()))
+        .and(with_db(db.clone()))
+        .and(json_body())
+        .and_then(add_member);
+    let member = members.and(warp::path::param::<String>()).and(warp::path::end());
+    let change_role = member
+        .and(warp::put())
+        .and(authenticated(db.clone()))
+        .and(with_db(db.clone()))
+        .and(json_body())
+        .and_then(change_role);
+    let remove_member = member
+        .and(warp::delete())
+        .and(authenticated(db.clone()))
+        .and(with_db(db))
+        .and_then(remove_member);
+
+    list.or(create)
+        .or(get)
+        .or(rename)
+        .or(delete)
+        .or(add_member)
+        .or(change_role)
+        .or(remove_member)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::server::db::{scratch, User};
+    use crate::server::status_of;
+    use super::*;
+
+    const ID: &str = "team";
+
+    // A workspace of ann (owner), ed (editor) and vic (viewer)
+    async fn team() -> SharedDb {
+        let db = scratch();
+        db.write(|data| {
+            data.workspaces.push(TeamWorkspace {
+                id: ID.to_string(),
+                name: "Team".to_string(),
+                created_at: timestamp(),
+                members: [("ann", Role::Owner), ("ed", Role::Editor), ("vic", Role::Viewer)]
+                    .into_iter()
+                    .map(|(user_id, role)| Member { user_id: user_id.to_string(), role })
+                    .collect(),
+            });
+            Ok(())
+        })
+        .await
+        .unwrap();
+        db
+    }
+
+    async fn role(db: &SharedDb, user_id: &str) -> Option<Role> {
+        db.read().await.workspaces.iter().find(|w| w.id == ID)?.role_of(user_id)
+    }
+
+    fn rename_to(name: &str) -> NewWorkspace {
+        NewWorkspace { name: name.to_string() }
+    }
+
+    fn make(role: Role) -> RoleChange {
+        RoleChange { role }
+    }
+
+    #[tokio::test]
+    async fn only_owners_manage_a_workspace() {
+        let db = team().await;
+        for user_id in ["ed", "vic"] {
+            let auth = || Auth::of(user_id);
+            assert_eq!(status_of(rename(ID.into(), auth(), db.clone(), rename_to("Mine")).await), StatusCode::FORBIDDEN);
+            assert_eq!(status_of(change_role(ID.into(), user_id.into(), auth(), db.clone(), make(Role::Owner)).await), StatusCode::FORBIDDEN);
+            assert_eq!(status_of(remove_member(ID.into(), "ann".into(), auth(), db.clone()).await), StatusCode::FORBIDDEN);
+            assert_eq!(status_of(delete(ID.into(), auth(), db.clone()).await), StatusCode::FORBIDDEN);
+            assert_eq!(status_of(get(ID.into(), auth(), db.clone()).await), StatusCode::OK);
+        }
+        assert_eq!(role(&db, "ed").await, Some(Role::Editor));
+        assert_eq!(role(&db, "vic").await, Some(Role::Viewer));
+
+        assert_eq!(status_of(change_role(ID.into(), "vic".into(), Auth::of("ann"), db.clone(), make(Role::Editor)).await), StatusCode::OK);
+        assert_eq!(role(&db, "vic").await, Some(Role::Editor));
+        assert_eq!(status_of(rename(ID.into(), Auth::of("ann"), db.clone(), rename_to("Ours")).await), StatusCode::OK);
+        assert_eq!(status_of(delete(ID.into(), Auth::of("ann"), db.clone()).await), StatusCode::NO_CONTENT);
+        assert!(db.read().await.workspaces.is_empty());
+    }
+
+    #[tokio::test]
+    async fn outsiders_cannot_tell_the_workspace_exists() {
+        let db = team().await;
+        assert_eq!(status_of(get(ID.into(), Auth::of("eve"), db.clone()).await), StatusCode::NOT_FOUND);
+        assert_eq!(status_of(get("missing".into(), Auth::of("eve"), db.clone()).await), StatusCode::NOT_FOUND);
+        assert_eq!(status_of(delete(ID.into(), Auth::of("eve"), db.clone()).await), StatusCode::NOT_FOUND);
+        assert_eq!(status_of(remove_member(ID.into(), "eve".into(), Auth::of("eve"), db.clone()).await), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn members_may_leave_but_the_last_owner_may_not() {
+        let db = team().await;
+        assert_eq!(status_of(remove_member(ID.into(), "vic".into(), Auth::of("vic"), db.clone()).await), StatusCode::NO_CONTENT);
+        assert_eq!(role(&db, "vic").await, None);
+
+        assert_eq!(status_of(remove_member(ID.into(), "ann".into(), Auth::of("ann"), db.clone()).await), StatusCode::CONFLICT);
+        assert_eq!(status_of(change_role(ID.into(), "ann".into(), Auth::of("ann"), db.clone(), make(Role::Editor)).await), StatusCode::CONFLICT);
+        assert_eq!(role(&db, "ann").await, Some(Role::Owner));
+
+        // With a second owner the first may step down
+        assert_eq!(status_of(change_role(ID.into(), "ed".into(), Auth::of("ann"), db.clone(), make(Role::Owner)).await), StatusCode::OK);
+        assert_eq!(status_of(change_role(ID.into(), "ann".into(), Auth::of("ann"), db.clone(), make(Role::Viewer)).await), StatusCode::OK);
+        assert_eq!(role(&db, "ann").await, Some(Role::Viewer));
+        assert_eq!(status_of(remove_member(ID.into(), "ed".into(), Auth::of("ed"), db.clone()).await), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn members_are_added_once_by_an_owner() {
+        let db = team().await;
+        db.write(|data| {
+            for username in ["ann", "ed", "vic", "sam"] {
+                data.users.push(User {
+                    id: username.to_string(),
+                    username: username.to_string(),
+                    password_hash: String::new(),
+                    created_at: timestamp(),
+                });
+            }
+            Ok(())
+        })
+        .await
+        .unwrap();
+        let new_member = |username: &str| NewMember { username: username.to_string(), role: Role::Editor };
+
+        assert_eq!(status_of(add_member(ID.into(), Auth::of("ed"), db.clone(), new_member("sam")).await), StatusCode::FORBIDDEN);
+        assert_eq!(status_of(add_member(ID.into(), Auth::of("ann"), db.clone(), new_member("nobody")).await), StatusCode::NOT_FOUND);
+        assert_eq!(status_of(add_member(ID.into(), Auth::of("ann"), db.clone(), new_member("vic")).await), StatusCode::CONFLICT);
+        assert_eq!(status_of(add_member(ID.into(), Auth::of("ann"), db.clone(), new_member(" SAM ")).await), StatusCode::CREATED);
+        assert_eq!(role(&db, "sam").await, Some(Role::Editor));
+    }
+}